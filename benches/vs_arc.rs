@@ -0,0 +1,177 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const THREADS: usize = 16;
+
+struct Payload(u64);
+
+impl splitrc::Notify for Payload {}
+
+fn clone_drop(c: &mut Criterion) {
+    let mut group = c.benchmark_group("clone_drop");
+    let arc = Arc::new(Payload(0));
+    group.bench_function("Arc", |b| b.iter(|| drop(black_box(arc.clone()))));
+    let (tx, rx) = splitrc::new(Payload(0));
+    group.bench_function("Tx", |b| b.iter(|| drop(black_box(tx.clone()))));
+    group.bench_function("Rx", |b| b.iter(|| drop(black_box(rx.clone()))));
+    let (tx, _rx) = splitrc::hybrid::new(Payload(0));
+    group.bench_function("hybrid::Tx", |b| b.iter(|| drop(black_box(tx.clone()))));
+    let (tx, _rx) = splitrc::weighted::new(Payload(0));
+    group.bench_function("weighted::Tx", |b| {
+        b.iter(|| splitrc::weighted::Tx::merge(&tx, black_box(tx.clone())))
+    });
+    group.finish();
+}
+
+// Runs `f(iters)` on THREADS threads at once and returns the
+// wall-clock time.
+fn on_threads<H: Clone + Send + 'static>(handle: &H, iters: u64, f: fn(&H, u64)) -> Duration {
+    let barrier = Arc::new(Barrier::new(THREADS + 1));
+    let threads: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let handle = handle.clone();
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                f(&handle, iters);
+            })
+        })
+        .collect();
+    barrier.wait();
+    let start = Instant::now();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    start.elapsed()
+}
+
+fn contended_clone(c: &mut Criterion) {
+    fn clones<H: Clone>(handle: &H, iters: u64) {
+        for _ in 0..iters {
+            drop(black_box(handle.clone()));
+        }
+    }
+
+    let mut group = c.benchmark_group("contended_clone");
+    let arc = Arc::new(Payload(0));
+    group.bench_function("Arc", |b| {
+        b.iter_custom(|iters| on_threads(&arc, iters, clones))
+    });
+    let (tx, _rx) = splitrc::new(Payload(0));
+    group.bench_function("Tx", |b| {
+        b.iter_custom(|iters| on_threads(&tx, iters, clones))
+    });
+    // Each thread clones its own hybrid handle, touching the atomic
+    // count only once.
+    group.bench_function("hybrid::Tx", |b| {
+        b.iter_custom(|iters| {
+            on_threads(&tx, iters, |tx, iters| {
+                clones(&splitrc::hybrid::Tx::from(tx.clone()), iters)
+            })
+        })
+    });
+    // Each thread splits its own weighted handle and merges the clone
+    // back, touching the atomic count only to borrow weight.
+    group.bench_function("weighted::Tx", |b| {
+        b.iter_custom(|iters| {
+            on_threads(&tx, iters, |tx, iters| {
+                let tx = splitrc::weighted::Tx::from(tx.clone());
+                for _ in 0..iters {
+                    splitrc::weighted::Tx::merge(&tx, black_box(tx.clone()));
+                }
+            })
+        })
+    });
+    group.finish();
+}
+
+fn deref(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deref");
+    let arc = Arc::new(Payload(1));
+    group.bench_function("Arc", |b| b.iter(|| black_box(&arc).0));
+    let (tx, _rx) = splitrc::new(Payload(1));
+    group.bench_function("Tx", |b| b.iter(|| black_box(&tx).0));
+    group.finish();
+}
+
+fn lifecycle(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lifecycle");
+    group.bench_function("Arc", |b| {
+        b.iter(|| {
+            let a = Arc::new(Payload(0));
+            let b = a.clone();
+            drop(black_box(a));
+            drop(black_box(b));
+        })
+    });
+    group.bench_function("splitrc", |b| {
+        b.iter(|| {
+            let (tx, rx) = splitrc::new(Payload(0));
+            drop(black_box(tx));
+            drop(black_box(rx));
+        })
+    });
+    group.finish();
+}
+
+// Drops the last two references from different threads at once, so
+// both take the final decrement path concurrently. Each iteration
+// races a batch of BATCH allocations.
+fn drop_race(c: &mut Criterion) {
+    const BATCH: usize = 1024;
+
+    fn race<A: Send + 'static, B: Send + 'static>(pairs: Vec<(A, B)>) -> Duration {
+        let (a, b): (Vec<A>, Vec<B>) = pairs.into_iter().unzip();
+        let barrier = Arc::new(Barrier::new(2));
+        let other = {
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                drop(b);
+            })
+        };
+        barrier.wait();
+        let start = Instant::now();
+        drop(a);
+        other.join().unwrap();
+        start.elapsed()
+    }
+
+    let mut group = c.benchmark_group("drop_race");
+    group.bench_function("Arc", |b| {
+        b.iter_custom(|iters| {
+            (0..iters)
+                .map(|_| {
+                    race(
+                        (0..BATCH)
+                            .map(|_| {
+                                let a = Arc::new(Payload(0));
+                                (a.clone(), a)
+                            })
+                            .collect(),
+                    )
+                })
+                .sum()
+        })
+    });
+    group.bench_function("splitrc", |b| {
+        b.iter_custom(|iters| {
+            (0..iters)
+                .map(|_| race((0..BATCH).map(|_| splitrc::new(Payload(0))).collect()))
+                .sum()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    clone_drop,
+    contended_clone,
+    deref,
+    lifecycle,
+    drop_race
+);
+criterion_main!(benches);
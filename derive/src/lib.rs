@@ -0,0 +1,114 @@
+//! Derive macro for [splitrc](https://crates.io/crates/splitrc)'s
+//! `Notify` trait. Use it through splitrc's `derive` feature.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields, Index, Member, Type};
+
+/// Implements `splitrc::Notify`.
+///
+/// Without attributes, every notification is ignored. If one field is
+/// marked `#[notify]`, notifications are forwarded to it instead.
+///
+/// Only the unpinned methods are forwarded, because the field is not
+/// structurally pinned.
+#[proc_macro_derive(Notify, attributes(notify))]
+pub fn derive_notify(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let target = notify_field(&input)?;
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    let body = match target {
+        None => quote! {},
+        Some((member, ty)) => {
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote!(#ty: ::splitrc::Notify));
+            forward(&member)
+        }
+    };
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::splitrc::Notify for #name #ty_generics #where_clause {
+            #body
+        }
+    })
+}
+
+/// Finds the field marked `#[notify]`, if any.
+fn notify_field(input: &DeriveInput) -> syn::Result<Option<(Member, Type)>> {
+    let mut found = None;
+    let fields: Vec<&Fields> = match &input.data {
+        Data::Struct(data) => vec![&data.fields],
+        Data::Enum(data) => data.variants.iter().map(|v| &v.fields).collect(),
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "Notify cannot be derived for unions",
+            ))
+        }
+    };
+    let is_struct = matches!(input.data, Data::Struct(_));
+    for (index, field) in fields.into_iter().flatten().enumerate() {
+        for attr in &field.attrs {
+            if !attr.path().is_ident("notify") {
+                continue;
+            }
+            attr.meta.require_path_only()?;
+            if !is_struct {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    "#[notify] is only supported on struct fields",
+                ));
+            }
+            if found.is_some() {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    "only one field may be marked #[notify]",
+                ));
+            }
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(index)),
+            };
+            found = Some((member, field.ty.clone()));
+        }
+    }
+    Ok(found)
+}
+
+fn forward(member: &Member) -> TokenStream2 {
+    let methods = [
+        quote!(last_tx_did_drop),
+        quote!(last_rx_did_drop),
+        quote!(will_deallocate),
+    ];
+    #[cfg(feature = "observer")]
+    let methods = {
+        let mut methods = methods.to_vec();
+        methods.extend([
+            quote!(tx_did_clone),
+            quote!(rx_did_clone),
+            quote!(tx_did_drop_one),
+            quote!(rx_did_drop_one),
+        ]);
+        methods
+    };
+    let methods = methods.iter();
+    quote! {
+        #(
+            fn #methods(&self) {
+                ::splitrc::Notify::#methods(&self.#member)
+            }
+        )*
+    }
+}
@@ -0,0 +1,10 @@
+#![no_main]
+
+// Run with `cargo fuzz run ops`.
+
+#[path = "../../tests/ops/mod.rs"]
+mod ops;
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+    ops::run(&ops::Op::decode_all(data));
+});
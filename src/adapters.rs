@@ -0,0 +1,128 @@
+//! [Notify] implementations for payloads that don't implement it.
+
+use crate::{new, AtomicBool, Notify, Rx, Tx};
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::Ordering;
+
+/// Wraps a payload that does not need notifications.
+///
+/// ```
+/// let (tx, rx) = splitrc::new(splitrc::Unnotified(vec![1, 2, 3]));
+/// assert_eq!(3, tx.len());
+/// # drop(rx);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Unnotified<T>(pub T);
+
+impl<T> Notify for Unnotified<T> {}
+
+impl<T> Deref for Unnotified<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Unnotified<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+// A callback that runs at most once, even if Notify methods are
+// called directly.
+struct Once<F> {
+    called: AtomicBool,
+    f: UnsafeCell<Option<F>>,
+}
+
+// SAFETY: Only the thread that sets `called` accesses `f`.
+unsafe impl<F: Send> Sync for Once<F> {}
+
+impl<F> Once<F> {
+    fn new(f: F) -> Self {
+        Self {
+            called: AtomicBool::new(false),
+            f: UnsafeCell::new(Some(f)),
+        }
+    }
+
+    fn take(&self) -> Option<F> {
+        if self.called.swap(true, Ordering::Acquire) {
+            None
+        } else {
+            // SAFETY: We are the only thread to set `called`.
+            unsafe { (*self.f.get()).take() }
+        }
+    }
+}
+
+/// A payload with closures called when the last [Tx] or last [Rx] is
+/// dropped. Created by [new_with_callbacks].
+pub struct Callbacks<T, F, G> {
+    data: T,
+    on_last_tx_drop: Once<F>,
+    on_last_rx_drop: Once<G>,
+}
+
+impl<T, F: FnOnce(&T), G: FnOnce(&T)> Notify for Callbacks<T, F, G> {
+    fn last_tx_did_drop(&self) {
+        if let Some(f) = self.on_last_tx_drop.take() {
+            f(&self.data)
+        }
+    }
+
+    fn last_rx_did_drop(&self) {
+        if let Some(g) = self.on_last_rx_drop.take() {
+            g(&self.data)
+        }
+    }
+}
+
+impl<T, F, G> Deref for Callbacks<T, F, G> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data
+    }
+}
+
+impl<T: fmt::Debug, F, G> fmt::Debug for Callbacks<T, F, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.data, f)
+    }
+}
+
+/// Allocates a pointer holding `data` and returns a pair of
+/// references, without requiring `T` to implement [Notify].
+///
+/// `on_last_tx_drop` and `on_last_rx_drop` follow the same rules as
+/// [Notify::last_tx_did_drop] and [Notify::last_rx_did_drop].
+///
+/// ```
+/// use std::sync::atomic::{AtomicBool, Ordering};
+///
+/// let (tx, rx) = splitrc::new_with_callbacks(
+///     AtomicBool::new(false),
+///     |closed| closed.store(true, Ordering::Release),
+///     |_| {},
+/// );
+/// drop(tx);
+/// assert!(rx.load(Ordering::Acquire));
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn new_with_callbacks<T, F: FnOnce(&T), G: FnOnce(&T)>(
+    data: T,
+    on_last_tx_drop: F,
+    on_last_rx_drop: G,
+) -> (Tx<Callbacks<T, F, G>>, Rx<Callbacks<T, F, G>>) {
+    new(Callbacks {
+        data,
+        on_last_tx_drop: Once::new(on_last_tx_drop),
+        on_last_rx_drop: Once::new(on_last_rx_drop),
+    })
+}
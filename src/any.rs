@@ -0,0 +1,75 @@
+//! Recovering concrete handle types from trait objects.
+
+use crate::{Notify, Rx, Tx};
+use core::any::Any;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+
+/// A [Notify] payload that can be downcast.
+///
+/// Handles to `dyn AnyNotify` forward notifications to the concrete
+/// payload, unlike `dyn Any`, which would lose them when unsized.
+/// Implemented for every `'static` payload.
+///
+/// ```
+/// use splitrc::{AnyNotify, Tx};
+///
+/// # struct MyValue {}
+/// # impl splitrc::Notify for MyValue {}
+/// let (tx, rx) = splitrc::new(MyValue {});
+/// let tx: Tx<dyn AnyNotify + Send + Sync> =
+///     unsafe { Tx::unsize(tx, |p| p as *const (dyn AnyNotify + Send + Sync)) };
+/// let tx: Tx<MyValue> = Tx::downcast(tx).unwrap();
+/// # drop(rx);
+/// ```
+pub trait AnyNotify: Any + Notify {
+    /// Returns the payload as [Any].
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Any + Notify> AnyNotify for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl fmt::Debug for dyn AnyNotify + Send + Sync {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AnyNotify { .. }")
+    }
+}
+
+impl Tx<dyn AnyNotify + Send + Sync> {
+    /// Attempts to downcast the handle to a concrete payload type.
+    ///
+    /// Returns the handle unchanged if the payload is not a `T`.
+    pub fn downcast<T: AnyNotify + Send + Sync>(this: Self) -> Result<Tx<T>, Self> {
+        if this.as_any().is::<T>() {
+            let this = ManuallyDrop::new(this);
+            Ok(Tx {
+                ptr: this.ptr.cast(),
+                phantom: PhantomData,
+            })
+        } else {
+            Err(this)
+        }
+    }
+}
+
+impl Rx<dyn AnyNotify + Send + Sync> {
+    /// Attempts to downcast the handle to a concrete payload type.
+    ///
+    /// Returns the handle unchanged if the payload is not a `T`.
+    pub fn downcast<T: AnyNotify + Send + Sync>(this: Self) -> Result<Rx<T>, Self> {
+        if this.as_any().is::<T>() {
+            let this = ManuallyDrop::new(this);
+            Ok(Rx {
+                ptr: this.ptr.cast(),
+                phantom: PhantomData,
+            })
+        } else {
+            Err(this)
+        }
+    }
+}
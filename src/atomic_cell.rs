@@ -0,0 +1,179 @@
+//! Optional handles stored in an atomic pointer.
+
+use crate::{Notify, Rx, Tx};
+use core::fmt;
+use core::marker::PhantomData;
+use core::sync::atomic::Ordering;
+
+#[cfg(loom)]
+use loom::sync::atomic::AtomicPtr;
+
+#[cfg(not(loom))]
+use core::sync::atomic::AtomicPtr;
+
+/// An `Option<Tx<T>>` that can be replaced without a lock.
+///
+/// Holds one tx reference while occupied. Only ownership moves in
+/// and out; there is no way to clone the stored handle in place.
+///
+/// ```
+/// struct Config(u32);
+/// impl splitrc::Notify for Config {}
+///
+/// let (tx, rx) = splitrc::new(Config(1));
+/// let cell = splitrc::AtomicTx::new(Some(tx));
+/// let (next, _next_rx) = splitrc::new(Config(2));
+/// let old = cell.swap(Some(next)).unwrap();
+/// assert_eq!(1, old.0);
+/// drop(old);
+/// assert_eq!(0, splitrc::Rx::tx_count(&rx));
+/// ```
+pub struct AtomicTx<T: Notify> {
+    ptr: AtomicPtr<T>,
+    phantom: PhantomData<Tx<T>>,
+}
+
+impl<T: Notify> AtomicTx<T> {
+    /// Creates a cell holding `tx`.
+    pub fn new(tx: Option<Tx<T>>) -> Self {
+        AtomicTx {
+            ptr: AtomicPtr::new(Tx::option_into_raw(tx) as *mut T),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns true if the cell held a handle at the time of the
+    /// check.
+    pub fn is_some(&self) -> bool {
+        !self.ptr.load(Ordering::Relaxed).is_null()
+    }
+
+    /// Replaces the stored handle with `tx`, dropping the old one.
+    pub fn store(&self, tx: Option<Tx<T>>) {
+        drop(self.swap(tx))
+    }
+
+    /// Replaces the stored handle with `tx` and returns the old one.
+    pub fn swap(&self, tx: Option<Tx<T>>) -> Option<Tx<T>> {
+        // Release publishes the new payload, and Acquire observes the
+        // old one before taking ownership.
+        let old = self
+            .ptr
+            .swap(Tx::option_into_raw(tx) as *mut T, Ordering::AcqRel);
+        // SAFETY: Every non-null pointer in the cell came from
+        // Tx::option_into_raw, and the swap gave us sole ownership.
+        unsafe { Tx::option_from_raw(old) }
+    }
+
+    /// Removes and returns the stored handle, leaving the cell empty.
+    pub fn take(&self) -> Option<Tx<T>> {
+        self.swap(None)
+    }
+
+    /// Consumes the cell, returning the stored handle.
+    pub fn into_inner(self) -> Option<Tx<T>> {
+        self.take()
+    }
+}
+
+impl<T: Notify> Default for AtomicTx<T> {
+    fn default() -> Self {
+        AtomicTx::new(None)
+    }
+}
+
+impl<T: Notify> From<Tx<T>> for AtomicTx<T> {
+    fn from(tx: Tx<T>) -> Self {
+        AtomicTx::new(Some(tx))
+    }
+}
+
+impl<T: Notify> Drop for AtomicTx<T> {
+    fn drop(&mut self) {
+        drop(self.take())
+    }
+}
+
+impl<T: Notify> fmt::Debug for AtomicTx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The payload may be replaced and freed while formatting, so
+        // only report occupancy.
+        f.debug_struct("AtomicTx")
+            .field("is_some", &self.is_some())
+            .finish()
+    }
+}
+
+/// An `Option<Rx<T>>` that can be replaced without a lock. See
+/// [AtomicTx].
+pub struct AtomicRx<T: Notify> {
+    ptr: AtomicPtr<T>,
+    phantom: PhantomData<Rx<T>>,
+}
+
+impl<T: Notify> AtomicRx<T> {
+    /// Creates a cell holding `rx`.
+    pub fn new(rx: Option<Rx<T>>) -> Self {
+        AtomicRx {
+            ptr: AtomicPtr::new(Rx::option_into_raw(rx) as *mut T),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns true if the cell held a handle at the time of the
+    /// check.
+    pub fn is_some(&self) -> bool {
+        !self.ptr.load(Ordering::Relaxed).is_null()
+    }
+
+    /// Replaces the stored handle with `rx`, dropping the old one.
+    pub fn store(&self, rx: Option<Rx<T>>) {
+        drop(self.swap(rx))
+    }
+
+    /// Replaces the stored handle with `rx` and returns the old one.
+    pub fn swap(&self, rx: Option<Rx<T>>) -> Option<Rx<T>> {
+        // See AtomicTx::swap.
+        let old = self
+            .ptr
+            .swap(Rx::option_into_raw(rx) as *mut T, Ordering::AcqRel);
+        // SAFETY: See AtomicTx::swap.
+        unsafe { Rx::option_from_raw(old) }
+    }
+
+    /// Removes and returns the stored handle, leaving the cell empty.
+    pub fn take(&self) -> Option<Rx<T>> {
+        self.swap(None)
+    }
+
+    /// Consumes the cell, returning the stored handle.
+    pub fn into_inner(self) -> Option<Rx<T>> {
+        self.take()
+    }
+}
+
+impl<T: Notify> Default for AtomicRx<T> {
+    fn default() -> Self {
+        AtomicRx::new(None)
+    }
+}
+
+impl<T: Notify> From<Rx<T>> for AtomicRx<T> {
+    fn from(rx: Rx<T>) -> Self {
+        AtomicRx::new(Some(rx))
+    }
+}
+
+impl<T: Notify> Drop for AtomicRx<T> {
+    fn drop(&mut self) {
+        drop(self.take())
+    }
+}
+
+impl<T: Notify> fmt::Debug for AtomicRx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AtomicRx")
+            .field("is_some", &self.is_some())
+            .finish()
+    }
+}
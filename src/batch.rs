@@ -0,0 +1,178 @@
+//! Cloning and dropping many handles with few atomic updates.
+
+use crate::{release_rx_by, release_tx_by, Allocator, Inner, Notify, Rx, Tx, MAX_BATCH};
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+
+/// Takes up to [MAX_BATCH] references from `remaining` at a time.
+fn next_chunk(remaining: usize) -> u32 {
+    remaining.min(MAX_BATCH as usize) as u32
+}
+
+/// Yields tx references that have already been counted. Releases the
+/// rest when dropped early.
+pub(crate) struct TxBatch<T: Notify + ?Sized, A: Allocator> {
+    ptr: NonNull<Inner<T, crate::Shared, A>>,
+    remaining: usize,
+}
+
+impl<T: Notify + ?Sized, A: Allocator> TxBatch<T, A> {
+    pub(crate) fn clone_from(tx: &Tx<T, A>, n: usize) -> Self {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { tx.ptr.as_ref() };
+        // If counting panics, dropping the batch releases what was
+        // counted.
+        let mut batch = TxBatch {
+            ptr: tx.ptr,
+            remaining: 0,
+        };
+        while batch.remaining < n {
+            let chunk = next_chunk(n - batch.remaining);
+            inner.count.inc_tx_by(chunk);
+            batch.remaining += chunk as usize;
+        }
+        batch
+    }
+}
+
+impl<T: Notify + ?Sized, A: Allocator> Iterator for TxBatch<T, A> {
+    type Item = Tx<T, A>;
+
+    fn next(&mut self) -> Option<Tx<T, A>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        lifecycle_event!(self.ptr, "clone tx");
+        #[cfg(feature = "observer")]
+        // SAFETY: We do not create a &mut to Inner.
+        unsafe { self.ptr.as_ref() }.data.tx_did_clone();
+        Some(Tx {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T: Notify + ?Sized, A: Allocator> ExactSizeIterator for TxBatch<T, A> {}
+
+impl<T: Notify + ?Sized, A: Allocator> Drop for TxBatch<T, A> {
+    fn drop(&mut self) {
+        while self.remaining != 0 {
+            let chunk = next_chunk(self.remaining);
+            self.remaining -= chunk as usize;
+            release_tx_by(self.ptr, chunk);
+        }
+    }
+}
+
+/// See [TxBatch].
+pub(crate) struct RxBatch<T: Notify + ?Sized, A: Allocator> {
+    ptr: NonNull<Inner<T, crate::Shared, A>>,
+    remaining: usize,
+}
+
+impl<T: Notify + ?Sized, A: Allocator> RxBatch<T, A> {
+    pub(crate) fn clone_from(rx: &Rx<T, A>, n: usize) -> Self {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { rx.ptr.as_ref() };
+        let mut batch = RxBatch {
+            ptr: rx.ptr,
+            remaining: 0,
+        };
+        while batch.remaining < n {
+            let chunk = next_chunk(n - batch.remaining);
+            inner.count.inc_rx_by(chunk);
+            batch.remaining += chunk as usize;
+        }
+        batch
+    }
+}
+
+impl<T: Notify + ?Sized, A: Allocator> Iterator for RxBatch<T, A> {
+    type Item = Rx<T, A>;
+
+    fn next(&mut self) -> Option<Rx<T, A>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        lifecycle_event!(self.ptr, "clone rx");
+        #[cfg(feature = "observer")]
+        // SAFETY: We do not create a &mut to Inner.
+        unsafe { self.ptr.as_ref() }.data.rx_did_clone();
+        Some(Rx {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T: Notify + ?Sized, A: Allocator> ExactSizeIterator for RxBatch<T, A> {}
+
+impl<T: Notify + ?Sized, A: Allocator> Drop for RxBatch<T, A> {
+    fn drop(&mut self) {
+        while self.remaining != 0 {
+            let chunk = next_chunk(self.remaining);
+            self.remaining -= chunk as usize;
+            release_rx_by(self.ptr, chunk);
+        }
+    }
+}
+
+/// Drops `handles`, releasing each run of handles to the same
+/// allocation with one update per [MAX_BATCH] references.
+pub(crate) fn drop_many_tx<T: Notify + ?Sized, A: Allocator>(
+    handles: impl IntoIterator<Item = Tx<T, A>>,
+) {
+    let mut run: Option<TxBatch<T, A>> = None;
+    for tx in handles {
+        let tx = ManuallyDrop::new(tx);
+        #[cfg(feature = "observer")]
+        // SAFETY: We do not create a &mut to Inner.
+        unsafe { tx.ptr.as_ref() }.data.tx_did_drop_one();
+        lifecycle_event!(tx.ptr, "drop tx");
+        match &mut run {
+            Some(batch) if batch.ptr.cast::<u8>() == tx.ptr.cast::<u8>() => batch.remaining += 1,
+            // Dropping the previous run releases it.
+            _ => {
+                run = Some(TxBatch {
+                    ptr: tx.ptr,
+                    remaining: 1,
+                })
+            }
+        }
+    }
+}
+
+/// See [drop_many_tx].
+pub(crate) fn drop_many_rx<T: Notify + ?Sized, A: Allocator>(
+    handles: impl IntoIterator<Item = Rx<T, A>>,
+) {
+    let mut run: Option<RxBatch<T, A>> = None;
+    for rx in handles {
+        let rx = ManuallyDrop::new(rx);
+        #[cfg(feature = "observer")]
+        // SAFETY: We do not create a &mut to Inner.
+        unsafe { rx.ptr.as_ref() }.data.rx_did_drop_one();
+        lifecycle_event!(rx.ptr, "drop rx");
+        match &mut run {
+            Some(batch) if batch.ptr.cast::<u8>() == rx.ptr.cast::<u8>() => batch.remaining += 1,
+            _ => {
+                run = Some(RxBatch {
+                    ptr: rx.ptr,
+                    remaining: 1,
+                })
+            }
+        }
+    }
+}
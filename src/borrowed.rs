@@ -0,0 +1,135 @@
+//! Handles borrowed without touching the reference count.
+
+use crate::{Inner, Notify, Rx, Tx};
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::ptr::NonNull;
+
+/// A borrowed [Tx] that can be copied freely without touching the
+/// reference count. Created by [Tx::borrow_handle].
+///
+/// Pass it down a call stack instead of `&Tx<T>` to save an
+/// indirection, and call [TxBorrow::to_tx] only where an owned
+/// handle is needed.
+pub struct TxBorrow<'a, T: Notify + ?Sized> {
+    ptr: NonNull<Inner<T>>,
+    phantom: PhantomData<&'a Tx<T>>,
+}
+
+unsafe impl<T: Sync + Send + Notify + ?Sized> Send for TxBorrow<'_, T> {}
+unsafe impl<T: Sync + Send + Notify + ?Sized> Sync for TxBorrow<'_, T> {}
+
+impl<T: Notify + ?Sized> Tx<T> {
+    /// Borrows this handle without incrementing the tx count.
+    pub fn borrow_handle(this: &Self) -> TxBorrow<'_, T> {
+        TxBorrow {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: Notify + ?Sized> TxBorrow<'a, T> {
+    /// Returns a new [Tx] to the same allocation.
+    pub fn to_tx(this: Self) -> Tx<T> {
+        // SAFETY: We do not create a &mut to Inner.
+        unsafe { this.ptr.as_ref() }.inc_tx();
+        Tx {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the payload for the whole borrow, not just the
+    /// lifetime of this copy.
+    pub fn get(this: Self) -> &'a T {
+        // SAFETY: The borrowed Tx keeps the payload alive for 'a.
+        &unsafe { this.ptr.as_ref() }.data
+    }
+}
+
+impl<T: Notify + ?Sized> Clone for TxBorrow<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Notify + ?Sized> Copy for TxBorrow<'_, T> {}
+
+impl<T: Notify + ?Sized> Deref for TxBorrow<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        TxBorrow::get(*self)
+    }
+}
+
+impl<T: Notify + fmt::Debug + ?Sized> fmt::Debug for TxBorrow<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A borrowed [Rx] that can be copied freely without touching the
+/// reference count. Created by [Rx::borrow_handle].
+///
+/// See [TxBorrow].
+pub struct RxBorrow<'a, T: Notify + ?Sized> {
+    ptr: NonNull<Inner<T>>,
+    phantom: PhantomData<&'a Rx<T>>,
+}
+
+unsafe impl<T: Sync + Send + Notify + ?Sized> Send for RxBorrow<'_, T> {}
+unsafe impl<T: Sync + Send + Notify + ?Sized> Sync for RxBorrow<'_, T> {}
+
+impl<T: Notify + ?Sized> Rx<T> {
+    /// Borrows this handle without incrementing the rx count.
+    pub fn borrow_handle(this: &Self) -> RxBorrow<'_, T> {
+        RxBorrow {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: Notify + ?Sized> RxBorrow<'a, T> {
+    /// Returns a new [Rx] to the same allocation.
+    pub fn to_rx(this: Self) -> Rx<T> {
+        // SAFETY: We do not create a &mut to Inner.
+        unsafe { this.ptr.as_ref() }.inc_rx();
+        Rx {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the payload for the whole borrow, not just the
+    /// lifetime of this copy.
+    pub fn get(this: Self) -> &'a T {
+        // SAFETY: The borrowed Rx keeps the payload alive for 'a.
+        &unsafe { this.ptr.as_ref() }.data
+    }
+}
+
+impl<T: Notify + ?Sized> Clone for RxBorrow<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Notify + ?Sized> Copy for RxBorrow<'_, T> {}
+
+impl<T: Notify + ?Sized> Deref for RxBorrow<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        RxBorrow::get(*self)
+    }
+}
+
+impl<T: Notify + fmt::Debug + ?Sized> fmt::Debug for RxBorrow<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
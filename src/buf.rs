@@ -0,0 +1,278 @@
+//! A byte buffer that the tx half appends to and the rx half reads
+//! without copying.
+//!
+//! The buffer's capacity is fixed when it is created, so appending
+//! never moves bytes that readers can see. A [Bytes] is an owned,
+//! zero-copy slice that keeps the allocation alive as an [Rx]. Once
+//! every reader and every [Bytes] is gone, the rx half is notified,
+//! and the writer can reclaim the storage with [Tx::try_reclaim].
+//!
+//! ```
+//! use splitrc::{buf, Rx, Tx};
+//!
+//! let (tx, rx) = buf::with_capacity(16);
+//! Tx::append(&tx, b"hello ");
+//! let hello = Rx::bytes(&rx);
+//! Tx::append(&tx, b"world");
+//! assert_eq!(b"hello ", &hello[..]);
+//! assert_eq!(b"world", &Rx::bytes(&rx).slice(6..)[..]);
+//!
+//! drop((rx, hello));
+//! let storage = Tx::try_reclaim(tx).unwrap();
+//! assert_eq!(b"hello world", &storage[..]);
+//! ```
+
+use crate::{AtomicUsize, Notify, Rx, Tx};
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ops::{Bound, Deref, RangeBounds};
+use core::slice;
+use core::sync::atomic::Ordering;
+
+#[cfg(loom)]
+use loom::sync::Mutex;
+
+#[cfg(not(loom))]
+use std::sync::Mutex;
+
+// The storage of a Vec<u8>. Bytes before `len` are initialized and
+// never written again.
+struct Storage {
+    ptr: *mut u8,
+    cap: usize,
+    len: AtomicUsize,
+}
+
+impl Storage {
+    fn new(vec: Vec<u8>) -> Self {
+        let mut vec = ManuallyDrop::new(vec);
+        Storage {
+            ptr: vec.as_mut_ptr(),
+            cap: vec.capacity(),
+            len: AtomicUsize::new(vec.len()),
+        }
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        let len = self.len.load(Ordering::Acquire);
+        // SAFETY: Bytes before len are initialized and never written
+        // again.
+        unsafe { slice::from_raw_parts(self.ptr, len) }
+    }
+
+    fn into_vec(self) -> Vec<u8> {
+        let this = ManuallyDrop::new(self);
+        let len = this.len.load(Ordering::Relaxed);
+        // SAFETY: The parts came from a Vec.
+        unsafe { Vec::from_raw_parts(this.ptr, len, this.cap) }
+    }
+}
+
+impl Drop for Storage {
+    fn drop(&mut self) {
+        let len = self.len.load(Ordering::Relaxed);
+        // SAFETY: The parts came from a Vec.
+        drop(unsafe { Vec::from_raw_parts(self.ptr, len, self.cap) })
+    }
+}
+
+/// A fixed-capacity byte buffer with append-only writes, forwarding
+/// notifications to `N`.
+///
+/// The [Tx] half appends with [Tx::append], and the [Rx] half takes
+/// slices with [Rx::bytes]. Both halves may read everything appended
+/// so far.
+pub struct SharedBuf<N = ()> {
+    storage: Storage,
+    // Serializes appends from cloned tx handles.
+    append: Mutex<()>,
+    notify: N,
+}
+
+// SAFETY: Readers only see initialized bytes that are never written
+// again, and appends are serialized.
+unsafe impl<N: Send> Send for SharedBuf<N> {}
+unsafe impl<N: Sync> Sync for SharedBuf<N> {}
+
+impl<N> SharedBuf<N> {
+    /// Allocates an empty buffer that holds up to `capacity` bytes.
+    pub fn with_capacity(capacity: usize, notify: N) -> Self {
+        SharedBuf::from_vec(Vec::with_capacity(capacity), notify)
+    }
+
+    /// Reuses `vec`'s allocation. Its contents are readable, and its
+    /// spare capacity is available to appends.
+    pub fn from_vec(vec: Vec<u8>, notify: N) -> Self {
+        SharedBuf {
+            storage: Storage::new(vec),
+            append: Mutex::new(()),
+            notify,
+        }
+    }
+
+    /// Returns every byte appended so far.
+    pub fn as_slice(&self) -> &[u8] {
+        self.storage.as_slice()
+    }
+
+    /// Returns the number of bytes appended so far.
+    pub fn len(&self) -> usize {
+        self.storage.len.load(Ordering::Acquire)
+    }
+
+    /// Returns true if nothing has been appended.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of bytes the buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.storage.cap
+    }
+
+    /// Returns the notification target.
+    pub fn notify(&self) -> &N {
+        &self.notify
+    }
+
+    /// Returns the storage, with every appended byte.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.storage.into_vec()
+    }
+
+    fn append(&self, bytes: &[u8]) -> usize {
+        let _guard = self.append.lock().unwrap_or_else(|e| e.into_inner());
+        // Only appends write len, and they hold the lock.
+        let len = self.storage.len.load(Ordering::Relaxed);
+        let n = bytes.len().min(self.storage.cap - len);
+        // SAFETY: Bytes from len to cap are not visible to readers,
+        // and the lock excludes other appends.
+        unsafe {
+            core::ptr::copy_nonoverlapping(bytes.as_ptr(), self.storage.ptr.add(len), n);
+        }
+        self.storage.len.store(len + n, Ordering::Release);
+        n
+    }
+}
+
+impl<N: Notify> Notify for SharedBuf<N> {
+    forward_pinned!(notify);
+}
+
+impl<N: fmt::Debug> fmt::Debug for SharedBuf<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedBuf")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .field("notify", &self.notify)
+            .finish()
+    }
+}
+
+/// Allocates an empty buffer that holds up to `capacity` bytes and
+/// returns a pair of references.
+pub fn with_capacity(capacity: usize) -> (Tx<SharedBuf>, Rx<SharedBuf>) {
+    crate::new(SharedBuf::with_capacity(capacity, ()))
+}
+
+impl<N: Notify> Tx<SharedBuf<N>> {
+    /// Appends as much of `bytes` as fits, returning the number of
+    /// bytes appended.
+    pub fn append(this: &Self, bytes: &[u8]) -> usize {
+        SharedBuf::append(this, bytes)
+    }
+
+    /// Returns the storage if this is the last reference, with every
+    /// reader and [Bytes] dropped. Otherwise, returns the handle
+    /// unchanged.
+    ///
+    /// Pass the storage to [SharedBuf::from_vec] after clearing it to
+    /// reuse the allocation.
+    pub fn try_reclaim(this: Self) -> Result<Vec<u8>, Self> {
+        Tx::try_unwrap(this).map(SharedBuf::into_vec)
+    }
+}
+
+impl<N: Notify> Rx<SharedBuf<N>> {
+    /// Returns every byte appended so far, without copying.
+    pub fn bytes(this: &Self) -> Bytes<N> {
+        Bytes {
+            end: SharedBuf::len(this),
+            rx: this.clone(),
+            start: 0,
+        }
+    }
+}
+
+/// A slice of a [SharedBuf] that keeps it alive as an [Rx].
+pub struct Bytes<N: Notify = ()> {
+    rx: Rx<SharedBuf<N>>,
+    start: usize,
+    end: usize,
+}
+
+impl<N: Notify> Bytes<N> {
+    /// Returns a subslice sharing the same buffer.
+    ///
+    /// Panics if `range` is out of bounds.
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> Self {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.checked_add(1).expect("out of range"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n.checked_add(1).expect("out of range"),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => self.len(),
+        };
+        assert!(
+            start <= end && end <= self.len(),
+            "range {}..{} out of bounds for length {}",
+            start,
+            end,
+            self.len()
+        );
+        Bytes {
+            rx: self.rx.clone(),
+            start: self.start + start,
+            end: self.start + end,
+        }
+    }
+
+    /// Returns the reader this slice holds.
+    pub fn rx(&self) -> &Rx<SharedBuf<N>> {
+        &self.rx
+    }
+}
+
+impl<N: Notify> Clone for Bytes<N> {
+    fn clone(&self) -> Self {
+        Bytes {
+            rx: self.rx.clone(),
+            start: self.start,
+            end: self.end,
+        }
+    }
+}
+
+impl<N: Notify> Deref for Bytes<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.rx.as_slice()[self.start..self.end]
+    }
+}
+
+impl<N: Notify> AsRef<[u8]> for Bytes<N> {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<N: Notify> fmt::Debug for Bytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
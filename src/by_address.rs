@@ -0,0 +1,127 @@
+//! Comparing handles by allocation identity.
+
+use crate::{Notify, Rx, Tx};
+use core::cmp;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::num::NonZeroUsize;
+use core::ops::Deref;
+
+/// Identifies an allocation, for keying registries by object
+/// identity without holding a handle or raw pointer. Returned by
+/// [Tx::id], [Rx::id], and their weak counterparts.
+///
+/// Every handle to an allocation, of either half, has the same id.
+/// Ids are unique among live allocations, but a freed allocation's
+/// address, and so its id, may be reused.
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// # struct MyValue {}
+/// # impl splitrc::Notify for MyValue {}
+/// let (tx, rx) = splitrc::new(MyValue {});
+/// let mut names = HashMap::new();
+/// names.insert(splitrc::Tx::id(&tx), "worker");
+/// assert_eq!(Some(&"worker"), names.get(&splitrc::Rx::id(&rx)));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SplitrcId(NonZeroUsize);
+
+impl SplitrcId {
+    pub(crate) fn from_addr(addr: usize) -> SplitrcId {
+        // Payload addresses are never null.
+        SplitrcId(NonZeroUsize::new(addr).unwrap())
+    }
+
+    /// Returns the allocation's payload address, as from [Tx::addr].
+    pub fn addr(self) -> usize {
+        self.0.get()
+    }
+}
+
+impl fmt::Debug for SplitrcId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SplitrcId({:#x})", self.0)
+    }
+}
+
+/// Wraps a [Tx] or [Rx] so that equality, ordering, and hashing use
+/// the allocation's address instead of the payload.
+///
+/// ```
+/// use splitrc::ByAddress;
+/// use std::collections::HashSet;
+///
+/// # #[derive(PartialEq)]
+/// # struct MyValue {}
+/// # impl splitrc::Notify for MyValue {}
+/// let (tx1, _rx1) = splitrc::new(MyValue {});
+/// let (tx2, _rx2) = splitrc::new(MyValue {});
+/// let mut registry = HashSet::new();
+/// assert!(registry.insert(ByAddress(tx1.clone())));
+/// assert!(!registry.insert(ByAddress(tx1)));
+/// assert!(registry.insert(ByAddress(tx2)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ByAddress<P>(pub P);
+
+impl<P> Deref for ByAddress<P> {
+    type Target = P;
+
+    fn deref(&self) -> &P {
+        &self.0
+    }
+}
+
+impl<T: Notify + ?Sized> PartialEq for ByAddress<Tx<T>> {
+    fn eq(&self, other: &Self) -> bool {
+        Tx::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T: Notify + ?Sized> Eq for ByAddress<Tx<T>> {}
+
+impl<T: Notify + ?Sized> PartialOrd for ByAddress<Tx<T>> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Notify + ?Sized> Ord for ByAddress<Tx<T>> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        Tx::addr(&self.0).cmp(&Tx::addr(&other.0))
+    }
+}
+
+impl<T: Notify + ?Sized> Hash for ByAddress<Tx<T>> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Tx::addr(&self.0).hash(state)
+    }
+}
+
+impl<T: Notify + ?Sized> PartialEq for ByAddress<Rx<T>> {
+    fn eq(&self, other: &Self) -> bool {
+        Rx::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T: Notify + ?Sized> Eq for ByAddress<Rx<T>> {}
+
+impl<T: Notify + ?Sized> PartialOrd for ByAddress<Rx<T>> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Notify + ?Sized> Ord for ByAddress<Rx<T>> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        Rx::addr(&self.0).cmp(&Rx::addr(&other.0))
+    }
+}
+
+impl<T: Notify + ?Sized> Hash for ByAddress<Rx<T>> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Rx::addr(&self.0).hash(state)
+    }
+}
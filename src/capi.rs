@@ -0,0 +1,179 @@
+//! A C interface to split-counted handles.
+//!
+//! Handles are opaque pointers. The payload is a [SplitrcCallbacks]
+//! whose function pointers are called when the last `SplitrcTx` or
+//! last `SplitrcRx` is dropped, and when the allocation is freed.
+//! Every function is prefixed with `splitrc_` and every type uses C
+//! layout, so cbindgen can generate a header from this module.
+//!
+//! The symbols are exported from whichever staticlib or cdylib links
+//! this crate with the `capi` feature enabled.
+//!
+//! ```c
+//! static void on_last_tx(void* user_data) { close_queue(user_data); }
+//!
+//! SplitrcCallbacks callbacks = {queue, on_last_tx, NULL, free_queue};
+//! SplitrcPair pair = splitrc_create(callbacks);
+//! SplitrcTx* tx2 = splitrc_clone_tx(pair.tx);
+//! splitrc_drop_tx(pair.tx);
+//! splitrc_drop_tx(tx2); /* calls on_last_tx */
+//! splitrc_drop_rx(pair.rx); /* calls free_queue */
+//! ```
+
+use crate::{new, Notify, Rx, Tx};
+use core::ffi::c_void;
+
+/// A C callback that receives [SplitrcCallbacks::user_data].
+pub type SplitrcCallback = Option<unsafe extern "C" fn(user_data: *mut c_void)>;
+
+/// The callbacks of an allocation created by [splitrc_create]. Null
+/// callbacks are not called.
+///
+/// A callback runs on whichever thread drops the handle that triggers
+/// it, so `user_data` must be safe to use from any thread that drops
+/// a handle.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct SplitrcCallbacks {
+    /// Passed to each callback.
+    pub user_data: *mut c_void,
+    /// Called when the last `SplitrcTx` is dropped, if any
+    /// `SplitrcRx` is alive.
+    pub last_tx_did_drop: SplitrcCallback,
+    /// Called when the last `SplitrcRx` is dropped, if any
+    /// `SplitrcTx` is alive.
+    pub last_rx_did_drop: SplitrcCallback,
+    /// Called once when the last handle of either half is dropped,
+    /// after any notification. Frees `user_data`.
+    pub free: SplitrcCallback,
+}
+
+/// An opaque tx handle.
+pub struct SplitrcTx {
+    _private: [u8; 0],
+}
+
+/// An opaque rx handle.
+pub struct SplitrcRx {
+    _private: [u8; 0],
+}
+
+/// The handles returned by [splitrc_create].
+#[repr(C)]
+#[derive(Debug)]
+pub struct SplitrcPair {
+    /// The first tx handle.
+    pub tx: *mut SplitrcTx,
+    /// The first rx handle.
+    pub rx: *mut SplitrcRx,
+}
+
+// The payload behind every handle.
+struct Payload(SplitrcCallbacks);
+
+fn call(callback: SplitrcCallback, user_data: *mut c_void) {
+    if let Some(callback) = callback {
+        // SAFETY: splitrc_create requires the callbacks to accept
+        // user_data.
+        unsafe { callback(user_data) }
+    }
+}
+
+impl Notify for Payload {
+    fn last_tx_did_drop(&self) {
+        call(self.0.last_tx_did_drop, self.0.user_data)
+    }
+
+    fn last_rx_did_drop(&self) {
+        call(self.0.last_rx_did_drop, self.0.user_data)
+    }
+}
+
+impl Drop for Payload {
+    fn drop(&mut self) {
+        call(self.0.free, self.0.user_data)
+    }
+}
+
+/// Allocates a payload holding `callbacks` and returns its first tx
+/// and rx handles.
+///
+/// # Safety
+///
+/// Each non-null callback must be safe to call with
+/// `callbacks.user_data` from any thread that drops a handle. The
+/// callbacks must not unwind.
+#[no_mangle]
+pub unsafe extern "C" fn splitrc_create(callbacks: SplitrcCallbacks) -> SplitrcPair {
+    let (tx, rx) = new(Payload(callbacks));
+    SplitrcPair {
+        tx: Tx::into_raw(tx) as *mut SplitrcTx,
+        rx: Rx::into_raw(rx) as *mut SplitrcRx,
+    }
+}
+
+/// Returns a new tx handle to the same allocation.
+///
+/// # Safety
+///
+/// `tx` must be a live handle returned by this module.
+#[no_mangle]
+pub unsafe extern "C" fn splitrc_clone_tx(tx: *const SplitrcTx) -> *mut SplitrcTx {
+    Tx::increment_tx_count(tx as *const Payload);
+    tx as *mut SplitrcTx
+}
+
+/// Returns a new rx handle to the same allocation.
+///
+/// # Safety
+///
+/// `rx` must be a live handle returned by this module.
+#[no_mangle]
+pub unsafe extern "C" fn splitrc_clone_rx(rx: *const SplitrcRx) -> *mut SplitrcRx {
+    Rx::increment_rx_count(rx as *const Payload);
+    rx as *mut SplitrcRx
+}
+
+/// Drops a tx handle, calling `last_tx_did_drop` or `free` if it
+/// was the last. Does nothing if `tx` is null.
+///
+/// # Safety
+///
+/// `tx` must be null or a live handle returned by this module. It
+/// may not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn splitrc_drop_tx(tx: *mut SplitrcTx) {
+    drop(Tx::option_from_raw(tx as *const Payload))
+}
+
+/// Drops an rx handle, calling `last_rx_did_drop` or `free` if it
+/// was the last. Does nothing if `rx` is null.
+///
+/// # Safety
+///
+/// `rx` must be null or a live handle returned by this module. It
+/// may not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn splitrc_drop_rx(rx: *mut SplitrcRx) {
+    drop(Rx::option_from_raw(rx as *const Payload))
+}
+
+/// Returns the `user_data` the allocation was created with.
+///
+/// # Safety
+///
+/// `tx` must be a live handle returned by this module.
+#[no_mangle]
+pub unsafe extern "C" fn splitrc_tx_user_data(tx: *const SplitrcTx) -> *mut c_void {
+    (*(tx as *const Payload)).0.user_data
+}
+
+/// Returns the `user_data` the allocation was created with.
+///
+/// # Safety
+///
+/// `rx` must be a live handle returned by this module.
+#[no_mangle]
+pub unsafe extern "C" fn splitrc_rx_user_data(rx: *const SplitrcRx) -> *mut c_void {
+    (*(rx as *const Payload)).0.user_data
+}
@@ -0,0 +1,256 @@
+//! Closed detection for custom channels.
+//!
+//! Senders are [Tx] handles and receivers are [Rx] handles to one
+//! [Shared] allocation that holds the queue. When every sender or
+//! every receiver has been dropped, the other side is marked closed
+//! and its wakers are woken. The queue itself is up to you.
+//!
+//! ```
+//! use splitrc::channel_core::{self, Receiver, Sender};
+//! use std::collections::VecDeque;
+//! use std::sync::Mutex;
+//!
+//! fn send(tx: &Sender<Mutex<VecDeque<u32>>>, value: u32) -> Result<(), u32> {
+//!     if tx.is_rx_closed() {
+//!         return Err(value);
+//!     }
+//!     tx.lock().unwrap().push_back(value);
+//!     tx.wake_receivers();
+//!     Ok(())
+//! }
+//!
+//! fn try_recv(rx: &Receiver<Mutex<VecDeque<u32>>>) -> Option<u32> {
+//!     rx.lock().unwrap().pop_front()
+//! }
+//!
+//! let (tx, rx) = channel_core::new(Mutex::new(VecDeque::new()));
+//! send(&tx, 1).unwrap();
+//! drop(tx);
+//! assert!(rx.is_tx_closed());
+//! assert_eq!(Some(1), try_recv(&rx));
+//! ```
+
+use crate::{AtomicBool, Notify, Rx, Tx};
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::Deref;
+use core::sync::atomic::Ordering;
+use core::task::{Context, Poll, Waker};
+
+#[cfg(loom)]
+use loom::sync::Mutex;
+
+#[cfg(not(loom))]
+use std::sync::Mutex;
+
+/// The sending half of a channel built on [Shared].
+pub type Sender<S> = Tx<Shared<S>>;
+
+/// The receiving half of a channel built on [Shared].
+pub type Receiver<S> = Rx<Shared<S>>;
+
+// Every registered waker is woken at once, so a channel may have
+// several blocked receivers or senders.
+#[derive(Default)]
+struct WakerList {
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl WakerList {
+    fn register(&self, waker: &Waker) {
+        let mut wakers = self.wakers.lock().unwrap_or_else(|e| e.into_inner());
+        if !wakers.iter().any(|old| old.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+
+    fn wake(&self) {
+        let wakers = mem::take(&mut *self.wakers.lock().unwrap_or_else(|e| e.into_inner()));
+        // Wake outside of the lock.
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// The state shared by a channel's senders and receivers: the queue
+/// `S`, whether each side has closed, and the wakers waiting on each
+/// side.
+pub struct Shared<S> {
+    queue: S,
+    tx_closed: AtomicBool,
+    rx_closed: AtomicBool,
+    receivers: WakerList,
+    senders: WakerList,
+}
+
+impl<S> Shared<S> {
+    /// Wraps `queue` with both sides open.
+    pub fn new(queue: S) -> Self {
+        Self {
+            queue,
+            tx_closed: AtomicBool::new(false),
+            rx_closed: AtomicBool::new(false),
+            receivers: WakerList::default(),
+            senders: WakerList::default(),
+        }
+    }
+
+    /// Returns the queue.
+    pub fn queue(&self) -> &S {
+        &self.queue
+    }
+
+    /// Returns true once every sender has been dropped.
+    pub fn is_tx_closed(&self) -> bool {
+        self.tx_closed.load(Ordering::Acquire)
+    }
+
+    /// Returns true once every receiver has been dropped.
+    pub fn is_rx_closed(&self) -> bool {
+        self.rx_closed.load(Ordering::Acquire)
+    }
+
+    /// Registers a waker to be woken by [Shared::wake_receivers] or
+    /// when the last sender is dropped.
+    ///
+    /// Check the queue and [Shared::is_tx_closed] again after
+    /// registering, or use [Shared::poll_recv].
+    pub fn register_receiver(&self, waker: &Waker) {
+        self.receivers.register(waker)
+    }
+
+    /// Wakes every registered receiver. Call after pushing to the
+    /// queue.
+    pub fn wake_receivers(&self) {
+        self.receivers.wake()
+    }
+
+    /// Registers a waker to be woken by [Shared::wake_senders] or
+    /// when the last receiver is dropped.
+    pub fn register_sender(&self, waker: &Waker) {
+        self.senders.register(waker)
+    }
+
+    /// Wakes every registered sender. Call after making room in a
+    /// bounded queue.
+    pub fn wake_senders(&self) {
+        self.senders.wake()
+    }
+
+    /// Receives with `try_recv`, registering the context's waker if
+    /// the queue is empty. Returns `None` once the queue is empty and
+    /// every sender has been dropped.
+    pub fn poll_recv<T>(
+        &self,
+        cx: &mut Context<'_>,
+        mut try_recv: impl FnMut(&S) -> Option<T>,
+    ) -> Poll<Option<T>> {
+        if let Some(value) = self.try_recv_or_closed(&mut try_recv) {
+            return Poll::Ready(value);
+        }
+        self.register_receiver(cx.waker());
+        // Checking again after registering ensures we either observe
+        // the send or close, or are registered before the wake.
+        match self.try_recv_or_closed(&mut try_recv) {
+            Some(value) => Poll::Ready(value),
+            None => Poll::Pending,
+        }
+    }
+
+    fn try_recv_or_closed<T>(
+        &self,
+        try_recv: &mut impl FnMut(&S) -> Option<T>,
+    ) -> Option<Option<T>> {
+        if let Some(value) = try_recv(&self.queue) {
+            return Some(Some(value));
+        }
+        // A send may land between the check and the last sender's
+        // drop, so check once more after observing the close.
+        self.is_tx_closed().then(|| try_recv(&self.queue))
+    }
+}
+
+impl<S> Notify for Shared<S> {
+    fn last_tx_did_drop(&self) {
+        self.tx_closed.store(true, Ordering::Release);
+        self.receivers.wake()
+    }
+
+    fn last_rx_did_drop(&self) {
+        self.rx_closed.store(true, Ordering::Release);
+        self.senders.wake()
+    }
+}
+
+impl<S> Deref for Shared<S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.queue
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for Shared<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shared")
+            .field("queue", &self.queue)
+            .field("tx_closed", &self.is_tx_closed())
+            .field("rx_closed", &self.is_rx_closed())
+            .finish()
+    }
+}
+
+/// Allocates the shared state around `queue` and returns the first
+/// sender and receiver.
+pub fn new<S>(queue: S) -> (Sender<S>, Receiver<S>) {
+    crate::new(Shared::new(queue))
+}
+
+impl<S> Tx<Shared<S>> {
+    /// Returns a new sender. Equivalent to cloning.
+    pub fn sender_handle(this: &Self) -> Sender<S> {
+        this.clone()
+    }
+
+    /// Returns a new receiver, or [None] if every receiver has been
+    /// dropped.
+    pub fn receiver_handle(this: &Self) -> Option<Receiver<S>> {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        if !inner.count.upgrade_rx() {
+            return None;
+        }
+        #[cfg(feature = "observer")]
+        inner.data.rx_did_clone();
+        Some(Rx {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<S> Rx<Shared<S>> {
+    /// Returns a new sender, or [None] if every sender has been
+    /// dropped.
+    pub fn sender_handle(this: &Self) -> Option<Sender<S>> {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        if !inner.count.upgrade_tx() {
+            return None;
+        }
+        #[cfg(feature = "observer")]
+        inner.data.tx_did_clone();
+        Some(Tx {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns a new receiver. Equivalent to cloning.
+    pub fn receiver_handle(this: &Self) -> Receiver<S> {
+        this.clone()
+    }
+}
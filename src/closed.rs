@@ -0,0 +1,265 @@
+//! Futures that resolve when the opposite half is dropped.
+
+use crate::{release_weak, rx_count, tx_count, Inner, Notify, Packed, Rx, Tx};
+use alloc::vec::Vec;
+use core::future::Future;
+use core::marker::PhantomData;
+use core::mem;
+use core::pin::Pin;
+use core::ptr::NonNull;
+use core::sync::atomic::Ordering;
+use core::task::{Context, Poll, Waker};
+
+#[cfg(loom)]
+use loom::sync::Mutex;
+
+#[cfg(not(loom))]
+use std::sync::Mutex;
+
+// Wakers are stored outside of data so futures, which hold weak
+// references, can deregister after data is dropped.
+//
+// Wakers are only woken while the allocation is known to be live:
+// when one half notifies, or when the allocation is being dropped.
+#[derive(Default)]
+pub(crate) struct Wakers {
+    tx_closed: Mutex<WakerSlab>,
+    rx_closed: Mutex<WakerSlab>,
+}
+
+impl Wakers {
+    #[cfg(not(any(loom, shuttle)))]
+    pub(crate) const fn new() -> Self {
+        Wakers {
+            tx_closed: Mutex::new(WakerSlab::new()),
+            rx_closed: Mutex::new(WakerSlab::new()),
+        }
+    }
+
+    pub(crate) fn wake_tx_closed(&self) {
+        wake_all(&self.tx_closed)
+    }
+
+    pub(crate) fn wake_rx_closed(&self) {
+        wake_all(&self.rx_closed)
+    }
+}
+
+fn wake_all(slab: &Mutex<WakerSlab>) {
+    let wakers = slab.lock().unwrap().clear();
+    // Wake outside of the lock.
+    for waker in wakers.into_iter().flatten() {
+        waker.wake();
+    }
+}
+
+// Futures hold a key into the slab so dropped futures don't leak
+// wakers. With the revive feature, a closed half may reopen, and its
+// futures register again, so clearing the slab starts a new
+// generation, and keys from an older one are ignored rather than
+// aliasing a new registration.
+#[derive(Default)]
+struct WakerSlab {
+    wakers: Vec<Option<Waker>>,
+    free: Vec<usize>,
+    generation: usize,
+}
+
+#[derive(Clone, Copy)]
+struct SlabKey {
+    generation: usize,
+    index: usize,
+}
+
+impl WakerSlab {
+    #[cfg(not(any(loom, shuttle)))]
+    const fn new() -> Self {
+        WakerSlab {
+            wakers: Vec::new(),
+            free: Vec::new(),
+            generation: 0,
+        }
+    }
+
+    /// Removes every waker and invalidates every key.
+    fn clear(&mut self) -> Vec<Option<Waker>> {
+        self.free.clear();
+        self.generation = self.generation.wrapping_add(1);
+        mem::take(&mut self.wakers)
+    }
+
+    fn slot(&mut self, key: Option<SlabKey>) -> Option<&mut Option<Waker>> {
+        let key = key.filter(|key| key.generation == self.generation)?;
+        self.wakers.get_mut(key.index)
+    }
+
+    fn register(&mut self, key: &mut Option<SlabKey>, waker: &Waker) {
+        if let Some(slot) = self.slot(*key) {
+            match slot {
+                Some(old) if old.will_wake(waker) => {}
+                _ => *slot = Some(waker.clone()),
+            }
+            return;
+        }
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.wakers[index] = Some(waker.clone());
+                index
+            }
+            None => {
+                self.wakers.push(Some(waker.clone()));
+                self.wakers.len() - 1
+            }
+        };
+        *key = Some(SlabKey {
+            generation: self.generation,
+            index,
+        });
+    }
+
+    fn remove(&mut self, key: &mut Option<SlabKey>) {
+        if let Some(key) = key.take() {
+            if let Some(slot) = self.slot(Some(key)) {
+                if slot.take().is_some() {
+                    self.free.push(key.index);
+                }
+            }
+        }
+    }
+}
+
+fn poll_closed<T: ?Sized>(
+    ptr: NonNull<Inner<T>>,
+    key: &mut Option<SlabKey>,
+    cx: &mut Context<'_>,
+    slab: impl FnOnce(&Wakers) -> &Mutex<WakerSlab>,
+    is_closed: impl FnOnce(Packed) -> bool,
+) -> Poll<()> {
+    // SAFETY: The future holds a weak reference. data may have been
+    // dropped, so only reference the counts and wakers.
+    let (count, wakers) = unsafe { (&(*ptr.as_ptr()).count, &(*ptr.as_ptr()).wakers) };
+    let mut slab = slab(wakers).lock().unwrap();
+    // Checking under the lock ensures we either observe the closed
+    // half or are registered before the wake.
+    if is_closed(count.0.load(Ordering::Acquire)) {
+        slab.remove(key);
+        Poll::Ready(())
+    } else {
+        slab.register(key, cx.waker());
+        Poll::Pending
+    }
+}
+
+fn drop_closed<T: ?Sized>(
+    ptr: NonNull<Inner<T>>,
+    key: &mut Option<SlabKey>,
+    slab: impl FnOnce(&Wakers) -> &Mutex<WakerSlab>,
+) {
+    if key.is_some() {
+        // SAFETY: The future holds a weak reference. data may have
+        // been dropped, so only reference the wakers.
+        let wakers = unsafe { &(*ptr.as_ptr()).wakers };
+        slab(wakers).lock().unwrap().remove(key);
+    }
+    release_weak(ptr);
+}
+
+/// Future returned by [Tx::closed]. Resolves when the last [Rx] is
+/// dropped.
+pub struct TxClosed<T: Notify + ?Sized> {
+    ptr: NonNull<Inner<T>>,
+    key: Option<SlabKey>,
+    phantom: PhantomData<T>,
+}
+
+unsafe impl<T: Sync + Send + Notify + ?Sized> Send for TxClosed<T> {}
+unsafe impl<T: Sync + Send + Notify + ?Sized> Sync for TxClosed<T> {}
+
+// The future never pins data.
+impl<T: Notify + ?Sized> Unpin for TxClosed<T> {}
+
+impl<T: Notify + ?Sized> Future for TxClosed<T> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        poll_closed(
+            this.ptr,
+            &mut this.key,
+            cx,
+            |wakers| &wakers.rx_closed,
+            |c| rx_count(c) == 0,
+        )
+    }
+}
+
+impl<T: Notify + ?Sized> Drop for TxClosed<T> {
+    fn drop(&mut self) {
+        drop_closed(self.ptr, &mut self.key, |wakers| &wakers.rx_closed)
+    }
+}
+
+impl<T: Notify + ?Sized> Tx<T> {
+    /// Returns a future that resolves when the last [Rx] is dropped.
+    ///
+    /// The future does not keep the allocation alive.
+    pub fn closed(this: &Self) -> TxClosed<T> {
+        // SAFETY: We do not create a &mut to Inner.
+        unsafe { this.ptr.as_ref() }.weak.inc();
+        TxClosed {
+            ptr: this.ptr,
+            key: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Future returned by [Rx::closed]. Resolves when the last [Tx] is
+/// dropped.
+pub struct RxClosed<T: Notify + ?Sized> {
+    ptr: NonNull<Inner<T>>,
+    key: Option<SlabKey>,
+    phantom: PhantomData<T>,
+}
+
+unsafe impl<T: Sync + Send + Notify + ?Sized> Send for RxClosed<T> {}
+unsafe impl<T: Sync + Send + Notify + ?Sized> Sync for RxClosed<T> {}
+
+// The future never pins data.
+impl<T: Notify + ?Sized> Unpin for RxClosed<T> {}
+
+impl<T: Notify + ?Sized> Future for RxClosed<T> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        poll_closed(
+            this.ptr,
+            &mut this.key,
+            cx,
+            |wakers| &wakers.tx_closed,
+            |c| tx_count(c) == 0,
+        )
+    }
+}
+
+impl<T: Notify + ?Sized> Drop for RxClosed<T> {
+    fn drop(&mut self) {
+        drop_closed(self.ptr, &mut self.key, |wakers| &wakers.tx_closed)
+    }
+}
+
+impl<T: Notify + ?Sized> Rx<T> {
+    /// Returns a future that resolves when the last [Tx] is dropped.
+    ///
+    /// The future does not keep the allocation alive.
+    pub fn closed(this: &Self) -> RxClosed<T> {
+        // SAFETY: We do not create a &mut to Inner.
+        unsafe { this.ptr.as_ref() }.weak.inc();
+        RxClosed {
+            ptr: this.ptr,
+            key: None,
+            phantom: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Running notifications off of the dropping thread.
+
+use crate::{new_cyclic, Notify, Rx, RxWeak, Tx, TxWeak};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use core::fmt;
+use core::ops::Deref;
+
+/// A notification queued by [new_deferred].
+pub type NotifyJob = Box<dyn FnOnce() + Send>;
+
+/// Runs notifications queued by [new_deferred], for example on a
+/// thread pool or an async runtime.
+///
+/// Implemented for closures that accept a [NotifyJob].
+pub trait NotifyExecutor: Send + Sync {
+    /// Schedules `job` to run later. `job` keeps the payload alive
+    /// until it runs or is dropped.
+    fn execute(&self, job: NotifyJob);
+}
+
+impl<F: Fn(NotifyJob) + Send + Sync> NotifyExecutor for F {
+    fn execute(&self, job: NotifyJob) {
+        self(job)
+    }
+}
+
+/// A payload whose notifications are run by a [NotifyExecutor].
+/// Created by [new_deferred].
+pub struct Deferred<T: Notify + Send + Sync + 'static> {
+    data: T,
+    executor: Arc<dyn NotifyExecutor>,
+    tx: TxWeak<Deferred<T>>,
+    rx: RxWeak<Deferred<T>>,
+}
+
+impl<T: Notify + Send + Sync + 'static> Notify for Deferred<T> {
+    fn last_tx_did_drop(&self) {
+        // The job holds an Rx so data outlives it. If the last Rx is
+        // being dropped concurrently, data is about to be dropped, so
+        // notify now instead.
+        match self.rx.upgrade() {
+            Some(rx) => self
+                .executor
+                .execute(Box::new(move || rx.data.last_tx_did_drop())),
+            None => self.data.last_tx_did_drop(),
+        }
+    }
+
+    fn last_rx_did_drop(&self) {
+        // See last_tx_did_drop.
+        match self.tx.upgrade() {
+            Some(tx) => self
+                .executor
+                .execute(Box::new(move || tx.data.last_rx_did_drop())),
+            None => self.data.last_rx_did_drop(),
+        }
+    }
+
+    fn will_deallocate(&self) {
+        // data is dropped right after, so this cannot be deferred.
+        self.data.will_deallocate()
+    }
+}
+
+impl<T: Notify + Send + Sync + 'static> Deref for Deferred<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data
+    }
+}
+
+impl<T: Notify + Send + Sync + fmt::Debug + 'static> fmt::Debug for Deferred<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.data, f)
+    }
+}
+
+/// Allocates a pointer holding `data` and returns a pair of
+/// references whose notifications are queued on `executor` instead
+/// of running inside [Drop].
+///
+/// Useful when [Notify] implementations take locks or are slow. A
+/// queued job holds a reference to the opposite half, so the payload
+/// stays alive until every job has run.
+///
+/// ```
+/// use std::sync::{mpsc, Arc};
+///
+/// # struct MyValue {}
+/// # impl splitrc::Notify for MyValue {}
+/// let (sender, queue) = mpsc::sync_channel::<splitrc::NotifyJob>(16);
+/// let (tx, rx) = splitrc::new_deferred(
+///     MyValue {},
+///     Arc::new(move |job| sender.send(job).unwrap()),
+/// );
+/// drop(tx);
+/// // Run the notification on this thread.
+/// queue.recv().unwrap()();
+/// # drop(rx);
+/// ```
+pub fn new_deferred<T: Notify + Send + Sync + 'static>(
+    data: T,
+    executor: Arc<dyn NotifyExecutor>,
+) -> (Tx<Deferred<T>>, Rx<Deferred<T>>) {
+    new_cyclic(|tx, rx| Deferred {
+        data,
+        executor,
+        tx: tx.clone(),
+        rx: rx.clone(),
+    })
+}
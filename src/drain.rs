@@ -0,0 +1,180 @@
+//! Draining in-flight work before teardown.
+//!
+//! Each worker holds a [DrainGuard], a tx handle to the shared work
+//! queue. The [DrainCoordinator] holds the rx handle and waits for
+//! the last guard to drop, after which it owns the queue outright.
+//!
+//! ```
+//! use std::sync::Mutex;
+//!
+//! let (guard, coordinator) = splitrc::drain::new(Mutex::new(Vec::new()));
+//! for i in 0..4 {
+//!     let guard = guard.clone();
+//!     std::thread::spawn(move || guard.lock().unwrap().push(i));
+//! }
+//! drop(guard);
+//! let done = coordinator.drain_blocking().into_inner().unwrap();
+//! assert_eq!(4, done.len());
+//! ```
+
+use crate::signal::Signal;
+use crate::{Notify, Rx, Tx};
+use core::fmt;
+use core::ops::Deref;
+
+#[cfg(feature = "async")]
+use crate::RxClosed;
+#[cfg(feature = "async")]
+use core::future::Future;
+#[cfg(feature = "async")]
+use core::pin::Pin;
+#[cfg(feature = "async")]
+use core::task::{Context, Poll};
+
+#[cfg(loom)]
+use loom::thread::yield_now;
+
+#[cfg(shuttle)]
+use shuttle::thread::yield_now;
+
+#[cfg(not(any(loom, shuttle)))]
+use std::thread::yield_now;
+
+// The data plus a flag set when the last guard drops.
+struct Protected<T> {
+    data: T,
+    signal: Signal,
+}
+
+impl<T> Notify for Protected<T> {
+    fn last_tx_did_drop(&self) {
+        self.signal.last_tx_did_drop()
+    }
+}
+
+/// Gives a worker access to the data and keeps the
+/// [DrainCoordinator] from tearing it down.
+pub struct DrainGuard<T>(Tx<Protected<T>>);
+
+impl<T> Clone for DrainGuard<T> {
+    fn clone(&self) -> Self {
+        DrainGuard(self.0.clone())
+    }
+}
+
+impl<T> Deref for DrainGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0.data
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for DrainGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Waits for every [DrainGuard] to drop, then takes the data.
+pub struct DrainCoordinator<T>(Rx<Protected<T>>);
+
+impl<T> DrainCoordinator<T> {
+    /// Returns the number of live [DrainGuard]s.
+    pub fn guard_count(&self) -> u32 {
+        Rx::tx_count(&self.0)
+    }
+
+    /// Returns true once every [DrainGuard] has been dropped.
+    pub fn is_drained(&self) -> bool {
+        self.0.signal.is_set()
+    }
+
+    /// Blocks the current thread until every [DrainGuard] has been
+    /// dropped, then returns the data.
+    pub fn drain_blocking(self) -> T {
+        self.0.signal.wait();
+        let mut rx = self.0;
+        loop {
+            match try_take(rx) {
+                Ok(data) => return data,
+                Err(again) => {
+                    rx = again;
+                    yield_now();
+                }
+            }
+        }
+    }
+
+    /// Returns a future that resolves to the data once every
+    /// [DrainGuard] has been dropped.
+    #[cfg(feature = "async")]
+    pub fn drain(self) -> Drain<T> {
+        Drain {
+            closed: Rx::closed(&self.0),
+            rx: Some(self.0),
+        }
+    }
+}
+
+// The last guard's notification is still finishing for a moment
+// after the signal is set, and until it does, the rx handle is not
+// yet unique.
+fn try_take<T>(rx: Rx<Protected<T>>) -> Result<T, Rx<Protected<T>>> {
+    Rx::try_unwrap(rx).map(|protected| protected.data)
+}
+
+impl<T> fmt::Debug for DrainCoordinator<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DrainCoordinator")
+            .field("guards", &self.guard_count())
+            .finish()
+    }
+}
+
+/// Future returned by [DrainCoordinator::drain].
+#[cfg(feature = "async")]
+pub struct Drain<T> {
+    closed: RxClosed<Protected<T>>,
+    rx: Option<Rx<Protected<T>>>,
+}
+
+#[cfg(feature = "async")]
+impl<T> Unpin for Drain<T> {}
+
+#[cfg(feature = "async")]
+impl<T> Future for Drain<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        if Pin::new(&mut self.closed).poll(cx).is_pending() {
+            return Poll::Pending;
+        }
+        let rx = self.rx.take().expect("Drain polled after completion");
+        match try_take(rx) {
+            Ok(data) => Poll::Ready(data),
+            Err(rx) => {
+                // Yield to the executor rather than spin.
+                self.rx = Some(rx);
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> fmt::Debug for Drain<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Drain")
+    }
+}
+
+/// Creates a [DrainGuard] and a [DrainCoordinator] sharing `data`.
+pub fn new<T>(data: T) -> (DrainGuard<T>, DrainCoordinator<T>) {
+    let (tx, rx) = crate::new(Protected {
+        data,
+        signal: Signal::default(),
+    });
+    (DrainGuard(tx), DrainCoordinator(rx))
+}
@@ -0,0 +1,136 @@
+//! Keep-alive handles with the payload type erased.
+
+use crate::{drop_rx, drop_tx, Inner, Notify, Rx, Tx};
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+
+// Inner is repr(C) with data last, so the counts are at the same
+// offsets regardless of T. Only dropping needs to know T.
+type ErasedInner = Inner<()>;
+
+/// A [Tx] whose payload type has been erased. Created by [Tx::erase].
+///
+/// Keeps the allocation alive and counts as a [Tx], but does not
+/// give access to the payload. Handles of different payload types
+/// can be stored in one collection.
+pub struct ErasedTx {
+    ptr: NonNull<ErasedInner>,
+    drop: fn(NonNull<ErasedInner>),
+    clone: fn(NonNull<ErasedInner>),
+}
+
+// SAFETY: Tx::erase requires the payload to be Send and Sync.
+unsafe impl Send for ErasedTx {}
+unsafe impl Sync for ErasedTx {}
+
+fn erased_drop_tx<T: Notify>(ptr: NonNull<ErasedInner>) {
+    drop_tx(ptr.cast::<Inner<T>>())
+}
+
+fn erased_clone_tx<T: Notify>(ptr: NonNull<ErasedInner>) {
+    // SAFETY: We do not create a &mut to Inner.
+    unsafe { ptr.cast::<Inner<T>>().as_ref() }.inc_tx()
+}
+
+impl<T: Notify + Send + Sync + 'static> Tx<T> {
+    /// Erases the payload type, returning a handle that only keeps
+    /// the allocation alive.
+    pub fn erase(this: Self) -> ErasedTx {
+        let this = ManuallyDrop::new(this);
+        ErasedTx {
+            ptr: this.ptr.cast(),
+            drop: erased_drop_tx::<T>,
+            clone: erased_clone_tx::<T>,
+        }
+    }
+}
+
+impl ErasedTx {
+    /// Returns true if both handles point to the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr == other.ptr
+    }
+}
+
+impl Drop for ErasedTx {
+    fn drop(&mut self) {
+        (self.drop)(self.ptr)
+    }
+}
+
+impl Clone for ErasedTx {
+    fn clone(&self) -> Self {
+        (self.clone)(self.ptr);
+        ErasedTx { ..*self }
+    }
+}
+
+impl fmt::Debug for ErasedTx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(ErasedTx)")
+    }
+}
+
+/// An [Rx] whose payload type has been erased. Created by [Rx::erase].
+///
+/// Keeps the allocation alive and counts as an [Rx], but does not
+/// give access to the payload. Handles of different payload types
+/// can be stored in one collection.
+pub struct ErasedRx {
+    ptr: NonNull<ErasedInner>,
+    drop: fn(NonNull<ErasedInner>),
+    clone: fn(NonNull<ErasedInner>),
+}
+
+// SAFETY: Rx::erase requires the payload to be Send and Sync.
+unsafe impl Send for ErasedRx {}
+unsafe impl Sync for ErasedRx {}
+
+fn erased_drop_rx<T: Notify>(ptr: NonNull<ErasedInner>) {
+    drop_rx(ptr.cast::<Inner<T>>())
+}
+
+fn erased_clone_rx<T: Notify>(ptr: NonNull<ErasedInner>) {
+    // SAFETY: We do not create a &mut to Inner.
+    unsafe { ptr.cast::<Inner<T>>().as_ref() }.inc_rx()
+}
+
+impl<T: Notify + Send + Sync + 'static> Rx<T> {
+    /// Erases the payload type, returning a handle that only keeps
+    /// the allocation alive.
+    pub fn erase(this: Self) -> ErasedRx {
+        let this = ManuallyDrop::new(this);
+        ErasedRx {
+            ptr: this.ptr.cast(),
+            drop: erased_drop_rx::<T>,
+            clone: erased_clone_rx::<T>,
+        }
+    }
+}
+
+impl ErasedRx {
+    /// Returns true if both handles point to the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr == other.ptr
+    }
+}
+
+impl Drop for ErasedRx {
+    fn drop(&mut self) {
+        (self.drop)(self.ptr)
+    }
+}
+
+impl Clone for ErasedRx {
+    fn clone(&self) -> Self {
+        (self.clone)(self.ptr);
+        ErasedRx { ..*self }
+    }
+}
+
+impl fmt::Debug for ErasedRx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(ErasedRx)")
+    }
+}
@@ -0,0 +1,108 @@
+//! A [Notify] adapter that reports drops to a channel.
+
+use crate::Notify;
+use core::fmt;
+use core::ops::Deref;
+
+/// A lifecycle event sent by [ChannelNotify].
+///
+/// `K` identifies the allocation, so one receiver can multiplex many.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DropEvent<K = ()> {
+    /// The last [crate::Tx] was dropped.
+    TxGone(K),
+    /// The last [crate::Rx] was dropped.
+    RxGone(K),
+}
+
+/// Somewhere to send [DropEvent]s.
+///
+/// Implemented for std and crossbeam senders. Sending happens inside
+/// [Drop], so it must not block: bounded senders discard the event
+/// when full. Errors such as a disconnected receiver are ignored.
+pub trait EventSink<E> {
+    /// Sends `event`.
+    fn send(&self, event: E);
+}
+
+#[cfg(feature = "std")]
+impl<E> EventSink<E> for std::sync::mpsc::Sender<E> {
+    fn send(&self, event: E) {
+        let _ = std::sync::mpsc::Sender::send(self, event);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E> EventSink<E> for std::sync::mpsc::SyncSender<E> {
+    fn send(&self, event: E) {
+        let _ = self.try_send(event);
+    }
+}
+
+#[cfg(feature = "crossbeam-channel")]
+impl<E> EventSink<E> for crossbeam_channel::Sender<E> {
+    fn send(&self, event: E) {
+        let _ = self.try_send(event);
+    }
+}
+
+/// A payload that sends a [DropEvent] when the last [crate::Tx] or
+/// last [crate::Rx] is dropped.
+///
+/// ```
+/// use splitrc::{ChannelNotify, DropEvent};
+///
+/// let (sender, receiver) = std::sync::mpsc::channel();
+/// let (tx, rx) = splitrc::new(ChannelNotify::with_key("a", 1, sender.clone()));
+/// drop(tx);
+/// assert_eq!(DropEvent::TxGone(1), receiver.recv().unwrap());
+/// # drop(rx);
+/// ```
+pub struct ChannelNotify<T, S, K = ()> {
+    data: T,
+    key: K,
+    sink: S,
+}
+
+impl<T, S: EventSink<DropEvent>> ChannelNotify<T, S> {
+    /// Wraps `data`, sending unkeyed events to `sink`.
+    pub fn new(data: T, sink: S) -> Self {
+        Self::with_key(data, (), sink)
+    }
+}
+
+impl<T, S: EventSink<DropEvent<K>>, K: Clone> ChannelNotify<T, S, K> {
+    /// Wraps `data`, sending events tagged with `key` to `sink`.
+    pub fn with_key(data: T, key: K, sink: S) -> Self {
+        Self { data, key, sink }
+    }
+
+    /// Returns the key sent with each event.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<T, S: EventSink<DropEvent<K>>, K: Clone> Notify for ChannelNotify<T, S, K> {
+    fn last_tx_did_drop(&self) {
+        self.sink.send(DropEvent::TxGone(self.key.clone()))
+    }
+
+    fn last_rx_did_drop(&self) {
+        self.sink.send(DropEvent::RxGone(self.key.clone()))
+    }
+}
+
+impl<T, S, K> Deref for ChannelNotify<T, S, K> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data
+    }
+}
+
+impl<T: fmt::Debug, S, K> fmt::Debug for ChannelNotify<T, S, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.data, f)
+    }
+}
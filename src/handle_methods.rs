@@ -0,0 +1,73 @@
+//! Method-call syntax for functions that consume a handle.
+
+/// Declares methods that take a [Tx](crate::Tx) or [Rx](crate::Rx)
+/// by value, like `self: Arc<Self>` methods take an [Arc].
+///
+/// Stable Rust does not allow `self: Rx<Self>` receivers. This macro
+/// declares a trait with the given methods and implements it for the
+/// handle type, so `rx.stop()` calls `stop` with `self` bound to the
+/// handle. The handle type may be a trait object, like
+/// `Rx<dyn Actor>`, to dispatch through the payload's trait. With the
+/// `nightly` feature, `self: Rx<Self>` works directly.
+///
+/// ```
+/// use std::sync::atomic::{AtomicBool, Ordering};
+///
+/// #[derive(Default)]
+/// struct Actor {
+///     stopped: AtomicBool,
+/// }
+/// impl splitrc::Notify for Actor {}
+///
+/// splitrc::handle_methods! {
+///     /// Methods on an actor's mailbox.
+///     pub trait Mailbox for splitrc::Rx<Actor> {
+///         /// Stops the actor and returns a weak handle to watch it.
+///         fn stop(self) -> splitrc::RxWeak<Actor> {
+///             self.stopped.store(true, Ordering::Release);
+///             splitrc::Rx::downgrade(&self)
+///         }
+///
+///         /// Returns the mailbox if it has exactly `n` readers.
+///         fn expect_readers(self, n: u32) -> Option<Self> {
+///             (splitrc::Rx::rx_count(&self) == n).then_some(self)
+///         }
+///     }
+/// }
+///
+/// let (tx, rx) = splitrc::new(Actor::default());
+/// let rx = rx.expect_readers(1).unwrap();
+/// let weak = rx.stop();
+/// assert!(tx.stopped.load(Ordering::Acquire));
+/// assert!(weak.upgrade().is_none());
+/// ```
+///
+/// [Arc]: alloc::sync::Arc
+#[macro_export]
+macro_rules! handle_methods {
+    (
+        $(#[$attr:meta])*
+        $vis:vis trait $name:ident for $handle:ty {
+            $(
+                $(#[$method_attr:meta])*
+                fn $method:ident($self:ident $(, $arg:ident: $arg_ty:ty)* $(,)?)
+                    $(-> $ret:ty)?
+                    $body:block
+            )*
+        }
+    ) => {
+        $(#[$attr])*
+        $vis trait $name: Sized {
+            $(
+                $(#[$method_attr])*
+                fn $method($self $(, $arg: $arg_ty)*) $(-> $ret)?;
+            )*
+        }
+
+        impl $name for $handle {
+            $(
+                fn $method($self $(, $arg: $arg_ty)*) $(-> $ret)? $body
+            )*
+        }
+    };
+}
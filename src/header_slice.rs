@@ -0,0 +1,188 @@
+//! A header and a variable-length slice in one allocation.
+
+use crate::{Inner, Notify, Rx, SplitCount, Tx, WeakCount};
+use alloc::alloc::{alloc, dealloc, handle_alloc_error, Layout};
+use allocator_api2::alloc::Global;
+use core::marker::PhantomData;
+use core::mem::{self, MaybeUninit};
+use core::ptr::{self, NonNull};
+
+/// A header followed by a slice, stored inline so that one
+/// allocation holds both. Created by [from_header_and_iter] and
+/// [from_header_and_slice].
+///
+/// Notifications are forwarded to the header.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HeaderSlice<H, S: ?Sized> {
+    /// The fixed-size header.
+    pub header: H,
+    /// The variable-length body.
+    pub slice: S,
+}
+
+impl<H: Notify, T> Notify for HeaderSlice<H, [T]> {
+    forward_pinned!(header);
+}
+
+/// Allocates an `Inner<HeaderSlice<H, [T]>>` holding `header` and
+/// the first `len` items.
+///
+/// Panics if `items` does not yield exactly `len` items. With a
+/// zero-sized `H`, the result has the same layout as `Inner<[T]>`.
+pub(crate) fn allocate_header_slice<H, T>(
+    header: H,
+    len: usize,
+    mut items: impl Iterator<Item = T>,
+) -> NonNull<Inner<HeaderSlice<H, [T]>>> {
+    // Frees the allocation and drops what was written if an item or
+    // the iterator panics.
+    struct Guard<H, T> {
+        ptr: *mut Inner<HeaderSlice<H, [T]>>,
+        layout: Layout,
+        written: usize,
+    }
+    impl<H, T> Drop for Guard<H, T> {
+        fn drop(&mut self) {
+            // SAFETY: The header and the first `written` items were
+            // initialized.
+            unsafe {
+                let data = ptr::addr_of_mut!((*self.ptr).data) as *mut HeaderSlice<H, [T]>;
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                    ptr::addr_of_mut!((*data).slice) as *mut T,
+                    self.written,
+                ));
+                ptr::drop_in_place(ptr::addr_of_mut!((*data).header));
+                #[cfg(feature = "async")]
+                ptr::drop_in_place(ptr::addr_of_mut!((*self.ptr).wakers));
+                #[cfg(feature = "event-listener")]
+                ptr::drop_in_place(ptr::addr_of_mut!((*self.ptr).events));
+                #[cfg(feature = "drop-callbacks")]
+                ptr::drop_in_place(ptr::addr_of_mut!((*self.ptr).subscribers));
+                #[cfg(feature = "leak-track")]
+                ptr::drop_in_place(ptr::addr_of_mut!((*self.ptr).leak));
+                dealloc(self.ptr as *mut u8, self.layout);
+            }
+        }
+    }
+
+    // repr(C) places the slice at the same offset whatever its
+    // length, so measure it with an empty array.
+    let prefix = Layout::new::<Inner<HeaderSlice<H, [T; 0]>>>();
+    let offset = {
+        let inner = MaybeUninit::<Inner<HeaderSlice<H, [T; 0]>>>::uninit();
+        let base = inner.as_ptr();
+        // SAFETY: Computing a field address does not read it, and both
+        // pointers are derived from the same local.
+        unsafe {
+            let data = ptr::addr_of!((*base).data) as *const HeaderSlice<H, [T; 0]>;
+            let slice = ptr::addr_of!((*data).slice);
+            (slice as *const u8).offset_from(base as *const u8) as usize
+        }
+    };
+    let layout = Layout::array::<T>(len)
+        .ok()
+        .and_then(|body| offset.checked_add(body.size()))
+        .and_then(|size| Layout::from_size_align(size, prefix.align()).ok())
+        .expect("capacity overflow")
+        .pad_to_align();
+
+    // SAFETY: Inner always contains the counts, so layout is not
+    // zero-sized.
+    let mem = unsafe { alloc(layout) };
+    if mem.is_null() {
+        handle_alloc_error(layout)
+    }
+    // Casting from a slice pointer keeps the length.
+    let p = ptr::slice_from_raw_parts_mut(mem as *mut T, len) as *mut Inner<HeaderSlice<H, [T]>>;
+    // SAFETY: The allocation is valid for writes of Inner with len
+    // items, and each field is written once.
+    unsafe {
+        ptr::addr_of_mut!((*p).count).write(SplitCount::new());
+        ptr::addr_of_mut!((*p).weak).write(WeakCount::new());
+        #[cfg(feature = "async")]
+        ptr::addr_of_mut!((*p).wakers).write(Default::default());
+        #[cfg(feature = "event-listener")]
+        ptr::addr_of_mut!((*p).events).write(Default::default());
+        #[cfg(feature = "drop-callbacks")]
+        ptr::addr_of_mut!((*p).subscribers).write(Default::default());
+        #[cfg(feature = "leak-track")]
+        ptr::addr_of_mut!((*p).leak).write(crate::leak::Entry::new(core::any::type_name::<
+            HeaderSlice<H, [T]>,
+        >()));
+        #[cfg(feature = "metrics")]
+        ptr::addr_of_mut!((*p).metrics_label).write(None);
+        #[cfg(feature = "revive")]
+        ptr::addr_of_mut!((*p).revival).write(None);
+        ptr::addr_of_mut!((*p).alloc).write(Global);
+        let data = ptr::addr_of_mut!((*p).data) as *mut HeaderSlice<H, [T]>;
+        ptr::addr_of_mut!((*data).header).write(header);
+        let mut guard = Guard {
+            ptr: p,
+            layout,
+            written: 0,
+        };
+        let slice = ptr::addr_of_mut!((*data).slice) as *mut T;
+        while guard.written < len {
+            let item = items
+                .next()
+                .expect("ExactSizeIterator over-reported length");
+            slice.add(guard.written).write(item);
+            guard.written += 1;
+        }
+        assert!(
+            items.next().is_none(),
+            "ExactSizeIterator under-reported length"
+        );
+        mem::forget(guard);
+        let ptr = NonNull::new_unchecked(p);
+        lifecycle_event!(ptr, "allocate");
+        ptr
+    }
+}
+
+/// Allocates a pointer holding `header` followed by the items of
+/// `items` and returns a pair of references.
+///
+/// ```
+/// # struct Packet { id: u32 }
+/// # impl splitrc::Notify for Packet {}
+/// let (tx, rx) = splitrc::from_header_and_iter(Packet { id: 7 }, 0..4u8);
+/// assert_eq!(7, rx.header.id);
+/// assert_eq!([0, 1, 2, 3], tx.slice);
+/// ```
+///
+/// Panics if the iterator reports the wrong length.
+#[allow(clippy::type_complexity)]
+pub fn from_header_and_iter<H, T, I>(
+    header: H,
+    items: I,
+) -> (Tx<HeaderSlice<H, [T]>>, Rx<HeaderSlice<H, [T]>>)
+where
+    H: Notify,
+    I: IntoIterator<Item = T>,
+    I::IntoIter: ExactSizeIterator,
+{
+    let items = items.into_iter();
+    let ptr = allocate_header_slice(header, items.len(), items);
+    (
+        Tx {
+            ptr,
+            phantom: PhantomData,
+        },
+        Rx {
+            ptr,
+            phantom: PhantomData,
+        },
+    )
+}
+
+/// Allocates a pointer holding `header` followed by clones of
+/// `items` and returns a pair of references.
+#[allow(clippy::type_complexity)]
+pub fn from_header_and_slice<H: Notify, T: Clone>(
+    header: H,
+    items: &[T],
+) -> (Tx<HeaderSlice<H, [T]>>, Rx<HeaderSlice<H, [T]>>) {
+    from_header_and_iter(header, items.iter().cloned())
+}
@@ -0,0 +1,238 @@
+//! Split reference counts biased toward one thread.
+//!
+//! A hybrid handle is owned by one thread. Every hybrid clone on that
+//! thread shares a single reference in the atomic count and tracks
+//! itself with a non-atomic counter, so cloning and dropping them is
+//! as cheap as [alloc::rc::Rc]. Convert to a [crate::Tx] or
+//! [crate::Rx] to hand a reference to another thread, and back with
+//! [From].
+//!
+//! ```
+//! # struct MyValue {}
+//! # impl splitrc::Notify for MyValue {}
+//! let (tx, rx) = splitrc::hybrid::new(MyValue {});
+//! let shared = splitrc::hybrid::Tx::to_shared(&tx);
+//! std::thread::spawn(move || {
+//!     let tx = splitrc::hybrid::Tx::from(shared);
+//!     # drop(tx);
+//! });
+//! # drop((tx, rx));
+//! ```
+
+use crate::{abort, drop_rx, drop_tx, Inner, Notify};
+use alloc::boxed::Box;
+use core::borrow::Borrow;
+use core::cell::Cell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::Deref;
+use core::ptr::NonNull;
+
+// The number of hybrid handles sharing one atomic reference.
+type LocalCount = Cell<usize>;
+
+fn inc_local(local: NonNull<LocalCount>) {
+    // SAFETY: local is live while any handle sharing it is.
+    let local = unsafe { local.as_ref() };
+    match local.get().checked_add(1) {
+        Some(n) => local.set(n),
+        None => abort(),
+    }
+}
+
+/// Returns true if the shared atomic reference should be released.
+fn dec_local(local: NonNull<LocalCount>) -> bool {
+    // SAFETY: local is live while any handle sharing it is.
+    let count = unsafe { local.as_ref() }.get() - 1;
+    if count == 0 {
+        // SAFETY: This was the last handle sharing local.
+        drop(unsafe { Box::from_raw(local.as_ptr()) });
+        true
+    } else {
+        // SAFETY: Other handles keep local alive.
+        unsafe { local.as_ref() }.set(count);
+        false
+    }
+}
+
+/// The write half of a split reference count, owned by one thread.
+pub struct Tx<T: Notify + ?Sized> {
+    local: NonNull<LocalCount>,
+    ptr: NonNull<Inner<T>>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Notify + ?Sized> Drop for Tx<T> {
+    fn drop(&mut self) {
+        if dec_local(self.local) {
+            drop_tx(self.ptr)
+        }
+    }
+}
+
+impl<T: Notify + ?Sized> Tx<T> {
+    /// Returns a thread-safe [crate::Tx] to the same allocation.
+    pub fn to_shared(this: &Self) -> crate::Tx<T> {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        inner.inc_tx();
+        crate::Tx {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns true if both handles point to the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr.cast::<u8>() == other.ptr.cast::<u8>()
+    }
+}
+
+impl<T: Notify + ?Sized> From<crate::Tx<T>> for Tx<T> {
+    fn from(tx: crate::Tx<T>) -> Self {
+        let ptr = tx.ptr;
+        // The local count takes over tx's reference.
+        mem::forget(tx);
+        Tx {
+            local: NonNull::from(Box::leak(Box::new(Cell::new(1)))),
+            ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Notify + ?Sized> Clone for Tx<T> {
+    fn clone(&self) -> Self {
+        inc_local(self.local);
+        Tx { ..*self }
+    }
+}
+
+impl<T: Notify + ?Sized> Deref for Tx<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: We know ptr is valid and do not create &mut.
+        &unsafe { self.ptr.as_ref() }.data
+    }
+}
+
+impl<T: Notify + ?Sized> AsRef<T> for Tx<T> {
+    fn as_ref(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + ?Sized> Borrow<T> for Tx<T> {
+    fn borrow(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + fmt::Debug + ?Sized> fmt::Debug for Tx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_ref(), f)
+    }
+}
+
+impl<T: Notify + fmt::Display + ?Sized> fmt::Display for Tx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_ref(), f)
+    }
+}
+
+/// The read half of a split reference count, owned by one thread.
+pub struct Rx<T: Notify + ?Sized> {
+    local: NonNull<LocalCount>,
+    ptr: NonNull<Inner<T>>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Notify + ?Sized> Drop for Rx<T> {
+    fn drop(&mut self) {
+        if dec_local(self.local) {
+            drop_rx(self.ptr)
+        }
+    }
+}
+
+impl<T: Notify + ?Sized> Rx<T> {
+    /// Returns a thread-safe [crate::Rx] to the same allocation.
+    pub fn to_shared(this: &Self) -> crate::Rx<T> {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        inner.inc_rx();
+        crate::Rx {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns true if both handles point to the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr.cast::<u8>() == other.ptr.cast::<u8>()
+    }
+}
+
+impl<T: Notify + ?Sized> From<crate::Rx<T>> for Rx<T> {
+    fn from(rx: crate::Rx<T>) -> Self {
+        let ptr = rx.ptr;
+        // The local count takes over rx's reference.
+        mem::forget(rx);
+        Rx {
+            local: NonNull::from(Box::leak(Box::new(Cell::new(1)))),
+            ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Notify + ?Sized> Clone for Rx<T> {
+    fn clone(&self) -> Self {
+        inc_local(self.local);
+        Rx { ..*self }
+    }
+}
+
+impl<T: Notify + ?Sized> Deref for Rx<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: We know ptr is valid and do not create &mut.
+        &unsafe { self.ptr.as_ref() }.data
+    }
+}
+
+impl<T: Notify + ?Sized> AsRef<T> for Rx<T> {
+    fn as_ref(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + ?Sized> Borrow<T> for Rx<T> {
+    fn borrow(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + fmt::Debug + ?Sized> fmt::Debug for Rx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_ref(), f)
+    }
+}
+
+impl<T: Notify + fmt::Display + ?Sized> fmt::Display for Rx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_ref(), f)
+    }
+}
+
+/// Allocates a pointer holding `data` and returns a pair of hybrid
+/// references owned by the calling thread.
+///
+/// The rules are the same as [crate::new].
+pub fn new<T: Notify>(data: T) -> (Tx<T>, Rx<T>) {
+    let (tx, rx) = crate::new(data);
+    (tx.into(), rx.into())
+}
@@ -0,0 +1,218 @@
+//! [Notify] for common standard types, and [AsRef] through handles
+//! to them.
+
+use crate::{DropContext, Notify, Rx, Tx};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::pin::Pin;
+
+macro_rules! impl_ignore {
+    ($($t:ty),*) => {
+        $(impl Notify for $t {})*
+    };
+}
+
+impl_ignore!((), String);
+impl_ignore!(i8, i16, i32, i64, i128, isize);
+impl_ignore!(u8, u16, u32, u64, u128, usize);
+
+impl<T> Notify for Vec<T> {}
+
+#[cfg(feature = "std")]
+impl_ignore!(std::path::PathBuf, std::ffi::OsString);
+#[cfg(feature = "std")]
+impl_ignore!(std::fs::File, std::net::TcpStream);
+#[cfg(all(feature = "std", unix))]
+impl_ignore!(std::os::unix::net::UnixStream);
+
+// Pin<&Box<T>> does not pin T, so only the unpinned methods can be
+// forwarded. The same goes for guards.
+macro_rules! forward_unpinned {
+    ($this:ident => $target:expr) => {
+        fn last_tx_did_drop(&self) {
+            let $this = self;
+            $target.last_tx_did_drop()
+        }
+
+        fn last_rx_did_drop(&self) {
+            let $this = self;
+            $target.last_rx_did_drop()
+        }
+
+        fn will_deallocate(&self) {
+            let $this = self;
+            $target.will_deallocate()
+        }
+
+        #[cfg(feature = "observer")]
+        fn tx_did_clone(&self) {
+            let $this = self;
+            $target.tx_did_clone()
+        }
+
+        #[cfg(feature = "observer")]
+        fn rx_did_clone(&self) {
+            let $this = self;
+            $target.rx_did_clone()
+        }
+
+        #[cfg(feature = "observer")]
+        fn tx_did_drop_one(&self) {
+            let $this = self;
+            $target.tx_did_drop_one()
+        }
+
+        #[cfg(feature = "observer")]
+        fn rx_did_drop_one(&self) {
+            let $this = self;
+            $target.rx_did_drop_one()
+        }
+    };
+}
+
+/// Forwards the unpinned notifications to the boxed value.
+impl<T: Notify + ?Sized> Notify for Box<T> {
+    forward_unpinned!(this => (**this));
+}
+
+/// Forwards the unpinned notifications to the protected value if the
+/// lock is free.
+///
+/// Notifications run inside [Drop], often on a thread that holds the
+/// lock, so they never block. If the lock is held, the notification
+/// is skipped. A poisoned lock is still used.
+#[cfg(feature = "std")]
+impl<T: Notify + ?Sized> Notify for std::sync::Mutex<T> {
+    forward_unpinned!(this => match this.try_lock() {
+        Ok(guard) => guard,
+        Err(std::sync::TryLockError::Poisoned(e)) => e.into_inner(),
+        Err(std::sync::TryLockError::WouldBlock) => return,
+    });
+}
+
+/// Forwards the unpinned notifications to the protected value if it
+/// can be read. See the [Mutex](std::sync::Mutex) implementation.
+#[cfg(feature = "std")]
+impl<T: Notify + ?Sized> Notify for std::sync::RwLock<T> {
+    forward_unpinned!(this => match this.try_read() {
+        Ok(guard) => guard,
+        Err(std::sync::TryLockError::Poisoned(e)) => e.into_inner(),
+        Err(std::sync::TryLockError::WouldBlock) => return,
+    });
+}
+
+// Tuples never move their elements, so they are structurally pinned.
+macro_rules! impl_tuple {
+    ($($name:ident $index:tt),+) => {
+        /// Forwards each notification to every element in order.
+        impl<$($name: Notify),+> Notify for ($($name,)+) {
+            fn last_tx_did_drop_with(self: Pin<&Self>, ctx: DropContext) {
+                // SAFETY: Tuple elements are structurally pinned.
+                $(unsafe { self.map_unchecked(|t| &t.$index) }.last_tx_did_drop_with(ctx);)+
+            }
+
+            fn last_tx_did_drop_pinned(self: Pin<&Self>) {
+                // SAFETY: Tuple elements are structurally pinned.
+                $(unsafe { self.map_unchecked(|t| &t.$index) }.last_tx_did_drop_pinned();)+
+            }
+
+            fn last_tx_did_drop(&self) {
+                $(self.$index.last_tx_did_drop();)+
+            }
+
+            fn last_rx_did_drop_with(self: Pin<&Self>, ctx: DropContext) {
+                // SAFETY: Tuple elements are structurally pinned.
+                $(unsafe { self.map_unchecked(|t| &t.$index) }.last_rx_did_drop_with(ctx);)+
+            }
+
+            fn last_rx_did_drop_pinned(self: Pin<&Self>) {
+                // SAFETY: Tuple elements are structurally pinned.
+                $(unsafe { self.map_unchecked(|t| &t.$index) }.last_rx_did_drop_pinned();)+
+            }
+
+            fn last_rx_did_drop(&self) {
+                $(self.$index.last_rx_did_drop();)+
+            }
+
+            fn will_deallocate_pinned(self: Pin<&Self>) {
+                // SAFETY: Tuple elements are structurally pinned.
+                $(unsafe { self.map_unchecked(|t| &t.$index) }.will_deallocate_pinned();)+
+            }
+
+            fn will_deallocate(&self) {
+                $(self.$index.will_deallocate();)+
+            }
+
+            #[cfg(feature = "observer")]
+            fn tx_did_clone(&self) {
+                $(self.$index.tx_did_clone();)+
+            }
+
+            #[cfg(feature = "observer")]
+            fn rx_did_clone(&self) {
+                $(self.$index.rx_did_clone();)+
+            }
+
+            #[cfg(feature = "observer")]
+            fn tx_did_drop_one(&self) {
+                $(self.$index.tx_did_drop_one();)+
+            }
+
+            #[cfg(feature = "observer")]
+            fn rx_did_drop_one(&self) {
+                $(self.$index.rx_did_drop_one();)+
+            }
+        }
+    };
+}
+
+impl_tuple!(A 0);
+impl_tuple!(A 0, B 1);
+impl_tuple!(A 0, B 1, C 2);
+impl_tuple!(A 0, B 1, C 2, D 3);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
+// A blanket `impl<T: AsRef<U>, U> AsRef<U> for Tx<T>` would overlap
+// with `AsRef<T> for Tx<T>`, so the conversions of common owned
+// payloads are forwarded one by one.
+macro_rules! forward_as_ref {
+    ($(impl<$($param:ident),*> $payload:ty => $target:ty;)*) => {
+        $(
+            impl<$($param),*> AsRef<$target> for Tx<$payload> {
+                fn as_ref(&self) -> &$target {
+                    (**self).as_ref()
+                }
+            }
+
+            impl<$($param),*> AsRef<$target> for Rx<$payload> {
+                fn as_ref(&self) -> &$target {
+                    (**self).as_ref()
+                }
+            }
+        )*
+    };
+}
+
+forward_as_ref! {
+    impl<> String => str;
+    impl<> String => [u8];
+    impl<T> Vec<T> => [T];
+}
+
+#[cfg(feature = "std")]
+forward_as_ref! {
+    impl<> String => std::ffi::OsStr;
+    impl<> String => std::path::Path;
+    impl<> std::ffi::OsString => std::ffi::OsStr;
+    impl<> std::ffi::OsString => std::path::Path;
+    impl<> std::path::PathBuf => std::ffi::OsStr;
+    impl<> std::path::PathBuf => std::path::Path;
+}
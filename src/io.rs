@@ -0,0 +1,64 @@
+//! [std::io] through handles to payloads that read and write through
+//! shared references, like [std::net::TcpStream] and [std::fs::File].
+
+use crate::{Notify, Rx, Tx};
+use std::io::{self, IoSlice, IoSliceMut, Read, Write};
+use std::string::String;
+use std::vec::Vec;
+
+macro_rules! forward_io {
+    ($handle:ident) => {
+        /// Reads through a shared reference to the payload, so every
+        /// handle to a socket can read from it, like `&TcpStream`.
+        impl<T: Notify + ?Sized> Read for $handle<T>
+        where
+            for<'a> &'a T: Read,
+        {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                (&**self).read(buf)
+            }
+
+            fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+                (&**self).read_vectored(bufs)
+            }
+
+            fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+                (&**self).read_to_end(buf)
+            }
+
+            fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+                (&**self).read_to_string(buf)
+            }
+
+            fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+                (&**self).read_exact(buf)
+            }
+        }
+
+        /// Writes through a shared reference to the payload, so every
+        /// handle to a socket can write to it, like `&TcpStream`.
+        impl<T: Notify + ?Sized> Write for $handle<T>
+        where
+            for<'a> &'a T: Write,
+        {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                (&**self).write(buf)
+            }
+
+            fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+                (&**self).write_vectored(bufs)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                (&**self).flush()
+            }
+
+            fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+                (&**self).write_all(buf)
+            }
+        }
+    };
+}
+
+forward_io!(Tx);
+forward_io!(Rx);
@@ -0,0 +1,155 @@
+//! A global registry of live allocations, for finding leaked handles.
+
+// The leak-track feature requires Rust 1.65 for Backtrace.
+#![allow(clippy::incompatible_msrv)]
+
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Instant;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+static REGISTRY: Mutex<Option<BTreeMap<u64, LiveAllocation>>> = Mutex::new(None);
+
+fn registry() -> MutexGuard<'static, Option<BTreeMap<u64, LiveAllocation>>> {
+    // Entries are inserted and removed whole, so a poisoned registry
+    // is still consistent.
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Registers its allocation for as long as it lives. Stored in every
+/// Inner.
+pub(crate) struct Entry {
+    // Zero for allocations that are never freed.
+    id: u64,
+}
+
+impl Entry {
+    pub(crate) fn new(type_name: &'static str) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        // Capturing respects RUST_BACKTRACE and RUST_LIB_BACKTRACE, so
+        // it is cheap unless enabled.
+        let backtrace = Backtrace::capture();
+        let backtrace = match backtrace.status() {
+            BacktraceStatus::Captured => Some(Arc::new(backtrace)),
+            _ => None,
+        };
+        let allocation = LiveAllocation {
+            id,
+            type_name,
+            created: Instant::now(),
+            backtrace,
+        };
+        registry()
+            .get_or_insert_with(BTreeMap::new)
+            .insert(id, allocation);
+        Entry { id }
+    }
+
+    /// Reports the allocation under `type_name`, for payloads built
+    /// as one type and viewed as another.
+    pub(crate) fn set_type_name(&self, type_name: &'static str) {
+        if let Some(allocation) = registry().as_mut().and_then(|map| map.get_mut(&self.id)) {
+            allocation.type_name = type_name;
+        }
+    }
+
+    /// For storage that outlives every handle, like a `static`.
+    #[cfg(not(any(loom, shuttle)))]
+    pub(crate) const fn untracked() -> Self {
+        Entry { id: 0 }
+    }
+}
+
+impl Drop for Entry {
+    fn drop(&mut self) {
+        if self.id != 0 {
+            if let Some(map) = registry().as_mut() {
+                map.remove(&self.id);
+            }
+        }
+    }
+}
+
+/// An allocation that has not been freed, as reported by
+/// [leak_report].
+#[derive(Clone, Debug)]
+pub struct LiveAllocation {
+    /// Identifies the allocation, in creation order.
+    pub id: u64,
+    /// The payload's type.
+    pub type_name: &'static str,
+    /// When the allocation was created.
+    pub created: Instant,
+    /// Where the allocation was created, if backtraces were enabled
+    /// with `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE`.
+    pub backtrace: Option<Arc<Backtrace>>,
+}
+
+/// A snapshot of every live allocation. Returned by [leak_report].
+#[derive(Clone, Debug, Default)]
+pub struct LeakReport {
+    /// The live allocations, oldest first.
+    pub allocations: Vec<LiveAllocation>,
+}
+
+impl LeakReport {
+    /// Returns the number of live allocations.
+    pub fn len(&self) -> usize {
+        self.allocations.len()
+    }
+
+    /// Returns true if no allocations are live.
+    pub fn is_empty(&self) -> bool {
+        self.allocations.is_empty()
+    }
+
+    /// Returns the number of live allocations of each payload type,
+    /// most first.
+    pub fn by_type(&self) -> Vec<(&'static str, usize)> {
+        let mut counts = BTreeMap::new();
+        for allocation in &self.allocations {
+            *counts.entry(allocation.type_name).or_insert(0) += 1;
+        }
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort_by_key(|&(_, count)| core::cmp::Reverse(count));
+        counts
+    }
+}
+
+/// Summarizes the live allocations by type.
+impl fmt::Display for LeakReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} live splitrc allocations", self.len())?;
+        for (type_name, count) in self.by_type() {
+            write!(f, "\n  {} {}", count, type_name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns every allocation that has not yet been freed.
+///
+/// An allocation is live until its last [crate::Tx], [crate::Rx], and
+/// weak reference are dropped. Allocations in a
+/// [crate::StaticSplitRc] are never freed and are not reported.
+///
+/// ```
+/// # struct MyValue {}
+/// # impl splitrc::Notify for MyValue {}
+/// let (tx, rx) = splitrc::new(MyValue {});
+/// let report = splitrc::leak_report();
+/// assert!(report.allocations.iter().any(|a| a.type_name.ends_with("MyValue")));
+/// # drop((tx, rx));
+/// ```
+pub fn leak_report() -> LeakReport {
+    let allocations = match registry().as_ref() {
+        Some(map) => map.values().cloned().collect(),
+        None => Vec::new(),
+    };
+    LeakReport { allocations }
+}
@@ -1,35 +1,343 @@
 #![doc = include_str!("../README.md")]
+#![no_std]
+#![cfg_attr(
+    feature = "nightly",
+    feature(
+        coerce_unsized,
+        dispatch_from_dyn,
+        legacy_receiver_trait,
+        pin_coerce_unsized_trait,
+        unsize
+    ),
+    allow(internal_features)
+)]
 
-use std::borrow::Borrow;
-use std::fmt;
-use std::marker::PhantomData;
-use std::ops::Deref;
-use std::pin::Pin;
-use std::process::abort;
-use std::ptr::NonNull;
-use std::sync::atomic::Ordering;
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+use alloc::alloc::{alloc, handle_alloc_error, Layout};
+use alloc::boxed::Box;
+use allocator_api2::alloc::{Allocator, Global};
+use core::borrow::Borrow;
+use core::cmp;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop, MaybeUninit};
+use core::ops::Deref;
+use core::panic::{RefUnwindSafe, UnwindSafe};
+use core::pin::Pin;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::Ordering;
 
 #[cfg(loom)]
-use loom::sync::atomic::AtomicU64;
+use loom::sync::atomic::{fence, AtomicBool, AtomicU32, AtomicU64, AtomicUsize};
+
+#[cfg(shuttle)]
+use shuttle::sync::atomic::{fence, AtomicBool, AtomicU32, AtomicU64, AtomicUsize};
+
+#[cfg(all(loom, shuttle))]
+compile_error!("cfg(loom) and cfg(shuttle) are mutually exclusive");
+
+#[cfg(all(
+    not(loom),
+    not(shuttle),
+    not(all(feature = "wasm-single-threaded", target_arch = "wasm32")),
+    not(feature = "portable-atomic"),
+    target_has_atomic = "64"
+))]
+use core::sync::atomic::{fence, AtomicBool, AtomicU32, AtomicU64, AtomicUsize};
+
+// Some 32-bit targets have no native 64-bit atomics, and some
+// embedded targets have no compare-and-swap at all.
+#[cfg(all(
+    not(loom),
+    not(shuttle),
+    not(all(feature = "wasm-single-threaded", target_arch = "wasm32")),
+    any(feature = "portable-atomic", not(target_has_atomic = "64"))
+))]
+use portable_atomic::{fence, AtomicBool, AtomicU32, AtomicU64, AtomicUsize};
+
+// Without threads, wasm32 needs no atomics at all.
+#[cfg(all(
+    not(loom),
+    not(shuttle),
+    feature = "wasm-single-threaded",
+    target_arch = "wasm32"
+))]
+use wasm::{fence, AtomicBool, AtomicU32, AtomicU64, AtomicUsize};
 
-#[cfg(not(loom))]
-use std::sync::atomic::AtomicU64;
+#[cfg(all(
+    feature = "wasm-single-threaded",
+    target_arch = "wasm32",
+    target_feature = "atomics"
+))]
+compile_error!("the wasm-single-threaded feature is unsound with wasm threads");
+
+#[cfg(feature = "std")]
+use std::process::abort;
 
 #[cfg(doc)]
-use std::marker::Unpin;
+use core::marker::Unpin;
+
+/// Reports a lifecycle event for the allocation behind a
+/// `NonNull<Inner>` to `tracing` and `metrics`, if enabled.
+macro_rules! lifecycle_event {
+    ($ptr:expr, $event:literal) => {
+        #[cfg(any(feature = "tracing", feature = "metrics"))]
+        {
+            let ptr = $ptr;
+            // SAFETY: We do not create a &mut to Inner.
+            #[allow(unused_unsafe)]
+            let inner = unsafe { ptr.as_ref() };
+            #[cfg(feature = "tracing")]
+            {
+                let counts = inner.count.counts();
+                tracing::trace!(
+                    ptr = ?ptr.cast::<u8>(),
+                    tx = counts.tx,
+                    rx = counts.rx,
+                    $event
+                );
+            }
+            #[cfg(feature = "metrics")]
+            crate::metrics::record(inner.metrics_label, $event);
+        }
+    };
+}
+
+/// Implements every [Notify] method, including the observer hooks, by
+/// forwarding to a structurally pinned field.
+macro_rules! forward_pinned {
+    ($field:ident) => {
+        fn last_tx_did_drop_with(self: core::pin::Pin<&Self>, ctx: crate::DropContext) {
+            // SAFETY: The field is structurally pinned.
+            unsafe { self.map_unchecked(|p| &p.$field) }.last_tx_did_drop_with(ctx)
+        }
+
+        fn last_tx_did_drop_pinned(self: core::pin::Pin<&Self>) {
+            // SAFETY: The field is structurally pinned.
+            unsafe { self.map_unchecked(|p| &p.$field) }.last_tx_did_drop_pinned()
+        }
+
+        fn last_tx_did_drop(&self) {
+            self.$field.last_tx_did_drop()
+        }
+
+        fn last_rx_did_drop_with(self: core::pin::Pin<&Self>, ctx: crate::DropContext) {
+            // SAFETY: The field is structurally pinned.
+            unsafe { self.map_unchecked(|p| &p.$field) }.last_rx_did_drop_with(ctx)
+        }
+
+        fn last_rx_did_drop_pinned(self: core::pin::Pin<&Self>) {
+            // SAFETY: The field is structurally pinned.
+            unsafe { self.map_unchecked(|p| &p.$field) }.last_rx_did_drop_pinned()
+        }
+
+        fn last_rx_did_drop(&self) {
+            self.$field.last_rx_did_drop()
+        }
+
+        fn will_deallocate_pinned(self: core::pin::Pin<&Self>) {
+            // SAFETY: The field is structurally pinned.
+            unsafe { self.map_unchecked(|p| &p.$field) }.will_deallocate_pinned()
+        }
+
+        fn will_deallocate(&self) {
+            self.$field.will_deallocate()
+        }
+
+        #[cfg(feature = "observer")]
+        fn tx_did_clone(&self) {
+            self.$field.tx_did_clone()
+        }
 
-// TODO:
-// * Missing trait implementations
-// * Error
-// * Pointer
-// * Eq, PartialEq
-// * Ord, PartialOrd
-// * Hash
+        #[cfg(feature = "observer")]
+        fn rx_did_clone(&self) {
+            self.$field.rx_did_clone()
+        }
+
+        #[cfg(feature = "observer")]
+        fn tx_did_drop_one(&self) {
+            self.$field.tx_did_drop_one()
+        }
+
+        #[cfg(feature = "observer")]
+        fn rx_did_drop_one(&self) {
+            self.$field.rx_did_drop_one()
+        }
+    };
+}
+
+mod adapters;
+mod any;
+#[cfg(target_has_atomic = "ptr")]
+mod atomic_cell;
+mod batch;
+mod borrowed;
+#[cfg(feature = "std")]
+pub mod buf;
+mod by_address;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "std")]
+pub mod channel_core;
+#[cfg(feature = "async")]
+mod closed;
+// alloc::sync requires pointer-sized atomics.
+#[cfg(target_has_atomic = "ptr")]
+mod deferred;
+#[cfg(feature = "std")]
+pub mod drain;
+mod erased;
+mod event;
+mod handle_methods;
+mod header_slice;
+pub mod hybrid;
+mod impls;
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "leak-track")]
+mod leak;
+#[cfg(feature = "std")]
+mod lifecycle;
+#[cfg(feature = "event-listener")]
+mod listener;
+pub mod local;
+mod map;
+#[cfg(feature = "metrics")]
+mod metrics;
+pub mod multi;
+#[cfg(feature = "nightly")]
+mod nightly;
+mod offset;
+mod once;
+#[cfg(feature = "std")]
+pub mod oneshot;
+mod padded;
+mod pair;
+mod pinned_weak;
+#[cfg(feature = "std")]
+pub mod pool;
+mod raw_waker;
+#[cfg(feature = "revive")]
+mod revive;
+mod scoped;
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(feature = "sharded")]
+pub mod sharded;
+#[cfg(feature = "std")]
+pub mod shutdown;
+#[cfg(feature = "std")]
+mod signal;
+mod slice;
+#[cfg(feature = "std")]
+mod split_swap;
+#[cfg(feature = "stable_deref_trait")]
+mod stable_deref;
+// loom and shuttle atomics cannot be constructed in a const context.
+#[cfg(not(any(loom, shuttle)))]
+mod static_rc;
+mod storage;
+#[cfg(feature = "async")]
+mod stream;
+#[cfg(feature = "drop-callbacks")]
+mod subscribers;
+mod tagged;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+#[cfg(feature = "std")]
+pub mod token;
+#[cfg(feature = "tokio")]
+mod tokio_notify;
+mod txrx;
+mod unique;
+#[cfg(feature = "std")]
+mod wait;
+#[cfg(feature = "async")]
+mod waker;
+#[cfg(all(feature = "wasm-single-threaded", target_arch = "wasm32"))]
+mod wasm;
+#[cfg(feature = "std")]
+pub mod watch;
+pub mod weighted;
+
+pub use adapters::{new_with_callbacks, Callbacks, Unnotified};
+pub use any::AnyNotify;
+#[cfg(target_has_atomic = "ptr")]
+pub use atomic_cell::{AtomicRx, AtomicTx};
+pub use borrowed::{RxBorrow, TxBorrow};
+pub use by_address::{ByAddress, SplitrcId};
+#[cfg(feature = "async")]
+pub use closed::{RxClosed, TxClosed};
+#[cfg(target_has_atomic = "ptr")]
+pub use deferred::{new_deferred, Deferred, NotifyExecutor, NotifyJob};
+pub use erased::{ErasedRx, ErasedTx};
+pub use event::{ChannelNotify, DropEvent, EventSink};
+pub use header_slice::{from_header_and_iter, from_header_and_slice, HeaderSlice};
+#[cfg(feature = "leak-track")]
+pub use leak::{leak_report, LeakReport, LiveAllocation};
+#[cfg(feature = "std")]
+pub use lifecycle::{new_with_events, Event, Events, Reported};
+pub use map::{MappedRx, MappedTx, PinnedMappedRx, PinnedMappedTx};
+pub use offset::{OffsetRx, OffsetTx};
+pub use once::{new_once, RxOnce, TxOnce};
+pub use padded::{new_padded, CachePadded};
+pub use pair::{new_pair, Pair, PinnedPair};
+pub use pinned_weak::{PinnedRxWeak, PinnedTxWeak};
+pub use raw_waker::Wake;
+#[cfg(feature = "revive")]
+pub use revive::{RxReviver, TxReviver};
+pub use scoped::{scope, ScopedRx, ScopedTx};
+#[cfg(feature = "serde")]
+pub use serde::{deserialize_pair, PairSeed};
+pub use slice::{from_iter, from_slice, from_str};
+#[cfg(feature = "std")]
+pub use split_swap::{SplitSwap, SplitSwapGuard};
+#[cfg(feature = "derive")]
+pub use splitrc_derive::Notify;
+#[cfg(not(any(loom, shuttle)))]
+pub use static_rc::StaticSplitRc;
+pub use storage::{new_in_place, SplitStorage, StorageRelease};
+#[cfg(feature = "async")]
+pub use stream::{SharedSink, SharedStream};
+#[cfg(feature = "drop-callbacks")]
+pub use subscribers::SubscriptionId;
+pub use tagged::{TaggedRx, TaggedTx};
+#[cfg(feature = "tokio")]
+pub use tokio_notify::{HalvesDropped, TokioNotify};
+pub use txrx::TxRx;
+pub use unique::{new_unique, UniqueHandle};
+#[cfg(feature = "async")]
+pub use waker::WakerNotify;
 
 /// Allows the reference-counted object to know when the last write
 /// reference or the last read reference is dropped.
 ///
 /// Exactly one of these functions will be called.
+///
+/// # Panics
+///
+/// If a notification or [Notify::will_deallocate] panics, the panic
+/// propagates out of the drop after the handle's reference is
+/// released: the closed half's waiters are still woken and the
+/// allocation is still freed exactly once. With the
+/// `abort-on-notify-panic` feature, the process aborts instead.
+///
+/// # Re-entrancy
+///
+/// A notification may drop handles to its own allocation, such as a
+/// [Tx] stored in the payload that is dropped from
+/// [Notify::last_rx_did_drop]. The closed half is never notified
+/// again, and dropping the other half's last handle does not notify
+/// or free anything. The payload is not dropped or freed until the
+/// running notification returns.
+///
+/// With the `revive` feature, a notification may instead reopen the
+/// closed half. See [Notify::last_rx_did_drop_revivable].
 pub trait Notify {
     /// Called when the last [Tx] is dropped. By default, delegates to
     /// [Notify::last_tx_did_drop].
@@ -46,6 +354,27 @@ pub trait Notify {
     /// NOTE: Only called if there are live [Rx] references.
     fn last_tx_did_drop(&self) {}
 
+    /// Called when the last [Tx] is dropped, with details about the
+    /// surviving [Rx] references. By default, delegates to
+    /// [Notify::last_tx_did_drop_pinned].
+    fn last_tx_did_drop_with(self: Pin<&Self>, ctx: DropContext) {
+        let _ = ctx;
+        self.last_tx_did_drop_pinned()
+    }
+
+    /// Called when the last [Tx] is dropped, with a [TxReviver] that
+    /// may reopen the tx half. By default, delegates to
+    /// [Notify::last_tx_did_drop_with]. See
+    /// [Notify::last_rx_did_drop_revivable].
+    #[cfg(feature = "revive")]
+    fn last_tx_did_drop_revivable(self: Pin<&Self>, ctx: DropContext, reviver: &TxReviver<'_, Self>)
+    where
+        Self: Sized,
+    {
+        let _ = reviver;
+        self.last_tx_did_drop_with(ctx)
+    }
+
     /// Called when the last [Rx] is dropped. By default, delegates to
     /// [Notify::last_rx_did_drop].
     fn last_rx_did_drop_pinned(self: Pin<&Self>) {
@@ -60,6 +389,197 @@ pub trait Notify {
     ///
     /// NOTE: Only called if there are live [Tx] references.
     fn last_rx_did_drop(&self) {}
+
+    /// Called when the last [Rx] is dropped, with details about the
+    /// surviving [Tx] references. By default, delegates to
+    /// [Notify::last_rx_did_drop_pinned].
+    fn last_rx_did_drop_with(self: Pin<&Self>, ctx: DropContext) {
+        let _ = ctx;
+        self.last_rx_did_drop_pinned()
+    }
+
+    /// Called when the last [Rx] is dropped, with an [RxReviver] that
+    /// may reopen the rx half, as a cache might when it would rather
+    /// keep an evicted entry. By default, delegates to
+    /// [Notify::last_rx_did_drop_with].
+    ///
+    /// Only called for payloads allocated by [new], [pin], and the
+    /// other constructors that return a sized [Rx] in the global
+    /// allocator. Other allocations, such as those from [local] or
+    /// [new_in], call [Notify::last_rx_did_drop_with] instead.
+    ///
+    /// See [RxReviver] for how revival interacts with the tx half.
+    #[cfg(feature = "revive")]
+    fn last_rx_did_drop_revivable(self: Pin<&Self>, ctx: DropContext, reviver: &RxReviver<'_, Self>)
+    where
+        Self: Sized,
+    {
+        let _ = reviver;
+        self.last_rx_did_drop_with(ctx)
+    }
+
+    /// Called right before the payload is dropped. By default,
+    /// delegates to [Notify::will_deallocate].
+    fn will_deallocate_pinned(self: Pin<&Self>) {
+        self.get_ref().will_deallocate()
+    }
+
+    /// Called right before the payload is dropped, once the last [Tx]
+    /// and the last [Rx] are gone, in whichever order they went.
+    ///
+    /// WARNING: This function is called during a [Drop::drop]
+    /// implementation. To avoid deadlock, ensure that it does not
+    /// acquire a lock that may be held during unwinding.
+    ///
+    /// NOTE: Not called if the payload is moved out, as by
+    /// [Tx::into_inner], or was never shared, as with a
+    /// [UniqueHandle].
+    fn will_deallocate(&self) {}
+
+    /// Called after a [Tx] is cloned or otherwise created from an
+    /// existing reference.
+    ///
+    /// Observer methods are for debugging, such as tracking down
+    /// leaked handles. They only exist with the `observer` feature.
+    /// Without it, clones and drops do no extra work. With it, the
+    /// default methods are empty and inline away, so only payloads
+    /// that implement them pay for them.
+    #[cfg(feature = "observer")]
+    fn tx_did_clone(&self) {}
+
+    /// Called after an [Rx] is cloned or otherwise created from an
+    /// existing reference. See [Notify::tx_did_clone].
+    #[cfg(feature = "observer")]
+    fn rx_did_clone(&self) {}
+
+    /// Called when any [Tx] is dropped, before its reference is
+    /// released. See [Notify::tx_did_clone].
+    #[cfg(feature = "observer")]
+    fn tx_did_drop_one(&self) {}
+
+    /// Called when any [Rx] is dropped, before its reference is
+    /// released. See [Notify::tx_did_clone].
+    #[cfg(feature = "observer")]
+    fn rx_did_drop_one(&self) {}
+}
+
+/// Details about a notification, passed to
+/// [Notify::last_tx_did_drop_with] and [Notify::last_rx_did_drop_with].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DropContext {
+    remaining: u32,
+    pinned: bool,
+}
+
+impl DropContext {
+    /// Returns the number of references to the other half when the
+    /// notification began. Other threads may clone or drop them at
+    /// any time, so it is only approximate.
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// Returns true if the payload was allocated by [pin], [try_pin],
+    /// or [local::pin] and so will never be moved.
+    pub fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+}
+
+impl<T> Notify for [T] {}
+impl Notify for str {}
+impl<T, const N: usize> Notify for [T; N] {}
+
+// The counting logic is shared by the thread-safe handles and the
+// single-threaded handles in [local]. Atomic is the subset of the
+// atomic integer API the counts need, and Backend selects the storage.
+trait Atomic<V> {
+    fn new(v: V) -> Self;
+    fn load(&self, order: Ordering) -> V;
+    fn fetch_add(&self, v: V, order: Ordering) -> V;
+    fn fetch_sub(&self, v: V, order: Ordering) -> V;
+    fn fetch_update<F: FnMut(V) -> Option<V>>(
+        &self,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        f: F,
+    ) -> Result<V, V>;
+    fn fence(order: Ordering);
+}
+
+macro_rules! impl_atomic {
+    ($atomic:ty, $v:ty) => {
+        impl Atomic<$v> for $atomic {
+            fn new(v: $v) -> Self {
+                <$atomic>::new(v)
+            }
+            fn load(&self, order: Ordering) -> $v {
+                <$atomic>::load(self, order)
+            }
+            fn fetch_add(&self, v: $v, order: Ordering) -> $v {
+                <$atomic>::fetch_add(self, v, order)
+            }
+            fn fetch_sub(&self, v: $v, order: Ordering) -> $v {
+                <$atomic>::fetch_sub(self, v, order)
+            }
+            fn fetch_update<F: FnMut($v) -> Option<$v>>(
+                &self,
+                set_order: Ordering,
+                fetch_order: Ordering,
+                f: F,
+            ) -> Result<$v, $v> {
+                <$atomic>::fetch_update(self, set_order, fetch_order, f)
+            }
+            fn fence(order: Ordering) {
+                fence(order)
+            }
+        }
+    };
+}
+
+impl_atomic!(AtomicU32, u32);
+impl_atomic!(AtomicU64, u64);
+impl_atomic!(AtomicUsize, usize);
+
+trait Backend {
+    type Count: Atomic<Packed>;
+    type Weak: Atomic<WeakPacked>;
+
+    /// Wakes threads blocked until the last [Tx] drops.
+    fn wake_tx_closed(_count: &Self::Count) {}
+
+    /// Wakes threads blocked until the last [Rx] drops.
+    fn wake_rx_closed(_count: &Self::Count) {}
+
+    /// Returns the notifications that may revive a half, if the
+    /// handles to this backend's allocations are [Tx] and [Rx].
+    #[cfg(feature = "revive")]
+    fn revival<T: Notify>() -> Option<&'static revive::Revival> {
+        None
+    }
+}
+
+/// Thread-safe counts.
+struct Shared;
+
+impl Backend for Shared {
+    type Count = AtomicPacked;
+    type Weak = AtomicWeak;
+
+    #[cfg(feature = "std")]
+    fn wake_tx_closed(count: &AtomicPacked) {
+        wait::wake_tx_closed(count)
+    }
+
+    #[cfg(feature = "std")]
+    fn wake_rx_closed(count: &AtomicPacked) {
+        wait::wake_rx_closed(count)
+    }
+
+    #[cfg(feature = "revive")]
+    fn revival<T: Notify>() -> Option<&'static revive::Revival> {
+        Some(revive::Revival::of::<T>())
+    }
 }
 
 // Encoding, big-endian:
@@ -75,33 +595,79 @@ pub trait Notify {
 // deallocate.
 //
 // Rust compiles AtomicU64 operations to a CAS loop on 32-bit ARM and
-// x86. That's acceptable.
+// x86. That's acceptable, but the compact-counts feature packs 15-bit
+// counts into an AtomicU32 instead, and shrinks the weak count to
+// match, so the counts take 8 bytes per allocation instead of 16 on
+// 64-bit targets.
+
+#[cfg(not(feature = "compact-counts"))]
+type Packed = u64;
+#[cfg(not(feature = "compact-counts"))]
+type AtomicPacked = AtomicU64;
+#[cfg(not(feature = "compact-counts"))]
+type WeakPacked = usize;
+#[cfg(not(feature = "compact-counts"))]
+type AtomicWeak = AtomicUsize;
 
+#[cfg(not(feature = "compact-counts"))]
 const TX_SHIFT: u8 = 33;
+#[cfg(not(feature = "compact-counts"))]
+const TX_MASK: u32 = (1 << 31) - 1;
+#[cfg(not(feature = "compact-counts"))]
+const RX_MASK: u32 = (1 << 31) - 1;
+
+#[cfg(feature = "compact-counts")]
+type Packed = u32;
+#[cfg(feature = "compact-counts")]
+type AtomicPacked = AtomicU32;
+#[cfg(feature = "compact-counts")]
+type WeakPacked = u32;
+#[cfg(feature = "compact-counts")]
+type AtomicWeak = AtomicU32;
+
+#[cfg(feature = "compact-counts")]
+const TX_SHIFT: u8 = 17;
+#[cfg(feature = "compact-counts")]
+const TX_MASK: u32 = (1 << 15) - 1;
+#[cfg(feature = "compact-counts")]
+const RX_MASK: u32 = (1 << 15) - 1;
+
 const RX_SHIFT: u8 = 2;
 const DC_SHIFT: u8 = 0;
 
-const TX_MASK: u32 = (1 << 31) - 1;
-const RX_MASK: u32 = (1 << 31) - 1;
 const DC_MASK: u8 = 3;
 
-const TX_INC: u64 = 1 << TX_SHIFT;
-const RX_INC: u64 = 1 << RX_SHIFT;
-const DC_INC: u64 = 1 << DC_SHIFT;
-const RC_INIT: u64 = TX_INC + RX_INC; // drop count = 0
+const TX_INC: Packed = 1 << TX_SHIFT;
+const RX_INC: Packed = 1 << RX_SHIFT;
+const DC_INC: Packed = 1 << DC_SHIFT;
+const RC_INIT: Packed = TX_INC + RX_INC; // drop count = 0
 
-fn tx_count(c: u64) -> u32 {
+// Packed is u32 with compact-counts.
+#[allow(clippy::unnecessary_cast)]
+fn tx_count(c: Packed) -> u32 {
     (c >> TX_SHIFT) as u32 & TX_MASK
 }
 
-fn rx_count(c: u64) -> u32 {
+#[allow(clippy::unnecessary_cast)]
+fn rx_count(c: Packed) -> u32 {
     (c >> RX_SHIFT) as u32 & RX_MASK
 }
 
-fn drop_count(c: u64) -> u8 {
+fn drop_count(c: Packed) -> u8 {
     (c >> DC_SHIFT) as u8 & DC_MASK
 }
 
+/// True if the only reference is a single [Tx] and the rx half has
+/// finished notifying.
+fn unique_tx(c: Packed) -> bool {
+    tx_count(c) == 1 && rx_count(c) == 0 && drop_count(c) == 1
+}
+
+/// See [unique_tx].
+fn unique_rx(c: Packed) -> bool {
+    rx_count(c) == 1 && tx_count(c) == 0 && drop_count(c) == 1
+}
+
 // To avoid accidental overflow (mem::forget or a 2-billion entry
 // Vec), which would lead to a user-after-free, we must detect
 // overflow. There are two ranges an overflow that stays within the
@@ -118,14 +684,99 @@ fn drop_count(c: u64) -> u8 {
 // CAS on Apple Silicon and AMD Zen as fast as uncontended increment?
 //
 // Under contention, probably. [TODO: link]
+#[cfg(not(feature = "compact-counts"))]
 const OVERFLOW_PANIC: u32 = 1 << 30;
+#[cfg(not(feature = "compact-counts"))]
 const OVERFLOW_ABORT: u32 = u32::MAX - (1 << 16);
 
-struct SplitCount(AtomicU64);
+// With 15-bit counts, an increment past the mask would carry into the
+// neighboring field, so abort well before it.
+#[cfg(feature = "compact-counts")]
+const OVERFLOW_PANIC: u32 = 1 << 14;
+#[cfg(feature = "compact-counts")]
+const OVERFLOW_ABORT: u32 = (1 << 15) - (1 << 12);
+
+// The most references one update may add or remove, as in
+// Tx::clone_many and weighted handles. Each racing update can
+// overshoot the panic range by this much before it is undone.
+const MAX_BATCH: u32 = OVERFLOW_PANIC >> 10;
+
+/// Without std, panicking while panicking is the portable way to
+/// abort.
+#[cfg(not(feature = "std"))]
+#[cold]
+fn abort() -> ! {
+    struct Abort;
+    impl Drop for Abort {
+        fn drop(&mut self) {
+            panic!("abort")
+        }
+    }
+    let _abort = Abort;
+    panic!("abort")
+}
+
+/// Reports an impossible count transition, which means a handle was
+/// dropped twice or conjured with `from_raw`.
+#[cfg(feature = "debug-checks")]
+#[cold]
+#[inline(never)]
+fn check_failed(what: &str) -> ! {
+    panic!("splitrc: {} (double drop or from_raw misuse?)", what)
+}
+
+struct SplitCount<A>(A);
 
-impl SplitCount {
+impl<A: Atomic<Packed>> SplitCount<A> {
     fn new() -> Self {
-        Self(AtomicU64::new(RC_INIT))
+        Self(A::new(RC_INIT))
+    }
+
+    /// One [Tx], with the rx half already dropped and notified.
+    fn new_tx_only() -> Self {
+        Self(A::new(TX_INC + DC_INC))
+    }
+
+    /// One [Rx], with the tx half already dropped and notified.
+    fn new_rx_only() -> Self {
+        Self(A::new(RX_INC + DC_INC))
+    }
+
+    fn counts(&self) -> Counts {
+        let c = self.0.load(Ordering::Acquire);
+        Counts {
+            tx: tx_count(c),
+            rx: rx_count(c),
+        }
+    }
+
+    fn is_unique_tx(&self) -> bool {
+        // Acquire pairs with the release in inc_drop_count so the
+        // notification's accesses to data happen before ours.
+        unique_tx(self.0.load(Ordering::Acquire))
+    }
+
+    fn is_unique_rx(&self) -> bool {
+        unique_rx(self.0.load(Ordering::Acquire))
+    }
+
+    /// Releases the last [Tx] if it is the only reference. Returns
+    /// false, changing nothing, otherwise.
+    fn release_unique_tx(&self) -> bool {
+        self.0
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |c| {
+                unique_tx(c).then(|| c - TX_INC)
+            })
+            .is_ok()
+    }
+
+    /// See release_unique_tx.
+    fn release_unique_rx(&self) -> bool {
+        self.0
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |c| {
+                unique_rx(c).then(|| c - RX_INC)
+            })
+            .is_ok()
     }
 
     fn inc_tx(&self) {
@@ -133,6 +784,10 @@ impl SplitCount {
         // and passing a reference to another thread is sufficiently
         // fenced, so relaxed is all that's necessary.
         let old = self.0.fetch_add(TX_INC, Ordering::Relaxed);
+        #[cfg(feature = "debug-checks")]
+        if tx_count(old) == 0 {
+            check_failed("cloned a Tx with no live references")
+        }
         if tx_count(old) < OVERFLOW_PANIC {
             return;
         }
@@ -140,7 +795,7 @@ impl SplitCount {
     }
 
     #[cold]
-    fn inc_tx_overflow(&self, old: u64) {
+    fn inc_tx_overflow(&self, old: Packed) {
         if tx_count(old) >= OVERFLOW_ABORT {
             abort()
         } else {
@@ -149,38 +804,61 @@ impl SplitCount {
         }
     }
 
+    /// Increments both counts at once, for [TxRx].
+    fn inc_both(&self) {
+        // See inc_tx.
+        let old = self.0.fetch_add(TX_INC + RX_INC, Ordering::Relaxed);
+        #[cfg(feature = "debug-checks")]
+        if tx_count(old) == 0 || rx_count(old) == 0 {
+            check_failed("cloned a TxRx with no live references")
+        }
+        if tx_count(old) < OVERFLOW_PANIC && rx_count(old) < OVERFLOW_PANIC {
+            return;
+        }
+        self.inc_both_overflow(old)
+    }
+
+    #[cold]
+    fn inc_both_overflow(&self, old: Packed) {
+        if tx_count(old) >= OVERFLOW_ABORT || rx_count(old) >= OVERFLOW_ABORT {
+            abort()
+        } else {
+            self.0.fetch_sub(TX_INC + RX_INC, Ordering::Relaxed);
+            panic!("count overflow")
+        }
+    }
+
     #[inline]
     fn dec_tx(&self) -> DecrementAction {
-        let mut action = DecrementAction::Nothing;
-        self.0
-            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |mut current| {
-                current -= TX_INC;
-                if tx_count(current) == 0 {
-                    // We are the last tx reference. Should we drop or
-                    // notify?
-                    action = if rx_count(current) == 0 {
-                        current += DC_INC;
-                        if drop_count(current) == 1 {
-                            // If drop_count was zero, the other half
-                            // is notifying and will deallocate.
-                            DecrementAction::Nothing
-                        } else {
-                            // If drop count was one, we are the last
-                            // half.
-                            DecrementAction::Drop
-                        }
-                    } else {
-                        DecrementAction::Notify
-                    }
-                } else {
-                    // We don't need to reset `action` because no
-                    // conflicting update will increase tx_count
-                    // again.
-                }
-                Some(current)
-            })
-            .unwrap();
-        action
+        // Release pairs with the acquire fence in dec_last so the last
+        // reference observes every write to data.
+        let old = self.0.fetch_sub(TX_INC, Ordering::Release);
+        #[cfg(feature = "debug-checks")]
+        if tx_count(old) == 0 {
+            check_failed("dropped a Tx whose count was already zero")
+        }
+        if tx_count(old) != 1 {
+            return DecrementAction::Nothing;
+        }
+        self.dec_last(rx_count(old))
+    }
+
+    /// Called after releasing the last reference to one half.
+    /// `other` is the other half's count at the time.
+    #[cold]
+    fn dec_last(&self, other: u32) -> DecrementAction {
+        A::fence(Ordering::Acquire);
+        if other != 0 {
+            return DecrementAction::Notify;
+        }
+        // The other half already released its last reference and
+        // notified, or is notifying. Both halves increment the drop
+        // count when they finish, and the second deallocates.
+        if self.inc_drop_count() {
+            DecrementAction::Drop
+        } else {
+            DecrementAction::Nothing
+        }
     }
 
     fn inc_rx(&self) {
@@ -188,6 +866,10 @@ impl SplitCount {
         // and passing a reference to another thread is sufficiently
         // fenced, so relaxed is all that's necessary.
         let old = self.0.fetch_add(RX_INC, Ordering::Relaxed);
+        #[cfg(feature = "debug-checks")]
+        if rx_count(old) == 0 {
+            check_failed("cloned an Rx with no live references")
+        }
         if rx_count(old) < OVERFLOW_PANIC {
             return;
         }
@@ -195,7 +877,7 @@ impl SplitCount {
     }
 
     #[cold]
-    fn inc_rx_overflow(&self, old: u64) {
+    fn inc_rx_overflow(&self, old: Packed) {
         if rx_count(old) >= OVERFLOW_ABORT {
             abort()
         } else {
@@ -206,174 +888,1646 @@ impl SplitCount {
 
     #[inline]
     fn dec_rx(&self) -> DecrementAction {
-        let mut action = DecrementAction::Nothing;
-        self.0
-            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |mut current| {
-                current -= RX_INC;
-                if rx_count(current) == 0 {
-                    // We are the last rx reference. Should we drop or
-                    // notify?
-                    action = if tx_count(current) == 0 {
-                        // drop_count is either 0 or 1 here
-                        current += DC_INC;
-                        if drop_count(current) == 1 {
-                            // If drop_count was zero, the other half
-                            // is notifying and will deallocate.
-                            DecrementAction::Nothing
-                        } else {
-                            // If drop count was one, we are the last
-                            // half.
-                            DecrementAction::Drop
-                        }
-                    } else {
-                        DecrementAction::Notify
-                    }
-                } else {
-                    // We don't need to reset `action` because no
-                    // conflicting update will increase tx_count
-                    // again.
-                }
-                Some(current)
-            })
-            .unwrap();
-        action
+        // See dec_tx.
+        let old = self.0.fetch_sub(RX_INC, Ordering::Release);
+        #[cfg(feature = "debug-checks")]
+        if rx_count(old) == 0 {
+            check_failed("dropped an Rx whose count was already zero")
+        }
+        if rx_count(old) != 1 {
+            return DecrementAction::Nothing;
+        }
+        self.dec_last(tx_count(old))
     }
 
-    /// Returns true if we should be deallocated.
-    fn inc_drop_count(&self) -> bool {
-        1 == self.0.fetch_add(DC_INC, Ordering::AcqRel)
+    /// Adds `n` tx references at once, for [weighted]. `n` must be
+    /// small next to the panic range.
+    fn inc_tx_by(&self, n: u32) {
+        // See inc_tx.
+        let old = self.0.fetch_add(n as Packed * TX_INC, Ordering::Relaxed);
+        #[cfg(feature = "debug-checks")]
+        if tx_count(old) == 0 {
+            check_failed("cloned a Tx with no live references")
+        }
+        if tx_count(old) < OVERFLOW_PANIC {
+            return;
+        }
+        self.inc_by_overflow(tx_count(old), n as Packed * TX_INC)
     }
-}
-
-enum DecrementAction {
-    Nothing,
-    Notify,
-    Drop,
-}
 
-struct Inner<T> {
-    data: T,
-    // Deref is more common than reference counting, so hint to the
-    // compiler that the count should be stored at the end.
-    count: SplitCount,
-}
+    /// See [SplitCount::inc_tx_by].
+    fn inc_rx_by(&self, n: u32) {
+        let old = self.0.fetch_add(n as Packed * RX_INC, Ordering::Relaxed);
+        #[cfg(feature = "debug-checks")]
+        if rx_count(old) == 0 {
+            check_failed("cloned an Rx with no live references")
+        }
+        if rx_count(old) < OVERFLOW_PANIC {
+            return;
+        }
+        self.inc_by_overflow(rx_count(old), n as Packed * RX_INC)
+    }
 
-fn deallocate<T>(ptr: NonNull<Inner<T>>) {
-    // SAFETY: Reference count is zero. Deallocate and leave the pointer
-    // dangling.
-    drop(unsafe { Box::from_raw(ptr.as_ptr()) });
-}
+    #[cold]
+    fn inc_by_overflow(&self, count: u32, delta: Packed) {
+        if count >= OVERFLOW_ABORT {
+            abort()
+        } else {
+            self.0.fetch_sub(delta, Ordering::Relaxed);
+            panic!("count overflow")
+        }
+    }
 
-/// The write half of a split reference count.
-pub struct Tx<T: Notify> {
-    ptr: NonNull<Inner<T>>,
-    phantom: PhantomData<T>,
-}
+    /// Releases `n` tx references at once, for [weighted].
+    fn dec_tx_by(&self, n: u32) -> DecrementAction {
+        // See dec_tx.
+        let old = self.0.fetch_sub(n as Packed * TX_INC, Ordering::Release);
+        #[cfg(feature = "debug-checks")]
+        if tx_count(old) < n {
+            check_failed("dropped a Tx whose count was already zero")
+        }
+        if tx_count(old) != n {
+            return DecrementAction::Nothing;
+        }
+        self.dec_last(rx_count(old))
+    }
 
-unsafe impl<T: Sync + Send + Notify> Send for Tx<T> {}
-unsafe impl<T: Sync + Send + Notify> Sync for Tx<T> {}
+    /// See [SplitCount::dec_tx_by].
+    fn dec_rx_by(&self, n: u32) -> DecrementAction {
+        let old = self.0.fetch_sub(n as Packed * RX_INC, Ordering::Release);
+        #[cfg(feature = "debug-checks")]
+        if rx_count(old) < n {
+            check_failed("dropped an Rx whose count was already zero")
+        }
+        if rx_count(old) != n {
+            return DecrementAction::Nothing;
+        }
+        self.dec_last(tx_count(old))
+    }
 
-impl<T: Notify> Drop for Tx<T> {
-    fn drop(&mut self) {
-        // SAFETY: We do not create a &mut to Inner.
-        let inner = unsafe { self.ptr.as_ref() };
-        match inner.count.dec_tx() {
-            DecrementAction::Nothing => (),
-            DecrementAction::Notify => {
-                // SAFETY: data is never moved
-                unsafe { Pin::new_unchecked(&inner.data) }.last_tx_did_drop_pinned();
-                if inner.count.inc_drop_count() {
-                    deallocate(self.ptr);
-                }
+    /// Returns true if we should be deallocated.
+    fn inc_drop_count(&self) -> bool {
+        #[cfg(not(feature = "debug-checks"))]
+        return 1 == self.0.fetch_add(DC_INC, Ordering::AcqRel);
+        #[cfg(feature = "debug-checks")]
+        {
+            let old = self.0.fetch_add(DC_INC, Ordering::AcqRel);
+            if drop_count(old) >= 2 {
+                check_failed("a half finished releasing twice")
             }
-            DecrementAction::Drop => {
-                deallocate(self.ptr);
+            if old != DC_INC {
+                return false;
             }
+            // Poisons the drop count, which never legitimately exceeds
+            // two, so any later release is caught above. The counts
+            // stay zero so weak upgrades still fail.
+            self.0.fetch_add(DC_INC, Ordering::Relaxed);
+            true
         }
     }
-}
 
-impl<T: Notify> Clone for Tx<T> {
-    fn clone(&self) -> Self {
-        // SAFETY: We do not create a &mut to Inner.
-        let inner = unsafe { self.ptr.as_ref() };
-        inner.count.inc_tx();
-        Tx { ..*self }
+    /// Increments the tx count if it is nonzero. Returns false if the
+    /// last [Tx] has already been dropped.
+    fn upgrade_tx(&self) -> bool {
+        // Acquire pairs with the release in dec_tx and dec_rx so the
+        // upgraded reference observes the data.
+        self.0
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |current| {
+                let count = tx_count(current);
+                if count == 0 {
+                    return None;
+                }
+                if count >= OVERFLOW_PANIC {
+                    panic!("tx count overflow")
+                }
+                Some(current + TX_INC)
+            })
+            .is_ok()
     }
-}
-
-impl<T: Notify> Deref for Tx<T> {
-    type Target = T;
 
-    fn deref(&self) -> &Self::Target {
-        // SAFETY: We know ptr is valid and do not create &mut.
-        &unsafe { self.ptr.as_ref() }.data
+    /// Increments the rx count if it is nonzero. Returns false if the
+    /// last [Rx] has already been dropped.
+    fn upgrade_rx(&self) -> bool {
+        self.0
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |current| {
+                let count = rx_count(current);
+                if count == 0 {
+                    return None;
+                }
+                if count >= OVERFLOW_PANIC {
+                    panic!("rx count overflow")
+                }
+                Some(current + RX_INC)
+            })
+            .is_ok()
     }
-}
 
-impl<T: Notify> AsRef<T> for Tx<T> {
-    fn as_ref(&self) -> &T {
-        self.deref()
+    /// Counts a [Tx] minted by a [TxReviver] during the tx half's
+    /// notification. If the half is still closed, also counts a
+    /// reference held for the notification until it returns.
+    #[cfg(feature = "revive")]
+    fn revive_tx(&self) {
+        // Only the notification can move the count off zero, and it
+        // has already acquired every prior write, so relaxed suffices.
+        let _ = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                let count = tx_count(current);
+                if count >= OVERFLOW_PANIC {
+                    panic!("tx count overflow")
+                }
+                Some(current + if count == 0 { 2 * TX_INC } else { TX_INC })
+            });
     }
-}
 
-impl<T: Notify> Borrow<T> for Tx<T> {
-    fn borrow(&self) -> &T {
-        self.deref()
+    /// See revive_tx.
+    #[cfg(feature = "revive")]
+    fn revive_rx(&self) {
+        let _ = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                let count = rx_count(current);
+                if count >= OVERFLOW_PANIC {
+                    panic!("rx count overflow")
+                }
+                Some(current + if count == 0 { 2 * RX_INC } else { RX_INC })
+            });
     }
-}
 
-impl<T: Notify + fmt::Debug> fmt::Debug for Tx<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(self.as_ref(), f)
+    /// Moves one reference from tx to rx. Returns None if the rx
+    /// half has already been dropped. Otherwise, returns true if it
+    /// was the last tx reference, in which case the caller must
+    /// notify.
+    fn tx_to_rx(&self) -> Option<bool> {
+        let old = self
+            .0
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+                let count = rx_count(current);
+                if count == 0 {
+                    // Reviving the rx half would notify twice.
+                    return None;
+                }
+                if count >= OVERFLOW_PANIC {
+                    panic!("rx count overflow")
+                }
+                Some(current - TX_INC + RX_INC)
+            })
+            .ok()?;
+        Some(tx_count(old) == 1)
     }
-}
 
-impl<T: Notify + fmt::Display> fmt::Display for Tx<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Display::fmt(self.as_ref(), f)
+    /// Moves one reference from rx to tx. Returns None if the tx
+    /// half has already been dropped. Otherwise, returns true if it
+    /// was the last rx reference, in which case the caller must
+    /// notify.
+    fn rx_to_tx(&self) -> Option<bool> {
+        let old = self
+            .0
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+                let count = tx_count(current);
+                if count == 0 {
+                    // Reviving the tx half would notify twice.
+                    return None;
+                }
+                if count >= OVERFLOW_PANIC {
+                    panic!("tx count overflow")
+                }
+                Some(current - RX_INC + TX_INC)
+            })
+            .ok()?;
+        Some(rx_count(old) == 1)
     }
 }
 
-/// The read half of a split reference count.
-pub struct Rx<T: Notify> {
-    ptr: NonNull<Inner<T>>,
-    phantom: PhantomData<T>,
-}
+// Weak references work like Arc's: all strong references collectively
+// hold one weak reference, released after `data` is dropped. The
+// allocation is freed when the weak count reaches zero.
+//
+// The weak count is stored separately because the split count has no
+// spare bits. Its top bit records whether the payload was pinned at
+// allocation.
+const MAX_WEAK: WeakPacked = WeakPacked::MAX >> 1;
+const WEAK_PINNED: WeakPacked = !MAX_WEAK;
+
+// The counts are the whole header of an allocation without optional
+// features.
+#[cfg(all(not(any(loom, shuttle)), feature = "compact-counts"))]
+const _: () = assert!(
+    mem::size_of::<SplitCount<AtomicPacked>>() + mem::size_of::<WeakCount<AtomicWeak>>() == 8
+);
+
+struct WeakCount<A>(A);
+
+impl<A: Atomic<WeakPacked>> WeakCount<A> {
+    fn new() -> Self {
+        Self(A::new(1))
+    }
+
+    fn inc(&self) {
+        // SAFETY: Like Arc, incrementing from an existing reference
+        // only needs relaxed ordering.
+        let old = self.0.fetch_add(1, Ordering::Relaxed);
+        if old & MAX_WEAK == MAX_WEAK {
+            abort()
+        }
+    }
+
+    /// Returns true if we should be deallocated.
+    fn dec(&self) -> bool {
+        if 1 == self.0.fetch_sub(1, Ordering::Release) & MAX_WEAK {
+            A::fence(Ordering::Acquire);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn load(&self, order: Ordering) -> WeakPacked {
+        self.0.load(order) & MAX_WEAK
+    }
+
+    /// Must be called before the allocation is shared.
+    fn set_pinned(&self) {
+        self.0.fetch_add(WEAK_PINNED, Ordering::Relaxed);
+    }
+
+    fn is_pinned(&self) -> bool {
+        self.0.load(Ordering::Relaxed) & WEAK_PINNED != 0
+    }
+}
+
+/// A snapshot of the number of live [Tx] and [Rx] references.
+///
+/// Other threads may clone or drop references at any time, so the
+/// counts are only approximate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Counts {
+    /// The number of live [Tx] references.
+    pub tx: u32,
+    /// The number of live [Rx] references.
+    pub rx: u32,
+}
+
+enum DecrementAction {
+    Nothing,
+    Notify,
+    Drop,
+}
+
+// repr(C) guarantees that Inner<T> and Inner<U> have the same field
+// offsets when U is an unsized view of T, which makes unsizing a
+// pointer cast.
+#[repr(C)]
+struct Inner<T: ?Sized, B: Backend = Shared, A: Allocator = Global> {
+    count: SplitCount<B::Count>,
+    weak: WeakCount<B::Weak>,
+    #[cfg(feature = "async")]
+    wakers: closed::Wakers,
+    #[cfg(feature = "event-listener")]
+    events: listener::Events,
+    #[cfg(feature = "drop-callbacks")]
+    subscribers: subscribers::Subscribers,
+    #[cfg(feature = "leak-track")]
+    leak: leak::Entry,
+    // Set by new_labeled.
+    #[cfg(feature = "metrics")]
+    metrics_label: Option<&'static str>,
+    // Set for sized payloads allocated in the global allocator, whose
+    // notifications can mint Tx<T> and Rx<T>.
+    #[cfg(feature = "revive")]
+    revival: Option<&'static revive::Revival>,
+    // Frees the allocation. Global is zero-sized.
+    alloc: A,
+    // Dropped when both halves' counts reach zero, which may be before
+    // the allocation is freed if weak references remain.
+    //
+    // Must be last so T may be unsized.
+    data: ManuallyDrop<T>,
+}
+
+/// The offset of `data` within `Inner<T>`.
+fn data_offset<T>() -> usize {
+    let inner = MaybeUninit::<Inner<T>>::uninit();
+    let base = inner.as_ptr();
+    // SAFETY: Computing a field address does not read it, and both
+    // pointers are derived from the same local.
+    unsafe {
+        let data = ptr::addr_of!((*base).data);
+        (data as *const u8).offset_from(base as *const u8) as usize
+    }
+}
+
+impl<T: Notify, B: Backend> Inner<T, B> {
+    fn new(data: T) -> Self {
+        #[allow(unused_mut)]
+        let mut inner = Inner::new_in(data, Global);
+        #[cfg(feature = "revive")]
+        {
+            inner.revival = B::revival::<T>();
+        }
+        inner
+    }
+}
+
+impl<T, B: Backend, A: Allocator> Inner<T, B, A> {
+    fn new_in(data: T, alloc: A) -> Self {
+        Inner {
+            count: SplitCount::new(),
+            weak: WeakCount::new(),
+            #[cfg(feature = "async")]
+            wakers: Default::default(),
+            #[cfg(feature = "event-listener")]
+            events: Default::default(),
+            #[cfg(feature = "drop-callbacks")]
+            subscribers: Default::default(),
+            #[cfg(feature = "leak-track")]
+            leak: leak::Entry::new(core::any::type_name::<T>()),
+            #[cfg(feature = "metrics")]
+            metrics_label: None,
+            #[cfg(feature = "revive")]
+            revival: None,
+            alloc,
+            data: ManuallyDrop::new(data),
+        }
+    }
+}
+
+impl<T: Notify + ?Sized, B: Backend, A: Allocator> Inner<T, B, A> {
+    /// Counts a new [Tx] created from an existing reference.
+    fn inc_tx(&self) {
+        self.count.inc_tx();
+        lifecycle_event!(NonNull::from(self), "clone tx");
+        #[cfg(feature = "observer")]
+        self.data.tx_did_clone();
+    }
+
+    /// Counts a new [Rx] created from an existing reference.
+    fn inc_rx(&self) {
+        self.count.inc_rx();
+        lifecycle_event!(NonNull::from(self), "clone rx");
+        #[cfg(feature = "observer")]
+        self.data.rx_did_clone();
+    }
+}
+
+fn allocate<T: Notify, B: Backend>(data: T) -> NonNull<Inner<T, B>> {
+    let x = Box::new(Inner::new(data));
+    // SAFETY: We just allocated the box, so it's not null.
+    let ptr = unsafe { NonNull::new_unchecked(Box::into_raw(x)) };
+    lifecycle_event!(ptr, "allocate");
+    ptr
+}
+
+fn try_allocate<T: Notify, B: Backend>(data: T) -> Result<NonNull<Inner<T, B>>, AllocError> {
+    let layout = Layout::new::<Inner<T, B>>();
+    // SAFETY: Inner always contains the counts, so layout is not
+    // zero-sized.
+    let ptr = NonNull::new(unsafe { alloc(layout) } as *mut Inner<T, B>).ok_or(AllocError)?;
+    // SAFETY: The allocation is valid for writes of Inner. It has
+    // the layout Box expects when the allocation is later freed.
+    unsafe { ptr.as_ptr().write(Inner::new(data)) };
+    lifecycle_event!(ptr, "allocate");
+    Ok(ptr)
+}
+
+fn try_allocate_in<T, A: Allocator>(
+    data: T,
+    alloc: A,
+) -> Result<NonNull<Inner<T, Shared, A>>, AllocError> {
+    let layout = Layout::new::<Inner<T, Shared, A>>();
+    let ptr = alloc
+        .allocate(layout)
+        .map_err(|_| AllocError)?
+        .cast::<Inner<T, Shared, A>>();
+    // SAFETY: The allocation is valid for writes of Inner and is
+    // freed by release_weak with the same layout.
+    unsafe { ptr.as_ptr().write(Inner::new_in(data, alloc)) };
+    lifecycle_event!(ptr, "allocate");
+    Ok(ptr)
+}
+
+fn drop_tx<T: Notify + ?Sized, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) {
+    #[cfg(feature = "observer")]
+    // SAFETY: We do not create a &mut to Inner.
+    unsafe { ptr.as_ref() }.data.tx_did_drop_one();
+    lifecycle_event!(ptr, "drop tx");
+    release_tx(ptr)
+}
+
+/// Like [drop_tx], but without the observer hook.
+fn release_tx<T: Notify + ?Sized, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) {
+    // SAFETY: We do not create a &mut to Inner.
+    let inner = unsafe { ptr.as_ref() };
+    match inner.count.dec_tx() {
+        DecrementAction::Nothing => (),
+        DecrementAction::Notify => notify_tx(ptr),
+        DecrementAction::Drop => {
+            deallocate(ptr);
+        }
+    }
+}
+
+/// Like [release_tx], but releases `n` references at once.
+fn release_tx_by<T: Notify + ?Sized, B: Backend, A: Allocator>(
+    ptr: NonNull<Inner<T, B, A>>,
+    n: u32,
+) {
+    // SAFETY: We do not create a &mut to Inner.
+    let inner = unsafe { ptr.as_ref() };
+    match inner.count.dec_tx_by(n) {
+        DecrementAction::Nothing => (),
+        DecrementAction::Notify => notify_tx(ptr),
+        DecrementAction::Drop => {
+            deallocate(ptr);
+        }
+    }
+}
+
+/// Called after the last [Tx] is released while [Rx] references
+/// remain.
+fn notify_tx<T: Notify + ?Sized, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) {
+    lifecycle_event!(ptr, "last tx dropped");
+    // SAFETY: We do not create a &mut to Inner.
+    let inner = unsafe { ptr.as_ref() };
+    let ctx = DropContext {
+        remaining: inner.count.counts().rx,
+        pinned: inner.weak.is_pinned(),
+    };
+    let unwind = Unwind {
+        ptr,
+        finish: |ptr| {
+            if !reopened_tx(ptr) {
+                close_tx(ptr);
+                release_notified(ptr);
+            }
+        },
+    };
+    // SAFETY: data is never moved
+    let data = unsafe { Pin::new_unchecked(&*inner.data) };
+    #[cfg(feature = "revive")]
+    match inner.revival {
+        // SAFETY: revival is only set for allocations of the type it
+        // was created for.
+        Some(revival) => unsafe { (revival.last_tx_did_drop)(ptr.cast(), ctx) },
+        None => data.last_tx_did_drop_with(ctx),
+    }
+    #[cfg(not(feature = "revive"))]
+    data.last_tx_did_drop_with(ctx);
+    if reopened_tx(ptr) {
+        mem::forget(unwind);
+        return;
+    }
+    close_tx(ptr);
+    mem::forget(unwind);
+    release_notified(ptr);
+}
+
+/// Returns true if the notification revived the tx half, after
+/// releasing the reference the [TxReviver] held for it. The half is
+/// open again, so it is neither closed nor finished.
+#[cfg_attr(not(feature = "revive"), allow(unused_variables))]
+fn reopened_tx<T: Notify + ?Sized, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) -> bool {
+    #[cfg(feature = "revive")]
+    // SAFETY: We do not create a &mut to Inner.
+    if unsafe { ptr.as_ref() }.count.counts().tx != 0 {
+        release_tx(ptr);
+        return true;
+    }
+    false
+}
+
+/// Tells everything else waiting on the tx half that it closed. Safe
+/// to repeat.
+fn close_tx<T: ?Sized, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) {
+    // SAFETY: We do not create a &mut to Inner.
+    let inner = unsafe { ptr.as_ref() };
+    #[cfg(feature = "drop-callbacks")]
+    inner.subscribers.fire_tx_dropped();
+    #[cfg(feature = "async")]
+    inner.wakers.wake_tx_closed();
+    #[cfg(feature = "event-listener")]
+    inner.events.notify_tx_closed();
+    B::wake_tx_closed(&inner.count.0);
+}
+
+fn drop_rx<T: Notify + ?Sized, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) {
+    #[cfg(feature = "observer")]
+    // SAFETY: We do not create a &mut to Inner.
+    unsafe { ptr.as_ref() }.data.rx_did_drop_one();
+    lifecycle_event!(ptr, "drop rx");
+    release_rx(ptr)
+}
+
+/// Like [drop_rx], but without the observer hook.
+fn release_rx<T: Notify + ?Sized, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) {
+    // SAFETY: We do not create a &mut to Inner.
+    let inner = unsafe { ptr.as_ref() };
+    match inner.count.dec_rx() {
+        DecrementAction::Nothing => (),
+        DecrementAction::Notify => notify_rx(ptr),
+        DecrementAction::Drop => {
+            deallocate(ptr);
+        }
+    }
+}
+
+/// Like [release_rx], but releases `n` references at once.
+fn release_rx_by<T: Notify + ?Sized, B: Backend, A: Allocator>(
+    ptr: NonNull<Inner<T, B, A>>,
+    n: u32,
+) {
+    // SAFETY: We do not create a &mut to Inner.
+    let inner = unsafe { ptr.as_ref() };
+    match inner.count.dec_rx_by(n) {
+        DecrementAction::Nothing => (),
+        DecrementAction::Notify => notify_rx(ptr),
+        DecrementAction::Drop => {
+            deallocate(ptr);
+        }
+    }
+}
+
+/// Called after the last [Rx] is released while [Tx] references
+/// remain.
+fn notify_rx<T: Notify + ?Sized, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) {
+    lifecycle_event!(ptr, "last rx dropped");
+    // SAFETY: We do not create a &mut to Inner.
+    let inner = unsafe { ptr.as_ref() };
+    let ctx = DropContext {
+        remaining: inner.count.counts().tx,
+        pinned: inner.weak.is_pinned(),
+    };
+    let unwind = Unwind {
+        ptr,
+        finish: |ptr| {
+            if !reopened_rx(ptr) {
+                close_rx(ptr);
+                release_notified(ptr);
+            }
+        },
+    };
+    // SAFETY: data is never moved
+    let data = unsafe { Pin::new_unchecked(&*inner.data) };
+    #[cfg(feature = "revive")]
+    match inner.revival {
+        // SAFETY: revival is only set for allocations of the type it
+        // was created for.
+        Some(revival) => unsafe { (revival.last_rx_did_drop)(ptr.cast(), ctx) },
+        None => data.last_rx_did_drop_with(ctx),
+    }
+    #[cfg(not(feature = "revive"))]
+    data.last_rx_did_drop_with(ctx);
+    if reopened_rx(ptr) {
+        mem::forget(unwind);
+        return;
+    }
+    close_rx(ptr);
+    mem::forget(unwind);
+    release_notified(ptr);
+}
+
+/// Returns true if the notification revived the rx half, after
+/// releasing the reference the [RxReviver] held for it. The half is
+/// open again, so it is neither closed nor finished.
+#[cfg_attr(not(feature = "revive"), allow(unused_variables))]
+fn reopened_rx<T: Notify + ?Sized, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) -> bool {
+    #[cfg(feature = "revive")]
+    // SAFETY: We do not create a &mut to Inner.
+    if unsafe { ptr.as_ref() }.count.counts().rx != 0 {
+        release_rx(ptr);
+        return true;
+    }
+    false
+}
+
+/// See [close_tx].
+fn close_rx<T: ?Sized, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) {
+    // SAFETY: We do not create a &mut to Inner.
+    let inner = unsafe { ptr.as_ref() };
+    #[cfg(feature = "drop-callbacks")]
+    inner.subscribers.fire_rx_dropped();
+    #[cfg(feature = "async")]
+    inner.wakers.wake_rx_closed();
+    #[cfg(feature = "event-listener")]
+    inner.events.notify_rx_closed();
+    B::wake_rx_closed(&inner.count.0);
+}
+
+/// Ends the notifying half's hold on the allocation.
+fn release_notified<T: Notify + ?Sized, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) {
+    // SAFETY: We do not create a &mut to Inner.
+    if unsafe { ptr.as_ref() }.count.inc_drop_count() {
+        deallocate(ptr);
+    }
+}
+
+/// Finishes a release if a [Notify] callback or drop subscriber
+/// unwinds, so the allocation is still freed exactly once. Forgotten
+/// once the callbacks return.
+#[cfg_attr(feature = "abort-on-notify-panic", allow(dead_code))]
+struct Unwind<T: ?Sized, B: Backend, A: Allocator> {
+    ptr: NonNull<Inner<T, B, A>>,
+    finish: fn(NonNull<Inner<T, B, A>>),
+}
+
+impl<T: ?Sized, B: Backend, A: Allocator> Drop for Unwind<T, B, A> {
+    fn drop(&mut self) {
+        #[cfg(feature = "abort-on-notify-panic")]
+        abort();
+        #[cfg(not(feature = "abort-on-notify-panic"))]
+        (self.finish)(self.ptr);
+    }
+}
+
+fn deallocate<T: Notify + ?Sized, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) {
+    lifecycle_event!(ptr, "deallocate");
+    let unwind = Unwind {
+        ptr,
+        finish: drop_data,
+    };
+    // SAFETY: We do not create a &mut to Inner, and data is never
+    // moved.
+    unsafe { Pin::new_unchecked(&*ptr.as_ref().data) }.will_deallocate_pinned();
+    mem::forget(unwind);
+    drop_data(ptr);
+}
+
+/// Like [deallocate], but without calling [Notify::will_deallocate].
+fn drop_data<T: ?Sized, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) {
+    wake_closed(ptr);
+    // SAFETY: Both reference counts are zero, so nobody else can
+    // observe data. Weak references only touch the counts.
+    unsafe { ManuallyDrop::drop(&mut *ptr::addr_of_mut!((*ptr.as_ptr()).data)) };
+    release_weak(ptr);
+}
+
+/// Like [deallocate], but moves data out instead of dropping it.
+fn take_data<T, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) -> T {
+    lifecycle_event!(ptr, "deallocate");
+    wake_closed(ptr);
+    // SAFETY: Both reference counts are zero, so nobody else can
+    // observe data, and it is never read again.
+    let data = unsafe { ptr::read(ptr::addr_of!((*ptr.as_ptr()).data)) };
+    release_weak(ptr);
+    ManuallyDrop::into_inner(data)
+}
+
+#[cfg_attr(
+    not(any(
+        feature = "async",
+        feature = "event-listener",
+        feature = "drop-callbacks"
+    )),
+    allow(unused_variables)
+)]
+fn wake_closed<T: ?Sized, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) {
+    #[cfg(any(
+        feature = "async",
+        feature = "event-listener",
+        feature = "drop-callbacks"
+    ))]
+    {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { ptr.as_ref() };
+        // Either half may have closed without waking while the other
+        // half notified.
+        #[cfg(feature = "async")]
+        {
+            inner.wakers.wake_tx_closed();
+            inner.wakers.wake_rx_closed();
+        }
+        #[cfg(feature = "event-listener")]
+        {
+            inner.events.notify_tx_closed();
+            inner.events.notify_rx_closed();
+        }
+        #[cfg(feature = "drop-callbacks")]
+        {
+            inner.subscribers.fire_tx_dropped();
+            inner.subscribers.fire_rx_dropped();
+        }
+    }
+}
+
+fn release_weak<T: ?Sized, B: Backend, A: Allocator>(ptr: NonNull<Inner<T, B, A>>) {
+    // SAFETY: data may have been dropped, so only reference the weak
+    // count.
+    if unsafe { &(*ptr.as_ptr()).weak }.dec() {
+        // SAFETY: Weak count is zero and data has already been
+        // dropped. Move the allocator out, deallocate, and leave the
+        // pointer dangling.
+        unsafe {
+            #[cfg(feature = "async")]
+            ptr::drop_in_place(ptr::addr_of_mut!((*ptr.as_ptr()).wakers));
+            #[cfg(feature = "event-listener")]
+            ptr::drop_in_place(ptr::addr_of_mut!((*ptr.as_ptr()).events));
+            #[cfg(feature = "drop-callbacks")]
+            ptr::drop_in_place(ptr::addr_of_mut!((*ptr.as_ptr()).subscribers));
+            #[cfg(feature = "leak-track")]
+            ptr::drop_in_place(ptr::addr_of_mut!((*ptr.as_ptr()).leak));
+            let layout = Layout::for_value(&*ptr.as_ptr());
+            let alloc = ptr::read(ptr::addr_of!((*ptr.as_ptr()).alloc));
+            alloc.deallocate(ptr.cast(), layout);
+        }
+    }
+}
+
+/// The write half of a split reference count.
+///
+/// A handle is one non-null pointer to the allocation, so
+/// `Option<Tx<T>>` is pointer-sized. The payload sits at a fixed
+/// offset from it: see [Tx::into_raw] and [Tx::option_into_raw].
+///
+/// Like [Arc](alloc::sync::Arc), `Tx<T>` is covariant in `T`, so a
+/// `Tx<&'static str>` can be used as a `Tx<&'a str>`. It owns the
+/// payload for drop check, so anything the payload borrows must
+/// outlive every handle.
+#[repr(transparent)]
+pub struct Tx<T: Notify + ?Sized, A: Allocator = Global> {
+    ptr: NonNull<Inner<T, Shared, A>>,
+    // Owns the allocation and allocator, and makes Tx covariant in T.
+    // Inner::data is ManuallyDrop, so this marker does not own T for
+    // drop check; the Drop impl below, which cannot be may_dangle,
+    // does.
+    phantom: PhantomData<Inner<T, Shared, A>>,
+}
+
+unsafe impl<T: Sync + Send + Notify + ?Sized, A: Allocator + Send + Sync> Send for Tx<T, A> {}
+unsafe impl<T: Sync + Send + Notify + ?Sized, A: Allocator + Send + Sync> Sync for Tx<T, A> {}
+
+// Like Arc, a panic cannot leave the counts inconsistent, so only the
+// payload and allocator matter.
+impl<T: RefUnwindSafe + Notify + ?Sized, A: Allocator + UnwindSafe> UnwindSafe for Tx<T, A> {}
+impl<T: RefUnwindSafe + Notify + ?Sized, A: Allocator + RefUnwindSafe> RefUnwindSafe for Tx<T, A> {}
+
+// Handles are a single non-null pointer, so Option<Tx> and Option<Rx>
+// are pointer-sized. Tx::option_into_raw and Tx::option_from_raw
+// rely on this to store optional handles in an AtomicPtr.
+const _: () = {
+    assert!(mem::size_of::<Option<Tx<()>>>() == mem::size_of::<*const ()>());
+    assert!(mem::size_of::<Option<Rx<()>>>() == mem::size_of::<*const ()>());
+    assert!(mem::align_of::<Tx<()>>() == mem::align_of::<*const ()>());
+    assert!(mem::align_of::<Rx<()>>() == mem::align_of::<*const ()>());
+};
+
+// Unlike Arc, this cannot be `#[may_dangle] T` under the nightly
+// feature: dropping the last Tx runs Notify::last_tx_did_drop, which
+// may read borrows in the payload, so they must outlive the handle.
+impl<T: Notify + ?Sized, A: Allocator> Drop for Tx<T, A> {
+    fn drop(&mut self) {
+        drop_tx(self.ptr)
+    }
+}
+
+impl<T: Notify + ?Sized> Tx<T> {
+    /// Creates a [TxWeak] pointing to this allocation.
+    ///
+    /// The weak reference does not keep the tx half alive, but
+    /// can be upgraded while any [Tx] remains. For pinned handles,
+    /// see [Tx::downgrade_pinned].
+    pub fn downgrade(this: &Self) -> TxWeak<T> {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        inner.weak.inc();
+        TxWeak {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns true if both handles point to the same allocation.
+    ///
+    /// Like [alloc::sync::Arc::ptr_eq], only addresses are compared, not
+    /// pointer metadata.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr.cast::<u8>() == other.ptr.cast::<u8>()
+    }
+
+    /// Returns the address of the payload, which identifies the
+    /// allocation. [Tx] and [Rx] handles to the same allocation
+    /// return the same address. See [ByAddress].
+    pub fn addr(this: &Self) -> usize {
+        &**this as *const T as *const u8 as usize
+    }
+
+    /// Returns the allocation's identity. See [SplitrcId].
+    pub fn id(this: &Self) -> SplitrcId {
+        SplitrcId::from_addr(Tx::addr(this))
+    }
+
+    /// Returns the number of live [Tx] references. See [Counts].
+    pub fn tx_count(this: &Self) -> u32 {
+        Self::counts(this).tx
+    }
+
+    /// Returns the number of live [Rx] references. See [Counts].
+    pub fn rx_count(this: &Self) -> u32 {
+        Self::counts(this).rx
+    }
+
+    /// Returns a snapshot of both reference counts.
+    pub fn counts(this: &Self) -> Counts {
+        // SAFETY: We do not create a &mut to Inner.
+        unsafe { this.ptr.as_ref() }.count.counts()
+    }
+
+    /// Returns a mutable reference to the payload if this is the
+    /// last reference of either kind, the rx half has been dropped
+    /// and notified, and no weak references remain.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        // SAFETY: We do not create a &mut to Inner yet.
+        let inner = unsafe { this.ptr.as_ref() };
+        // Downgrading requires a Tx, and this is the only one, so
+        // neither count can change once both are checked.
+        if inner.weak.load(Ordering::Acquire) != 1 || !inner.count.is_unique_tx() {
+            return None;
+        }
+        // SAFETY: No other reference can observe data.
+        Some(unsafe { &mut *ptr::addr_of_mut!((*this.ptr.as_ptr()).data) })
+    }
+
+    /// Returns a pointer to the payload without consuming the handle.
+    ///
+    /// The pointer carries the provenance of the whole allocation,
+    /// not just the payload, so it may round-trip through C and be
+    /// offset back to the header, as [Tx::from_raw] does. It is valid
+    /// while any handle keeps the payload alive, and every [Tx] and
+    /// [Rx] to the same allocation returns the same pointer. Writing
+    /// through it requires interior mutability in `T`.
+    pub fn as_ptr(this: &Self) -> *const T {
+        // SAFETY: Computing a field address does not create a
+        // reference. ManuallyDrop<T> is repr(transparent).
+        unsafe { ptr::addr_of!((*this.ptr.as_ptr()).data) as *const T }
+    }
+
+    /// Consumes the handle, returning a pointer to the payload.
+    ///
+    /// The tx count is not decremented. Use [Tx::from_raw] to
+    /// reclaim the handle.
+    pub fn into_raw(this: Self) -> *const T {
+        Tx::as_ptr(&ManuallyDrop::new(this))
+    }
+
+    /// Converts this handle into an [Rx] with a single atomic update.
+    ///
+    /// If this was the last [Tx], [Notify::last_tx_did_drop] is
+    /// called as if it had been dropped.
+    ///
+    /// Returns the handle unchanged if the last [Rx] has already
+    /// been dropped, because the rx half cannot be revived.
+    pub fn into_rx(this: Self) -> Result<Rx<T>, Self> {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        let last = match inner.count.tx_to_rx() {
+            Some(last) => last,
+            None => return Err(this),
+        };
+        let ptr = this.ptr;
+        mem::forget(this);
+        if last {
+            // The new Rx keeps the allocation alive.
+            notify_tx(ptr);
+        }
+        Ok(Rx {
+            ptr,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<T: Notify> Tx<T> {
+    /// Returns the payload if this is the only reference: the last
+    /// [Tx], with every [Rx] dropped and notified. Otherwise,
+    /// returns the handle unchanged.
+    ///
+    /// Weak references may remain but can no longer upgrade.
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        if !inner.count.release_unique_tx() {
+            return Err(this);
+        }
+        let ptr = this.ptr;
+        mem::forget(this);
+        Ok(take_data(ptr))
+    }
+
+    /// Drops the handle, returning the payload if it was the last
+    /// reference of either kind.
+    ///
+    /// If this was the last [Tx] while the rx half is still
+    /// notifying, the rx half drops the payload and this returns
+    /// None.
+    pub fn into_inner(this: Self) -> Option<T> {
+        let ptr = this.ptr;
+        mem::forget(this);
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { ptr.as_ref() };
+        match inner.count.dec_tx() {
+            DecrementAction::Nothing => None,
+            DecrementAction::Notify => {
+                notify_tx(ptr);
+                None
+            }
+            DecrementAction::Drop => Some(take_data(ptr)),
+        }
+    }
+
+    /// Returns a mutable reference to the payload, first cloning it
+    /// into a new allocation unless [Tx::get_mut] would succeed.
+    ///
+    /// The copy has no [Rx] half: it starts as if the rx half had
+    /// already been dropped and notified, so its
+    /// [Notify::last_rx_did_drop] is never called. Dropping this
+    /// handle's reference to the original allocation may notify the
+    /// remaining [Rx] handles as usual.
+    pub fn make_mut(this: &mut Self) -> &mut T
+    where
+        T: Clone,
+    {
+        if Self::get_mut(this).is_none() {
+            let ptr = allocate((**this).clone());
+            // SAFETY: Nobody else can observe the new allocation.
+            unsafe { (*ptr.as_ptr()).count = SplitCount::new_tx_only() };
+            *this = Tx {
+                ptr,
+                phantom: PhantomData,
+            };
+        }
+        // SAFETY: This is the only reference.
+        unsafe { &mut *ptr::addr_of_mut!((*this.ptr.as_ptr()).data) }
+    }
+
+    /// Converts into a handle to an unsized view of the payload, such
+    /// as a trait object or slice.
+    ///
+    /// `f` is given a pointer to the payload and must return it
+    /// unsized with an `as` cast. With the `nightly` feature, handles
+    /// also coerce implicitly.
+    ///
+    /// ```
+    /// use std::fmt::Debug;
+    ///
+    /// trait Payload: Debug + splitrc::Notify {}
+    ///
+    /// #[derive(Debug)]
+    /// struct MyValue;
+    /// impl splitrc::Notify for MyValue {}
+    /// impl Payload for MyValue {}
+    ///
+    /// let (tx, rx) = splitrc::new(MyValue);
+    /// let tx: splitrc::Tx<dyn Payload> =
+    ///     unsafe { splitrc::Tx::unsize(tx, |p| p as *const dyn Payload) };
+    /// assert_eq!("MyValue", format!("{:?}", tx));
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `f` must not dereference its argument, and must return the
+    /// same pointer with only metadata added.
+    pub unsafe fn unsize<U: Notify + ?Sized>(
+        this: Self,
+        f: impl FnOnce(*const T) -> *const U,
+    ) -> Tx<U> {
+        let this = ManuallyDrop::new(this);
+        // Pointer metadata depends only on the type, and Inner is
+        // repr(C), so apply the cast to the Inner pointer directly.
+        let ptr = f(this.ptr.as_ptr() as *const T) as *mut Inner<U>;
+        Tx {
+            ptr: NonNull::new_unchecked(ptr),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Reconstructs a handle from a pointer returned by
+    /// [Tx::into_raw].
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [Tx::into_raw] for the same
+    /// `T`, and each call to `from_raw` must be balanced by a call to
+    /// `into_raw` or [Tx::increment_tx_count].
+    pub unsafe fn from_raw(ptr: *const T) -> Self {
+        let inner = (ptr as *const u8).sub(data_offset::<T>()) as *mut Inner<T>;
+        Tx {
+            ptr: NonNull::new_unchecked(inner),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Like [Tx::into_raw], but maps [None] to a null pointer, so an
+    /// optional handle fits in an `AtomicPtr`.
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicPtr, Ordering};
+    ///
+    /// struct Job;
+    /// impl splitrc::Notify for Job {}
+    ///
+    /// let (tx, _rx) = splitrc::new(Job);
+    /// let slot = AtomicPtr::new(splitrc::Tx::option_into_raw(Some(tx)) as *mut Job);
+    /// let old = slot.swap(std::ptr::null_mut(), Ordering::AcqRel);
+    /// let tx = unsafe { splitrc::Tx::option_from_raw(old) };
+    /// assert!(tx.is_some());
+    /// ```
+    pub fn option_into_raw(this: Option<Self>) -> *const T {
+        this.map_or(ptr::null(), Tx::into_raw)
+    }
+
+    /// Like [Tx::from_raw], but maps a null pointer to [None].
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be null or satisfy the requirements of
+    /// [Tx::from_raw].
+    pub unsafe fn option_from_raw(ptr: *const T) -> Option<Self> {
+        if ptr.is_null() {
+            None
+        } else {
+            Some(Tx::from_raw(ptr))
+        }
+    }
+
+    /// Increments the tx count of the allocation behind a pointer
+    /// returned by [Tx::into_raw].
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [Tx::into_raw] for the same
+    /// `T`, and the handle it represents must not have been reclaimed.
+    pub unsafe fn increment_tx_count(ptr: *const T) {
+        mem::forget(Tx::clone(&ManuallyDrop::new(Tx::from_raw(ptr))));
+    }
+
+    /// Decrements the tx count of the allocation behind a pointer
+    /// returned by [Tx::into_raw], notifying or deallocating as if a
+    /// handle was dropped.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [Tx::into_raw] for the same
+    /// `T`, and the tx count must be at least one.
+    pub unsafe fn decrement_tx_count(ptr: *const T) {
+        drop(Tx::from_raw(ptr))
+    }
+}
+
+impl<T: Notify + ?Sized, A: Allocator> Tx<T, A> {
+    /// Returns the allocator that holds this allocation.
+    pub fn allocator(this: &Self) -> &A {
+        // SAFETY: We do not create a &mut to Inner.
+        &unsafe { this.ptr.as_ref() }.alloc
+    }
+
+    /// Consumes the handle without releasing its reference, returning
+    /// a reference to the payload that lives for the rest of the
+    /// program.
+    ///
+    /// The tx half never closes, so [Notify::last_tx_did_drop]
+    /// is never called and the payload is never dropped. The [Rx]
+    /// half is unaffected: dropping its last handle still notifies.
+    /// To keep the allocation entirely silent, leak it too with
+    /// [Rx::leak].
+    ///
+    /// ```
+    /// # struct Config {}
+    /// # impl splitrc::Notify for Config {}
+    /// let (tx, rx) = splitrc::new(Config {});
+    /// let config: &'static Config = splitrc::Tx::leak(tx);
+    /// splitrc::Rx::leak(rx);
+    /// # let _ = config;
+    /// ```
+    pub fn leak(this: Self) -> &'static T
+    where
+        T: 'static,
+        A: 'static,
+    {
+        let ptr = ManuallyDrop::new(this).ptr;
+        // SAFETY: The forgotten reference keeps the payload alive,
+        // and the allocation is never freed.
+        &unsafe { ptr.as_ref() }.data
+    }
+
+    /// Returns an iterator over `n` new handles, counted with one
+    /// atomic update rather than one per handle.
+    ///
+    /// Very large batches take one update per million or so handles.
+    /// Handles the iterator has not yet yielded are released when it
+    /// is dropped.
+    ///
+    /// # Panics
+    ///
+    /// If the tx count would overflow, in which case no handles are
+    /// created.
+    ///
+    /// ```
+    /// # struct MyValue {}
+    /// # impl splitrc::Notify for MyValue {}
+    /// let (tx, rx) = splitrc::new(MyValue {});
+    /// let workers: Vec<_> = splitrc::Tx::clone_many(&tx, 100).collect();
+    /// assert_eq!(101, splitrc::Tx::tx_count(&tx));
+    /// splitrc::Tx::drop_many(workers);
+    /// # drop(rx);
+    /// ```
+    pub fn clone_many(this: &Self, n: usize) -> impl ExactSizeIterator<Item = Tx<T, A>> {
+        batch::TxBatch::clone_from(this, n)
+    }
+
+    /// Drops `handles`, releasing consecutive handles to the same
+    /// allocation with one atomic update.
+    pub fn drop_many(handles: impl IntoIterator<Item = Tx<T, A>>) {
+        batch::drop_many_tx(handles)
+    }
+}
+
+impl<T: Notify + ?Sized, A: Allocator> Clone for Tx<T, A> {
+    fn clone(&self) -> Self {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { self.ptr.as_ref() };
+        inner.inc_tx();
+        Tx { ..*self }
+    }
+}
+
+impl<T: Notify + ?Sized, A: Allocator> Deref for Tx<T, A> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: We know ptr is valid and do not create &mut.
+        &unsafe { self.ptr.as_ref() }.data
+    }
+}
+
+impl<T: Notify + ?Sized> AsRef<T> for Tx<T> {
+    fn as_ref(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + ?Sized> Borrow<T> for Tx<T> {
+    fn borrow(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + fmt::Debug + ?Sized, A: Allocator> fmt::Debug for Tx<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: Notify + fmt::Display + ?Sized, A: Allocator> fmt::Display for Tx<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<T: Notify + ?Sized, A: Allocator> fmt::Pointer for Tx<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&(&**self as *const T), f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Notify + std::error::Error + ?Sized, A: Allocator> std::error::Error for Tx<T, A> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        (**self).source()
+    }
+}
+
+impl<T: Notify + PartialEq + ?Sized, A: Allocator> PartialEq for Tx<T, A> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Notify + PartialEq + ?Sized, A: Allocator> PartialEq<Rx<T, A>> for Tx<T, A> {
+    fn eq(&self, other: &Rx<T, A>) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Notify + Eq + ?Sized, A: Allocator> Eq for Tx<T, A> {}
+
+impl<T: Notify + PartialOrd + ?Sized, A: Allocator> PartialOrd for Tx<T, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<T: Notify + Ord + ?Sized, A: Allocator> Ord for Tx<T, A> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<T: Notify + Hash + ?Sized, A: Allocator> Hash for Tx<T, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+/// A weak reference to the write half of a split reference count.
+///
+/// Does not keep the payload alive. Obtained from [Tx::downgrade].
+pub struct TxWeak<T: Notify + ?Sized> {
+    ptr: NonNull<Inner<T>>,
+    phantom: PhantomData<T>,
+}
+
+unsafe impl<T: Sync + Send + Notify + ?Sized> Send for TxWeak<T> {}
+unsafe impl<T: Sync + Send + Notify + ?Sized> Sync for TxWeak<T> {}
+
+impl<T: RefUnwindSafe + Notify + ?Sized> UnwindSafe for TxWeak<T> {}
+impl<T: RefUnwindSafe + Notify + ?Sized> RefUnwindSafe for TxWeak<T> {}
+
+impl<T: Notify + ?Sized> TxWeak<T> {
+    /// Returns the allocation's identity, the same as [Tx::id]
+    /// on its strong handles. The allocation lives at least as long as
+    /// this weak reference.
+    pub fn id(&self) -> SplitrcId {
+        // SAFETY: Computing a field address does not create a
+        // reference, and the weak reference keeps the allocation.
+        SplitrcId::from_addr(
+            unsafe { ptr::addr_of!((*self.ptr.as_ptr()).data) } as *const u8 as usize,
+        )
+    }
+
+    /// Attempts to upgrade to a [Tx]. Returns [None] if the last
+    /// [Tx] has been dropped, even if [Rx] references remain.
+    pub fn upgrade(&self) -> Option<Tx<T>> {
+        // SAFETY: The weak count keeps the counts allocated. data
+        // may have been dropped, so only reference the count.
+        let count = unsafe { &(*self.ptr.as_ptr()).count };
+        if count.upgrade_tx() {
+            // SAFETY: The new reference keeps data alive.
+            #[cfg(feature = "observer")]
+            unsafe { &(*self.ptr.as_ptr()).data }.tx_did_clone();
+            Some(Tx {
+                ptr: self.ptr,
+                phantom: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Notify + ?Sized> Drop for TxWeak<T> {
+    fn drop(&mut self) {
+        release_weak(self.ptr);
+    }
+}
+
+impl<T: Notify + ?Sized> Clone for TxWeak<T> {
+    fn clone(&self) -> Self {
+        // SAFETY: data may have been dropped, so only reference the
+        // weak count.
+        unsafe { &(*self.ptr.as_ptr()).weak }.inc();
+        TxWeak { ..*self }
+    }
+}
+
+impl<T: Notify + ?Sized> fmt::Debug for TxWeak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(TxWeak)")
+    }
+}
+
+/// The read half of a split reference count.
+///
+/// Has the same layout, variance, and drop check as [Tx].
+#[repr(transparent)]
+pub struct Rx<T: Notify + ?Sized, A: Allocator = Global> {
+    ptr: NonNull<Inner<T, Shared, A>>,
+    phantom: PhantomData<Inner<T, Shared, A>>,
+}
+
+unsafe impl<T: Sync + Send + Notify + ?Sized, A: Allocator + Send + Sync> Send for Rx<T, A> {}
+unsafe impl<T: Sync + Send + Notify + ?Sized, A: Allocator + Send + Sync> Sync for Rx<T, A> {}
 
-unsafe impl<T: Sync + Send + Notify> Send for Rx<T> {}
-unsafe impl<T: Sync + Send + Notify> Sync for Rx<T> {}
+impl<T: RefUnwindSafe + Notify + ?Sized, A: Allocator + UnwindSafe> UnwindSafe for Rx<T, A> {}
+impl<T: RefUnwindSafe + Notify + ?Sized, A: Allocator + RefUnwindSafe> RefUnwindSafe for Rx<T, A> {}
 
-impl<T: Notify> Drop for Rx<T> {
+// See Drop for Tx.
+impl<T: Notify + ?Sized, A: Allocator> Drop for Rx<T, A> {
     fn drop(&mut self) {
+        drop_rx(self.ptr)
+    }
+}
+
+impl<T: Notify + ?Sized> Rx<T> {
+    /// Creates a [RxWeak] pointing to this allocation.
+    ///
+    /// The weak reference does not keep the rx half alive, but
+    /// can be upgraded while any [Rx] remains.
+    pub fn downgrade(this: &Self) -> RxWeak<T> {
         // SAFETY: We do not create a &mut to Inner.
-        let inner = unsafe { self.ptr.as_ref() };
+        let inner = unsafe { this.ptr.as_ref() };
+        inner.weak.inc();
+        RxWeak {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns true if both handles point to the same allocation.
+    ///
+    /// Like [alloc::sync::Arc::ptr_eq], only addresses are compared, not
+    /// pointer metadata.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr.cast::<u8>() == other.ptr.cast::<u8>()
+    }
+
+    /// Returns the address of the payload, which identifies the
+    /// allocation. [Rx] and [Tx] handles to the same allocation
+    /// return the same address. See [ByAddress].
+    pub fn addr(this: &Self) -> usize {
+        &**this as *const T as *const u8 as usize
+    }
+
+    /// Returns the allocation's identity. See [SplitrcId].
+    pub fn id(this: &Self) -> SplitrcId {
+        SplitrcId::from_addr(Rx::addr(this))
+    }
+
+    /// Returns the number of live [Tx] references. See [Counts].
+    pub fn tx_count(this: &Self) -> u32 {
+        Self::counts(this).tx
+    }
+
+    /// Returns the number of live [Rx] references. See [Counts].
+    pub fn rx_count(this: &Self) -> u32 {
+        Self::counts(this).rx
+    }
+
+    /// Returns a snapshot of both reference counts.
+    pub fn counts(this: &Self) -> Counts {
+        // SAFETY: We do not create a &mut to Inner.
+        unsafe { this.ptr.as_ref() }.count.counts()
+    }
+
+    /// Returns a mutable reference to the payload if this is the
+    /// last reference of either kind, the tx half has been dropped
+    /// and notified, and no weak references remain.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        // SAFETY: We do not create a &mut to Inner yet.
+        let inner = unsafe { this.ptr.as_ref() };
+        // Downgrading requires a Rx, and this is the only one, so
+        // neither count can change once both are checked.
+        if inner.weak.load(Ordering::Acquire) != 1 || !inner.count.is_unique_rx() {
+            return None;
+        }
+        // SAFETY: No other reference can observe data.
+        Some(unsafe { &mut *ptr::addr_of_mut!((*this.ptr.as_ptr()).data) })
+    }
+
+    /// Returns a pointer to the payload without consuming the handle.
+    /// See [Tx::as_ptr].
+    pub fn as_ptr(this: &Self) -> *const T {
+        // SAFETY: Computing a field address does not create a
+        // reference. ManuallyDrop<T> is repr(transparent).
+        unsafe { ptr::addr_of!((*this.ptr.as_ptr()).data) as *const T }
+    }
+
+    /// Consumes the handle, returning a pointer to the payload.
+    ///
+    /// The rx count is not decremented. Use [Rx::from_raw] to
+    /// reclaim the handle.
+    pub fn into_raw(this: Self) -> *const T {
+        Rx::as_ptr(&ManuallyDrop::new(this))
+    }
+
+    /// Converts this handle into a [Tx] with a single atomic update.
+    ///
+    /// If this was the last [Rx], [Notify::last_rx_did_drop] is
+    /// called as if it had been dropped.
+    ///
+    /// Returns the handle unchanged if the last [Tx] has already
+    /// been dropped, because the tx half cannot be revived.
+    pub fn into_tx(this: Self) -> Result<Tx<T>, Self> {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        let last = match inner.count.rx_to_tx() {
+            Some(last) => last,
+            None => return Err(this),
+        };
+        let ptr = this.ptr;
+        mem::forget(this);
+        if last {
+            // The new Tx keeps the allocation alive.
+            notify_rx(ptr);
+        }
+        Ok(Tx {
+            ptr,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<T: Notify> Rx<T> {
+    /// Returns the payload if this is the only reference: the last
+    /// [Rx], with every [Tx] dropped and notified. Otherwise,
+    /// returns the handle unchanged.
+    ///
+    /// Weak references may remain but can no longer upgrade.
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        if !inner.count.release_unique_rx() {
+            return Err(this);
+        }
+        let ptr = this.ptr;
+        mem::forget(this);
+        Ok(take_data(ptr))
+    }
+
+    /// Drops the handle, returning the payload if it was the last
+    /// reference of either kind.
+    ///
+    /// If this was the last [Rx] while the tx half is still
+    /// notifying, the tx half drops the payload and this returns
+    /// None.
+    pub fn into_inner(this: Self) -> Option<T> {
+        let ptr = this.ptr;
+        mem::forget(this);
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { ptr.as_ref() };
         match inner.count.dec_rx() {
-            DecrementAction::Nothing => (),
+            DecrementAction::Nothing => None,
             DecrementAction::Notify => {
-                // SAFETY: data is never moved
-                unsafe { Pin::new_unchecked(&inner.data) }.last_rx_did_drop_pinned();
-                if inner.count.inc_drop_count() {
-                    deallocate(self.ptr);
-                }
-            }
-            DecrementAction::Drop => {
-                deallocate(self.ptr);
+                notify_rx(ptr);
+                None
             }
+            DecrementAction::Drop => Some(take_data(ptr)),
+        }
+    }
+
+    /// Returns a mutable reference to the payload, first cloning it
+    /// into a new allocation unless [Rx::get_mut] would succeed.
+    ///
+    /// The copy has no [Tx] half: it starts as if the tx half had
+    /// already been dropped and notified, so its
+    /// [Notify::last_tx_did_drop] is never called. Dropping this
+    /// handle's reference to the original allocation may notify the
+    /// remaining [Tx] handles as usual.
+    pub fn make_mut(this: &mut Self) -> &mut T
+    where
+        T: Clone,
+    {
+        if Self::get_mut(this).is_none() {
+            let ptr = allocate((**this).clone());
+            // SAFETY: Nobody else can observe the new allocation.
+            unsafe { (*ptr.as_ptr()).count = SplitCount::new_rx_only() };
+            *this = Rx {
+                ptr,
+                phantom: PhantomData,
+            };
+        }
+        // SAFETY: This is the only reference.
+        unsafe { &mut *ptr::addr_of_mut!((*this.ptr.as_ptr()).data) }
+    }
+
+    /// Converts into a handle to an unsized view of the payload, such
+    /// as a trait object or slice.
+    ///
+    /// `f` is given a pointer to the payload and must return it
+    /// unsized with an `as` cast.
+    ///
+    /// ```
+    /// use std::fmt::Debug;
+    ///
+    /// trait Payload: Debug + splitrc::Notify {}
+    ///
+    /// #[derive(Debug)]
+    /// struct MyValue;
+    /// impl splitrc::Notify for MyValue {}
+    /// impl Payload for MyValue {}
+    ///
+    /// let (tx, rx) = splitrc::new(MyValue);
+    /// let rx: splitrc::Rx<dyn Payload> =
+    ///     unsafe { splitrc::Rx::unsize(rx, |p| p as *const dyn Payload) };
+    /// assert_eq!("MyValue", format!("{:?}", rx));
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `f` must not dereference its argument, and must return the
+    /// same pointer with only metadata added.
+    pub unsafe fn unsize<U: Notify + ?Sized>(
+        this: Self,
+        f: impl FnOnce(*const T) -> *const U,
+    ) -> Rx<U> {
+        let this = ManuallyDrop::new(this);
+        // Pointer metadata depends only on the type, and Inner is
+        // repr(C), so apply the cast to the Inner pointer directly.
+        let ptr = f(this.ptr.as_ptr() as *const T) as *mut Inner<U>;
+        Rx {
+            ptr: NonNull::new_unchecked(ptr),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Reconstructs a handle from a pointer returned by
+    /// [Rx::into_raw].
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [Rx::into_raw] for the same
+    /// `T`, and each call to `from_raw` must be balanced by a call to
+    /// `into_raw` or [Rx::increment_rx_count].
+    pub unsafe fn from_raw(ptr: *const T) -> Self {
+        let inner = (ptr as *const u8).sub(data_offset::<T>()) as *mut Inner<T>;
+        Rx {
+            ptr: NonNull::new_unchecked(inner),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Like [Rx::into_raw], but maps [None] to a null pointer. See
+    /// [Tx::option_into_raw].
+    pub fn option_into_raw(this: Option<Self>) -> *const T {
+        this.map_or(ptr::null(), Rx::into_raw)
+    }
+
+    /// Like [Rx::from_raw], but maps a null pointer to [None].
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be null or satisfy the requirements of
+    /// [Rx::from_raw].
+    pub unsafe fn option_from_raw(ptr: *const T) -> Option<Self> {
+        if ptr.is_null() {
+            None
+        } else {
+            Some(Rx::from_raw(ptr))
         }
     }
+
+    /// Increments the rx count of the allocation behind a pointer
+    /// returned by [Rx::into_raw].
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [Rx::into_raw] for the same
+    /// `T`, and the handle it represents must not have been reclaimed.
+    pub unsafe fn increment_rx_count(ptr: *const T) {
+        mem::forget(Rx::clone(&ManuallyDrop::new(Rx::from_raw(ptr))));
+    }
+
+    /// Decrements the rx count of the allocation behind a pointer
+    /// returned by [Rx::into_raw], notifying or deallocating as if a
+    /// handle was dropped.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [Rx::into_raw] for the same
+    /// `T`, and the rx count must be at least one.
+    pub unsafe fn decrement_rx_count(ptr: *const T) {
+        drop(Rx::from_raw(ptr))
+    }
+}
+
+impl<T: Notify + ?Sized, A: Allocator> Rx<T, A> {
+    /// Returns the allocator that holds this allocation.
+    pub fn allocator(this: &Self) -> &A {
+        // SAFETY: We do not create a &mut to Inner.
+        &unsafe { this.ptr.as_ref() }.alloc
+    }
+
+    /// See [Tx::leak].
+    pub fn leak(this: Self) -> &'static T
+    where
+        T: 'static,
+        A: 'static,
+    {
+        let ptr = ManuallyDrop::new(this).ptr;
+        // SAFETY: The forgotten reference keeps the payload alive,
+        // and the allocation is never freed.
+        &unsafe { ptr.as_ref() }.data
+    }
+
+    /// See [Tx::clone_many].
+    pub fn clone_many(this: &Self, n: usize) -> impl ExactSizeIterator<Item = Rx<T, A>> {
+        batch::RxBatch::clone_from(this, n)
+    }
+
+    /// See [Tx::drop_many].
+    pub fn drop_many(handles: impl IntoIterator<Item = Rx<T, A>>) {
+        batch::drop_many_rx(handles)
+    }
 }
 
-impl<T: Notify> Clone for Rx<T> {
+impl<T: Notify + ?Sized, A: Allocator> Clone for Rx<T, A> {
     fn clone(&self) -> Self {
         // SAFETY: We do not create a &mut to Inner.
         let inner = unsafe { self.ptr.as_ref() };
-        inner.count.inc_rx();
+        inner.inc_rx();
         Rx { ..*self }
     }
 }
 
-impl<T: Notify> Deref for Rx<T> {
+impl<T: Notify + ?Sized, A: Allocator> Deref for Rx<T, A> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -382,30 +2536,147 @@ impl<T: Notify> Deref for Rx<T> {
     }
 }
 
-impl<T: Notify> AsRef<T> for Rx<T> {
+impl<T: Notify + ?Sized> AsRef<T> for Rx<T> {
     fn as_ref(&self) -> &T {
         self.deref()
     }
 }
 
-impl<T: Notify> Borrow<T> for Rx<T> {
+impl<T: Notify + ?Sized> Borrow<T> for Rx<T> {
     fn borrow(&self) -> &T {
         self.deref()
     }
 }
 
-impl<T: Notify + fmt::Debug> fmt::Debug for Rx<T> {
+impl<T: Notify + fmt::Debug + ?Sized, A: Allocator> fmt::Debug for Rx<T, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(self.as_ref(), f)
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: Notify + fmt::Display + ?Sized, A: Allocator> fmt::Display for Rx<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<T: Notify + ?Sized, A: Allocator> fmt::Pointer for Rx<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&(&**self as *const T), f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Notify + std::error::Error + ?Sized, A: Allocator> std::error::Error for Rx<T, A> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        (**self).source()
+    }
+}
+
+impl<T: Notify + PartialEq + ?Sized, A: Allocator> PartialEq for Rx<T, A> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Notify + PartialEq + ?Sized, A: Allocator> PartialEq<Tx<T, A>> for Rx<T, A> {
+    fn eq(&self, other: &Tx<T, A>) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Notify + Eq + ?Sized, A: Allocator> Eq for Rx<T, A> {}
+
+impl<T: Notify + PartialOrd + ?Sized, A: Allocator> PartialOrd for Rx<T, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<T: Notify + Ord + ?Sized, A: Allocator> Ord for Rx<T, A> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<T: Notify + Hash + ?Sized, A: Allocator> Hash for Rx<T, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+/// A weak reference to the read half of a split reference count.
+///
+/// Does not keep the payload alive. Obtained from [Rx::downgrade].
+pub struct RxWeak<T: Notify + ?Sized> {
+    ptr: NonNull<Inner<T>>,
+    phantom: PhantomData<T>,
+}
+
+unsafe impl<T: Sync + Send + Notify + ?Sized> Send for RxWeak<T> {}
+unsafe impl<T: Sync + Send + Notify + ?Sized> Sync for RxWeak<T> {}
+
+impl<T: RefUnwindSafe + Notify + ?Sized> UnwindSafe for RxWeak<T> {}
+impl<T: RefUnwindSafe + Notify + ?Sized> RefUnwindSafe for RxWeak<T> {}
+
+impl<T: Notify + ?Sized> RxWeak<T> {
+    /// Returns the allocation's identity, the same as [Rx::id]
+    /// on its strong handles. The allocation lives at least as long as
+    /// this weak reference.
+    pub fn id(&self) -> SplitrcId {
+        // SAFETY: Computing a field address does not create a
+        // reference, and the weak reference keeps the allocation.
+        SplitrcId::from_addr(
+            unsafe { ptr::addr_of!((*self.ptr.as_ptr()).data) } as *const u8 as usize,
+        )
+    }
+
+    /// Attempts to upgrade to a [Rx]. Returns [None] if the last
+    /// [Rx] has been dropped, even if [Tx] references remain.
+    pub fn upgrade(&self) -> Option<Rx<T>> {
+        // SAFETY: The weak count keeps the counts allocated. data
+        // may have been dropped, so only reference the count.
+        let count = unsafe { &(*self.ptr.as_ptr()).count };
+        if count.upgrade_rx() {
+            // SAFETY: The new reference keeps data alive.
+            #[cfg(feature = "observer")]
+            unsafe { &(*self.ptr.as_ptr()).data }.rx_did_clone();
+            Some(Rx {
+                ptr: self.ptr,
+                phantom: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Notify + ?Sized> Drop for RxWeak<T> {
+    fn drop(&mut self) {
+        release_weak(self.ptr);
+    }
+}
+
+impl<T: Notify + ?Sized> Clone for RxWeak<T> {
+    fn clone(&self) -> Self {
+        // SAFETY: data may have been dropped, so only reference the
+        // weak count.
+        unsafe { &(*self.ptr.as_ptr()).weak }.inc();
+        RxWeak { ..*self }
     }
 }
 
-impl<T: Notify + fmt::Display> fmt::Display for Rx<T> {
+impl<T: Notify + ?Sized> fmt::Debug for RxWeak<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Display::fmt(self.as_ref(), f)
+        write!(f, "(RxWeak)")
     }
 }
 
+/// Returns true if `tx` and `rx` are halves of the same allocation.
+pub fn same_allocation<T: Notify + ?Sized>(tx: &Tx<T>, rx: &Rx<T>) -> bool {
+    tx.ptr.cast::<u8>() == rx.ptr.cast::<u8>()
+}
+
 /// Allocates a pointer holding `data` and returns a pair of references.
 ///
 /// T must implement [Notify] to receive a notification when the write
@@ -413,12 +2684,193 @@ impl<T: Notify + fmt::Display> fmt::Display for Rx<T> {
 ///
 /// `data` is dropped when both halves' reference counts reach zero.
 pub fn new<T: Notify>(data: T) -> (Tx<T>, Rx<T>) {
-    let x = Box::new(Inner {
-        count: SplitCount::new(),
-        data,
-    });
+    let ptr = allocate(data);
+    (
+        Tx {
+            ptr,
+            phantom: PhantomData,
+        },
+        Rx {
+            ptr,
+            phantom: PhantomData,
+        },
+    )
+}
+
+/// Like [new], but the allocation's events are exported to the
+/// `metrics` facade under `label`, such as the name of the subsystem
+/// that owns it. Allocations from [new] and the other constructors
+/// are exported without a label.
+///
+/// ```
+/// # struct MyValue {}
+/// # impl splitrc::Notify for MyValue {}
+/// let (tx, rx) = splitrc::new_labeled("connections", MyValue {});
+/// # drop((tx, rx));
+/// ```
+#[cfg(feature = "metrics")]
+pub fn new_labeled<T: Notify>(label: &'static str, data: T) -> (Tx<T>, Rx<T>) {
+    let mut x = Box::new(Inner::new(data));
+    x.metrics_label = Some(label);
     // SAFETY: We just allocated the box, so it's not null.
     let ptr = unsafe { NonNull::new_unchecked(Box::into_raw(x)) };
+    lifecycle_event!(ptr, "allocate");
+    (
+        Tx {
+            ptr,
+            phantom: PhantomData,
+        },
+        Rx {
+            ptr,
+            phantom: PhantomData,
+        },
+    )
+}
+
+/// Allocates a pointer holding the result of `data_fn` and returns a
+/// pair of references.
+///
+/// `data_fn` is given weak references to the allocation so the
+/// payload can refer to itself. Upgrading them fails until
+/// `new_cyclic` returns.
+///
+/// ```
+/// struct Actor {
+///     this: splitrc::RxWeak<Actor>,
+/// }
+/// impl splitrc::Notify for Actor {}
+///
+/// let (tx, rx) = splitrc::new_cyclic(|_tx, rx| Actor { this: rx.clone() });
+/// assert!(tx.this.upgrade().is_some());
+/// # drop(rx);
+/// ```
+pub fn new_cyclic<T: Notify>(data_fn: impl FnOnce(&TxWeak<T>, &RxWeak<T>) -> T) -> (Tx<T>, Rx<T>) {
+    let x: Box<Inner<MaybeUninit<T>>> = Box::new(Inner {
+        // Both counts start at zero so upgrades fail until data is
+        // initialized.
+        count: SplitCount(AtomicPacked::new(0)),
+        // One for each weak reference passed to data_fn. If data_fn
+        // panics, dropping them frees the allocation without touching
+        // data.
+        weak: WeakCount(AtomicWeak::new(2)),
+        #[cfg(feature = "async")]
+        wakers: Default::default(),
+        #[cfg(feature = "event-listener")]
+        events: Default::default(),
+        #[cfg(feature = "drop-callbacks")]
+        subscribers: Default::default(),
+        #[cfg(feature = "leak-track")]
+        leak: leak::Entry::new(core::any::type_name::<T>()),
+        #[cfg(feature = "metrics")]
+        metrics_label: None,
+        #[cfg(feature = "revive")]
+        revival: Some(revive::Revival::of::<T>()),
+        alloc: Global,
+        data: ManuallyDrop::new(MaybeUninit::uninit()),
+    });
+    // SAFETY: We just allocated the box, so it's not null.
+    // MaybeUninit<T> has the same layout as T and Inner is repr(C).
+    let ptr: NonNull<Inner<T>> = unsafe { NonNull::new_unchecked(Box::into_raw(x)).cast() };
+    let tx_weak = TxWeak {
+        ptr,
+        phantom: PhantomData,
+    };
+    let rx_weak = RxWeak {
+        ptr,
+        phantom: PhantomData,
+    };
+    let data = data_fn(&tx_weak, &rx_weak);
+
+    // SAFETY: Nobody can access data until the counts are nonzero.
+    unsafe {
+        ptr::addr_of_mut!((*ptr.as_ptr()).data).write(ManuallyDrop::new(data));
+    }
+    // SAFETY: We do not create a &mut to Inner.
+    let inner = unsafe { ptr.as_ref() };
+    // Release pairs with the acquire in upgrade so weak references
+    // that escaped data_fn observe the initialized data.
+    inner.count.0.store(RC_INIT, Ordering::Release);
+    lifecycle_event!(ptr, "allocate");
+
+    // One weak reference becomes the weak reference collectively held
+    // by the strong references.
+    mem::forget(tx_weak);
+    drop(rx_weak);
+    (
+        Tx {
+            ptr,
+            phantom: PhantomData,
+        },
+        Rx {
+            ptr,
+            phantom: PhantomData,
+        },
+    )
+}
+
+/// Allocates uninitialized memory for a `T` and calls `init` to
+/// initialize it in place, so large payloads are never copied through
+/// the stack.
+///
+/// If `init` panics, the memory is freed without dropping `T`.
+///
+/// ```
+/// # struct Buffer { bytes: [u8; 1 << 20] }
+/// # impl splitrc::Notify for Buffer {}
+/// let (tx, rx) = unsafe {
+///     splitrc::new_with(|slot: &mut std::mem::MaybeUninit<Buffer>| {
+///         let bytes = std::ptr::addr_of_mut!((*slot.as_mut_ptr()).bytes);
+///         bytes.write_bytes(0, 1);
+///     })
+/// };
+/// # drop((tx, rx));
+/// ```
+///
+/// # Safety
+///
+/// `init` must fully initialize the slot before returning.
+pub unsafe fn new_with<T: Notify>(init: impl FnOnce(&mut MaybeUninit<T>)) -> (Tx<T>, Rx<T>) {
+    // Frees the allocation if init panics.
+    struct Guard<T> {
+        ptr: NonNull<Inner<MaybeUninit<T>>>,
+    }
+    impl<T> Drop for Guard<T> {
+        fn drop(&mut self) {
+            // The header is fully written and holds the one weak
+            // reference, and release_weak never touches data.
+            release_weak(self.ptr)
+        }
+    }
+
+    let layout = Layout::new::<Inner<T>>();
+    // Inner always contains the counts, so layout is not zero-sized.
+    let ptr = match NonNull::new(alloc(layout) as *mut Inner<MaybeUninit<T>>) {
+        Some(ptr) => ptr,
+        None => handle_alloc_error(layout),
+    };
+    let p = ptr.as_ptr();
+    ptr::addr_of_mut!((*p).count).write(SplitCount::new());
+    ptr::addr_of_mut!((*p).weak).write(WeakCount::new());
+    #[cfg(feature = "async")]
+    ptr::addr_of_mut!((*p).wakers).write(Default::default());
+    #[cfg(feature = "event-listener")]
+    ptr::addr_of_mut!((*p).events).write(Default::default());
+    #[cfg(feature = "drop-callbacks")]
+    ptr::addr_of_mut!((*p).subscribers).write(Default::default());
+    #[cfg(feature = "leak-track")]
+    ptr::addr_of_mut!((*p).leak).write(leak::Entry::new(core::any::type_name::<T>()));
+    #[cfg(feature = "metrics")]
+    ptr::addr_of_mut!((*p).metrics_label).write(None);
+    #[cfg(feature = "revive")]
+    ptr::addr_of_mut!((*p).revival).write(Some(revive::Revival::of::<T>()));
+    ptr::addr_of_mut!((*p).alloc).write(Global);
+    let guard = Guard { ptr };
+    init(&mut *ptr::addr_of_mut!((*p).data));
+    mem::forget(guard);
+    lifecycle_event!(ptr, "allocate");
+
+    // MaybeUninit<T> has the same layout as T and Inner is repr(C).
+    let ptr = ptr.cast::<Inner<T>>();
     (
         Tx {
             ptr,
@@ -438,6 +2890,90 @@ pub fn new<T: Notify>(data: T) -> (Tx<T>, Rx<T>) {
 /// in place and cannot be moved again, unless `T` implements [Unpin].
 pub fn pin<T: Notify>(data: T) -> (Pin<Tx<T>>, Pin<Rx<T>>) {
     let (tx, rx) = new(data);
+    // SAFETY: We do not create a &mut to Inner.
+    unsafe { tx.ptr.as_ref() }.weak.set_pinned();
     // SAFETY: data is never moved again
     unsafe { (Pin::new_unchecked(tx), Pin::new_unchecked(rx)) }
 }
+
+/// The error returned by [try_new], [try_pin], and [try_new_in] when
+/// memory allocation fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AllocError;
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AllocError {}
+
+/// Like [new], but returns an error instead of aborting if memory
+/// allocation fails. `data` is dropped on failure.
+pub fn try_new<T: Notify>(data: T) -> Result<(Tx<T>, Rx<T>), AllocError> {
+    let ptr = try_allocate(data)?;
+    Ok((
+        Tx {
+            ptr,
+            phantom: PhantomData,
+        },
+        Rx {
+            ptr,
+            phantom: PhantomData,
+        },
+    ))
+}
+
+/// Like [pin], but returns an error instead of aborting if memory
+/// allocation fails. `data` is dropped on failure.
+#[allow(clippy::type_complexity)]
+pub fn try_pin<T: Notify>(data: T) -> Result<(Pin<Tx<T>>, Pin<Rx<T>>), AllocError> {
+    let (tx, rx) = try_new(data)?;
+    // SAFETY: We do not create a &mut to Inner.
+    unsafe { tx.ptr.as_ref() }.weak.set_pinned();
+    // SAFETY: data is never moved again
+    Ok(unsafe { (Pin::new_unchecked(tx), Pin::new_unchecked(rx)) })
+}
+
+/// Allocates a pointer holding `data` in `alloc` and returns a pair of
+/// references.
+///
+/// The allocator is stored alongside the counts and frees the memory
+/// once both halves and every weak reference are gone.
+///
+/// ```
+/// use allocator_api2::alloc::Global;
+///
+/// # struct MyValue {}
+/// # impl splitrc::Notify for MyValue {}
+/// let (tx, rx) = splitrc::new_in(MyValue {}, Global);
+/// # drop((tx, rx));
+/// ```
+pub fn new_in<T: Notify, A: Allocator>(data: T, alloc: A) -> (Tx<T, A>, Rx<T, A>) {
+    match try_new_in(data, alloc) {
+        Ok(pair) => pair,
+        Err(AllocError) => handle_alloc_error(Layout::new::<Inner<T, Shared, A>>()),
+    }
+}
+
+/// Like [new_in], but returns an error instead of aborting if memory
+/// allocation fails. `data` and `alloc` are dropped on failure.
+#[allow(clippy::type_complexity)]
+pub fn try_new_in<T: Notify, A: Allocator>(
+    data: T,
+    alloc: A,
+) -> Result<(Tx<T, A>, Rx<T, A>), AllocError> {
+    let ptr = try_allocate_in(data, alloc)?;
+    Ok((
+        Tx {
+            ptr,
+            phantom: PhantomData,
+        },
+        Rx {
+            ptr,
+            phantom: PhantomData,
+        },
+    ))
+}
@@ -0,0 +1,199 @@
+//! A stream of lifecycle events for payloads that don't implement
+//! [Notify].
+
+use crate::{new, Notify, Rx, Tx};
+use alloc::collections::VecDeque;
+use core::fmt;
+use core::ops::Deref;
+use core::task::{Context, Poll, Waker};
+
+#[cfg(loom)]
+use loom::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+#[cfg(not(loom))]
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+/// A lifecycle event yielded by [Events].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Event {
+    /// The last [Tx] was dropped.
+    LastTxDropped,
+    /// The last [Rx] was dropped.
+    LastRxDropped,
+    /// The payload was dropped, so no more events follow.
+    Deallocated,
+}
+
+#[derive(Default)]
+struct State {
+    events: VecDeque<Event>,
+    waker: Option<Waker>,
+    deallocated: bool,
+}
+
+#[derive(Default)]
+struct Queue {
+    state: Mutex<State>,
+    ready: Condvar,
+}
+
+impl Queue {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // Events are pushed from Drop, so never propagate a poison.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn push(&self, event: Event) {
+        let waker = {
+            let mut state = self.lock();
+            state.events.push_back(event);
+            if event == Event::Deallocated {
+                state.deallocated = true;
+            }
+            state.waker.take()
+        };
+        self.ready.notify_all();
+        // Wake outside of the lock.
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// A payload that reports its lifecycle to [Events]. Created by
+/// [new_with_events].
+pub struct Reported<T> {
+    data: T,
+    queue: Arc<Queue>,
+}
+
+impl<T> Notify for Reported<T> {
+    fn last_tx_did_drop(&self) {
+        self.queue.push(Event::LastTxDropped)
+    }
+
+    fn last_rx_did_drop(&self) {
+        self.queue.push(Event::LastRxDropped)
+    }
+}
+
+impl<T> Drop for Reported<T> {
+    fn drop(&mut self) {
+        self.queue.push(Event::Deallocated)
+    }
+}
+
+impl<T> Deref for Reported<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Reported<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.data, f)
+    }
+}
+
+/// The lifecycle events of one allocation, in order. Created by
+/// [new_with_events].
+///
+/// Iterating blocks until the next event. Both iteration and the
+/// stream end after [Event::Deallocated]. If a handle is leaked,
+/// they never end.
+pub struct Events {
+    queue: Arc<Queue>,
+}
+
+impl Events {
+    /// Returns the next event if one is queued, without blocking.
+    pub fn try_next(&mut self) -> Option<Event> {
+        self.queue.lock().events.pop_front()
+    }
+
+    /// Returns [Poll::Ready] with the next event, or `None` once the
+    /// events have ended. Otherwise, registers the context's waker.
+    pub fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        let mut state = self.queue.lock();
+        // Checking under the lock ensures we either observe the event
+        // or are registered before the wake.
+        if let Some(event) = state.events.pop_front() {
+            Poll::Ready(Some(event))
+        } else if state.deallocated {
+            Poll::Ready(None)
+        } else {
+            match &state.waker {
+                Some(old) if old.will_wake(cx.waker()) => {}
+                _ => state.waker = Some(cx.waker().clone()),
+            }
+            Poll::Pending
+        }
+    }
+}
+
+impl Iterator for Events {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        let mut state = self.queue.lock();
+        loop {
+            if let Some(event) = state.events.pop_front() {
+                return Some(event);
+            }
+            if state.deallocated {
+                return None;
+            }
+            state = self
+                .queue
+                .ready
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl futures_core::Stream for Events {
+    type Item = Event;
+
+    fn poll_next(self: core::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        Events::poll_next(self.get_mut(), cx)
+    }
+}
+
+impl fmt::Debug for Events {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Events")
+            .field("pending", &self.queue.lock().events.len())
+            .finish()
+    }
+}
+
+/// Allocates a pointer holding `data` and returns a pair of
+/// references along with the stream of their lifecycle [Event]s.
+///
+/// `T` need not implement [Notify], so a supervisor can watch many
+/// kinds of objects without writing an implementation for each.
+///
+/// ```
+/// use splitrc::Event;
+///
+/// let (tx, rx, events) = splitrc::new_with_events(vec![1, 2, 3]);
+/// drop(tx);
+/// drop(rx);
+/// assert_eq!(
+///     vec![Event::LastTxDropped, Event::Deallocated],
+///     events.collect::<Vec<_>>(),
+/// );
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn new_with_events<T>(data: T) -> (Tx<Reported<T>>, Rx<Reported<T>>, Events) {
+    let queue = Arc::new(Queue::default());
+    let (tx, rx) = new(Reported {
+        data,
+        queue: queue.clone(),
+    });
+    (tx, rx, Events { queue })
+}
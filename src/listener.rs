@@ -0,0 +1,99 @@
+//! Waiting for a half to close with event-listener.
+
+use crate::{Notify, Rx, Tx};
+use core::future::Future;
+use event_listener::{Event, EventListener};
+
+// Unlike the wakers, the events are dropped with the allocation, and
+// listeners keep their own reference to the event's state.
+pub(crate) struct Events {
+    tx_closed: Event,
+    rx_closed: Event,
+}
+
+impl Events {
+    #[cfg(not(loom))]
+    pub(crate) const fn new() -> Self {
+        Events {
+            tx_closed: Event::new(),
+            rx_closed: Event::new(),
+        }
+    }
+
+    #[cfg(loom)]
+    pub(crate) fn new() -> Self {
+        Events {
+            tx_closed: Event::new(),
+            rx_closed: Event::new(),
+        }
+    }
+
+    pub(crate) fn notify_tx_closed(&self) {
+        self.tx_closed.notify(usize::MAX);
+    }
+
+    pub(crate) fn notify_rx_closed(&self) {
+        self.rx_closed.notify(usize::MAX);
+    }
+}
+
+impl Default for Events {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Checking again after listening ensures we either observe the
+// closed half or are listening before the notification.
+fn listen(event: &Event, is_closed: impl Fn() -> bool) -> Option<EventListener> {
+    if is_closed() {
+        return None;
+    }
+    let listener = event.listen();
+    if is_closed() {
+        return None;
+    }
+    Some(listener)
+}
+
+impl<T: Notify + ?Sized> Tx<T> {
+    /// Returns a future that resolves when the last [Rx] is dropped.
+    ///
+    /// Works on any executor. The future does not keep the
+    /// allocation alive.
+    pub fn rx_closed(this: &Self) -> impl Future<Output = ()> + Send + Sync + 'static {
+        let listener = Self::listen_rx_closed(this);
+        async move {
+            if let Some(listener) = listener {
+                listener.await
+            }
+        }
+    }
+
+    fn listen_rx_closed(this: &Self) -> Option<EventListener> {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        listen(&inner.events.rx_closed, || inner.count.counts().rx == 0)
+    }
+}
+
+impl<T: Notify + ?Sized> Rx<T> {
+    /// Returns a future that resolves when the last [Tx] is dropped.
+    ///
+    /// Works on any executor. The future does not keep the
+    /// allocation alive.
+    pub fn tx_closed(this: &Self) -> impl Future<Output = ()> + Send + Sync + 'static {
+        let listener = Self::listen_tx_closed(this);
+        async move {
+            if let Some(listener) = listener {
+                listener.await
+            }
+        }
+    }
+
+    fn listen_tx_closed(this: &Self) -> Option<EventListener> {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        listen(&inner.events.tx_closed, || inner.count.counts().tx == 0)
+    }
+}
@@ -0,0 +1,215 @@
+//! Single-threaded split reference counts.
+//!
+//! These handles have the same [Notify] semantics as [crate::Tx] and
+//! [crate::Rx], but count with a [Cell] instead of an atomic, so they
+//! are neither [Send] nor [Sync]. Useful on targets without threads
+//! and in hot single-threaded loops.
+//!
+//! ```
+//! # struct MyValue {}
+//! # impl splitrc::Notify for MyValue {}
+//! let (tx, rx) = splitrc::local::new(MyValue {});
+//! ```
+
+use crate::{allocate, drop_rx, drop_tx, Atomic, Backend, Inner, Notify, Packed, WeakPacked};
+use core::borrow::Borrow;
+use core::cell::Cell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::pin::Pin;
+use core::ptr::NonNull;
+use core::sync::atomic::Ordering;
+
+#[cfg(doc)]
+use core::marker::Unpin;
+
+macro_rules! impl_cell {
+    ($v:ty) => {
+        impl Atomic<$v> for Cell<$v> {
+            fn new(v: $v) -> Self {
+                Cell::new(v)
+            }
+            fn load(&self, _order: Ordering) -> $v {
+                self.get()
+            }
+            fn fetch_add(&self, v: $v, _order: Ordering) -> $v {
+                let old = self.get();
+                self.set(old.wrapping_add(v));
+                old
+            }
+            fn fetch_sub(&self, v: $v, _order: Ordering) -> $v {
+                let old = self.get();
+                self.set(old.wrapping_sub(v));
+                old
+            }
+            fn fetch_update<F: FnMut($v) -> Option<$v>>(
+                &self,
+                _set_order: Ordering,
+                _fetch_order: Ordering,
+                mut f: F,
+            ) -> Result<$v, $v> {
+                let old = self.get();
+                match f(old) {
+                    Some(new) => {
+                        self.set(new);
+                        Ok(old)
+                    }
+                    None => Err(old),
+                }
+            }
+            fn fence(_order: Ordering) {}
+        }
+    };
+}
+
+impl_cell!(u32);
+impl_cell!(u64);
+impl_cell!(usize);
+
+/// Single-threaded counts.
+struct Local;
+
+impl Backend for Local {
+    type Count = Cell<Packed>;
+    type Weak = Cell<WeakPacked>;
+}
+
+/// The write half of a single-threaded split reference count.
+pub struct Tx<T: Notify + ?Sized> {
+    ptr: NonNull<Inner<T, Local>>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Notify + ?Sized> Drop for Tx<T> {
+    fn drop(&mut self) {
+        drop_tx(self.ptr)
+    }
+}
+
+impl<T: Notify + ?Sized> Clone for Tx<T> {
+    fn clone(&self) -> Self {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { self.ptr.as_ref() };
+        inner.inc_tx();
+        Tx { ..*self }
+    }
+}
+
+impl<T: Notify + ?Sized> Deref for Tx<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: We know ptr is valid and do not create &mut.
+        &unsafe { self.ptr.as_ref() }.data
+    }
+}
+
+impl<T: Notify + ?Sized> AsRef<T> for Tx<T> {
+    fn as_ref(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + ?Sized> Borrow<T> for Tx<T> {
+    fn borrow(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + fmt::Debug + ?Sized> fmt::Debug for Tx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_ref(), f)
+    }
+}
+
+impl<T: Notify + fmt::Display + ?Sized> fmt::Display for Tx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_ref(), f)
+    }
+}
+
+/// The read half of a single-threaded split reference count.
+pub struct Rx<T: Notify + ?Sized> {
+    ptr: NonNull<Inner<T, Local>>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Notify + ?Sized> Drop for Rx<T> {
+    fn drop(&mut self) {
+        drop_rx(self.ptr)
+    }
+}
+
+impl<T: Notify + ?Sized> Clone for Rx<T> {
+    fn clone(&self) -> Self {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { self.ptr.as_ref() };
+        inner.inc_rx();
+        Rx { ..*self }
+    }
+}
+
+impl<T: Notify + ?Sized> Deref for Rx<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: We know ptr is valid and do not create &mut.
+        &unsafe { self.ptr.as_ref() }.data
+    }
+}
+
+impl<T: Notify + ?Sized> AsRef<T> for Rx<T> {
+    fn as_ref(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + ?Sized> Borrow<T> for Rx<T> {
+    fn borrow(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + fmt::Debug + ?Sized> fmt::Debug for Rx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_ref(), f)
+    }
+}
+
+impl<T: Notify + fmt::Display + ?Sized> fmt::Display for Rx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_ref(), f)
+    }
+}
+
+/// Allocates a pointer holding `data` and returns a pair of
+/// single-threaded references.
+///
+/// The rules are the same as [crate::new].
+pub fn new<T: Notify>(data: T) -> (Tx<T>, Rx<T>) {
+    let ptr = allocate(data);
+    (
+        Tx {
+            ptr,
+            phantom: PhantomData,
+        },
+        Rx {
+            ptr,
+            phantom: PhantomData,
+        },
+    )
+}
+
+/// Allocates a pointer holding `data` and returns a pair of pinned
+/// single-threaded references.
+///
+/// The rules are the same as [new] except that the memory is pinned
+/// in place and cannot be moved again, unless `T` implements [Unpin].
+pub fn pin<T: Notify>(data: T) -> (Pin<Tx<T>>, Pin<Rx<T>>) {
+    let (tx, rx) = new(data);
+    // SAFETY: We do not create a &mut to Inner.
+    unsafe { tx.ptr.as_ref() }.weak.set_pinned();
+    // SAFETY: data is never moved again
+    unsafe { (Pin::new_unchecked(tx), Pin::new_unchecked(rx)) }
+}
@@ -0,0 +1,355 @@
+//! Handles that dereference to part of the payload.
+
+use crate::{Notify, Rx, Tx};
+use core::borrow::Borrow;
+use core::fmt;
+use core::ops::Deref;
+use core::pin::Pin;
+use core::ptr::NonNull;
+
+/// A [Tx] that keeps the whole allocation alive but dereferences
+/// to part of the payload. Created by [Tx::map].
+pub struct MappedTx<T: Notify + ?Sized, U: ?Sized> {
+    handle: Tx<T>,
+    // Points into the allocation owned by handle.
+    ptr: NonNull<U>,
+}
+
+unsafe impl<T: Sync + Send + Notify + ?Sized, U: Sync + ?Sized> Send for MappedTx<T, U> {}
+unsafe impl<T: Sync + Send + Notify + ?Sized, U: Sync + ?Sized> Sync for MappedTx<T, U> {}
+
+impl<T: Notify + ?Sized> Tx<T> {
+    /// Returns a handle that dereferences to the result of `f`,
+    /// typically a field of the payload.
+    pub fn map<U: ?Sized>(this: Self, f: impl FnOnce(&T) -> &U) -> MappedTx<T, U> {
+        let ptr = NonNull::from(f(&this));
+        MappedTx { handle: this, ptr }
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> MappedTx<T, U> {
+    /// Projects further into the payload.
+    pub fn map<V: ?Sized>(this: Self, f: impl FnOnce(&U) -> &V) -> MappedTx<T, V> {
+        let ptr = NonNull::from(f(&this));
+        MappedTx {
+            handle: this.handle,
+            ptr,
+        }
+    }
+
+    /// Returns the underlying handle.
+    pub fn handle(this: &Self) -> &Tx<T> {
+        &this.handle
+    }
+
+    /// Discards the projection and returns the underlying
+    /// handle.
+    pub fn into_handle(this: Self) -> Tx<T> {
+        this.handle
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> Clone for MappedTx<T, U> {
+    fn clone(&self) -> Self {
+        MappedTx {
+            handle: self.handle.clone(),
+            ptr: self.ptr,
+        }
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> Deref for MappedTx<T, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        // SAFETY: handle keeps the payload alive, and the
+        // payload is never moved.
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> AsRef<U> for MappedTx<T, U> {
+    fn as_ref(&self) -> &U {
+        self.deref()
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> Borrow<U> for MappedTx<T, U> {
+    fn borrow(&self) -> &U {
+        self.deref()
+    }
+}
+
+impl<T: Notify + ?Sized, U: fmt::Debug + ?Sized> fmt::Debug for MappedTx<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_ref(), f)
+    }
+}
+
+impl<T: Notify + ?Sized, U: fmt::Display + ?Sized> fmt::Display for MappedTx<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_ref(), f)
+    }
+}
+
+/// A [Rx] that keeps the whole allocation alive but dereferences
+/// to part of the payload. Created by [Rx::map].
+pub struct MappedRx<T: Notify + ?Sized, U: ?Sized> {
+    handle: Rx<T>,
+    // Points into the allocation owned by handle.
+    ptr: NonNull<U>,
+}
+
+unsafe impl<T: Sync + Send + Notify + ?Sized, U: Sync + ?Sized> Send for MappedRx<T, U> {}
+unsafe impl<T: Sync + Send + Notify + ?Sized, U: Sync + ?Sized> Sync for MappedRx<T, U> {}
+
+impl<T: Notify + ?Sized> Rx<T> {
+    /// Returns a handle that dereferences to the result of `f`,
+    /// typically a field of the payload.
+    pub fn map<U: ?Sized>(this: Self, f: impl FnOnce(&T) -> &U) -> MappedRx<T, U> {
+        let ptr = NonNull::from(f(&this));
+        MappedRx { handle: this, ptr }
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> MappedRx<T, U> {
+    /// Projects further into the payload.
+    pub fn map<V: ?Sized>(this: Self, f: impl FnOnce(&U) -> &V) -> MappedRx<T, V> {
+        let ptr = NonNull::from(f(&this));
+        MappedRx {
+            handle: this.handle,
+            ptr,
+        }
+    }
+
+    /// Returns the underlying handle.
+    pub fn handle(this: &Self) -> &Rx<T> {
+        &this.handle
+    }
+
+    /// Discards the projection and returns the underlying
+    /// handle.
+    pub fn into_handle(this: Self) -> Rx<T> {
+        this.handle
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> Clone for MappedRx<T, U> {
+    fn clone(&self) -> Self {
+        MappedRx {
+            handle: self.handle.clone(),
+            ptr: self.ptr,
+        }
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> Deref for MappedRx<T, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        // SAFETY: handle keeps the payload alive, and the
+        // payload is never moved.
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> AsRef<U> for MappedRx<T, U> {
+    fn as_ref(&self) -> &U {
+        self.deref()
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> Borrow<U> for MappedRx<T, U> {
+    fn borrow(&self) -> &U {
+        self.deref()
+    }
+}
+
+impl<T: Notify + ?Sized, U: fmt::Debug + ?Sized> fmt::Debug for MappedRx<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_ref(), f)
+    }
+}
+
+impl<T: Notify + ?Sized, U: fmt::Display + ?Sized> fmt::Display for MappedRx<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_ref(), f)
+    }
+}
+
+/// A pinned [Tx] that dereferences to a pinned part of the payload.
+/// Created by [Tx::map_pinned].
+///
+/// Unlike [MappedTx], the underlying handle stays pinned, so the
+/// payload cannot be moved through it.
+pub struct PinnedMappedTx<T: Notify + ?Sized, U: ?Sized> {
+    handle: Pin<Tx<T>>,
+    // Points into the allocation owned by handle.
+    ptr: NonNull<U>,
+}
+
+unsafe impl<T: Sync + Send + Notify + ?Sized, U: Sync + ?Sized> Send for PinnedMappedTx<T, U> {}
+unsafe impl<T: Sync + Send + Notify + ?Sized, U: Sync + ?Sized> Sync for PinnedMappedTx<T, U> {}
+
+impl<T: Notify + ?Sized> Tx<T> {
+    /// Returns a handle that dereferences to the result of `f`,
+    /// typically a structurally pinned field of the payload, as
+    /// projected by `pin-project`'s `project_ref`.
+    ///
+    /// ```
+    /// use std::marker::PhantomPinned;
+    /// use std::pin::Pin;
+    ///
+    /// struct Node {
+    ///     links: (u32, PhantomPinned),
+    ///     value: u32,
+    /// }
+    /// impl splitrc::Notify for Node {}
+    ///
+    /// let (tx, rx) = splitrc::pin(Node { links: (0, PhantomPinned), value: 7 });
+    /// let links = splitrc::Tx::map_pinned(tx, |node| {
+    ///     // SAFETY: links is structurally pinned.
+    ///     unsafe { node.map_unchecked(|node| &node.links) }
+    /// });
+    /// let links: Pin<&(u32, PhantomPinned)> = splitrc::PinnedMappedTx::as_pin(&links);
+    /// assert_eq!(0, links.0);
+    /// # drop(rx);
+    /// ```
+    pub fn map_pinned<U: ?Sized>(
+        this: Pin<Self>,
+        f: impl FnOnce(Pin<&T>) -> Pin<&U>,
+    ) -> PinnedMappedTx<T, U> {
+        let ptr = NonNull::from(f(this.as_ref()).get_ref());
+        PinnedMappedTx { handle: this, ptr }
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> PinnedMappedTx<T, U> {
+    /// Projects further into the payload.
+    pub fn map<V: ?Sized>(this: Self, f: impl FnOnce(Pin<&U>) -> Pin<&V>) -> PinnedMappedTx<T, V> {
+        let ptr = NonNull::from(f(PinnedMappedTx::as_pin(&this)).get_ref());
+        PinnedMappedTx {
+            handle: this.handle,
+            ptr,
+        }
+    }
+
+    /// Returns the projected part of the payload, pinned.
+    pub fn as_pin(this: &Self) -> Pin<&U> {
+        // SAFETY: f returned a pinned reference, and the payload stays
+        // pinned while handle lives.
+        unsafe { Pin::new_unchecked(&**this) }
+    }
+
+    /// Returns the underlying handle.
+    pub fn handle(this: &Self) -> &Pin<Tx<T>> {
+        &this.handle
+    }
+
+    /// Discards the projection and returns the underlying
+    /// handle.
+    pub fn into_handle(this: Self) -> Pin<Tx<T>> {
+        this.handle
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> Clone for PinnedMappedTx<T, U> {
+    fn clone(&self) -> Self {
+        PinnedMappedTx {
+            handle: self.handle.clone(),
+            ptr: self.ptr,
+        }
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> Deref for PinnedMappedTx<T, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        // SAFETY: handle keeps the payload alive, and the
+        // payload is never moved.
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: Notify + ?Sized, U: fmt::Debug + ?Sized> fmt::Debug for PinnedMappedTx<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A pinned [Rx] that dereferences to a pinned part of the payload.
+/// Created by [Rx::map_pinned]. See [PinnedMappedTx].
+pub struct PinnedMappedRx<T: Notify + ?Sized, U: ?Sized> {
+    handle: Pin<Rx<T>>,
+    // Points into the allocation owned by handle.
+    ptr: NonNull<U>,
+}
+
+unsafe impl<T: Sync + Send + Notify + ?Sized, U: Sync + ?Sized> Send for PinnedMappedRx<T, U> {}
+unsafe impl<T: Sync + Send + Notify + ?Sized, U: Sync + ?Sized> Sync for PinnedMappedRx<T, U> {}
+
+impl<T: Notify + ?Sized> Rx<T> {
+    /// Returns a handle that dereferences to the result of `f`. See
+    /// [Tx::map_pinned].
+    pub fn map_pinned<U: ?Sized>(
+        this: Pin<Self>,
+        f: impl FnOnce(Pin<&T>) -> Pin<&U>,
+    ) -> PinnedMappedRx<T, U> {
+        let ptr = NonNull::from(f(this.as_ref()).get_ref());
+        PinnedMappedRx { handle: this, ptr }
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> PinnedMappedRx<T, U> {
+    /// Projects further into the payload.
+    pub fn map<V: ?Sized>(this: Self, f: impl FnOnce(Pin<&U>) -> Pin<&V>) -> PinnedMappedRx<T, V> {
+        let ptr = NonNull::from(f(PinnedMappedRx::as_pin(&this)).get_ref());
+        PinnedMappedRx {
+            handle: this.handle,
+            ptr,
+        }
+    }
+
+    /// Returns the projected part of the payload, pinned.
+    pub fn as_pin(this: &Self) -> Pin<&U> {
+        // SAFETY: See PinnedMappedTx::as_pin.
+        unsafe { Pin::new_unchecked(&**this) }
+    }
+
+    /// Returns the underlying handle.
+    pub fn handle(this: &Self) -> &Pin<Rx<T>> {
+        &this.handle
+    }
+
+    /// Discards the projection and returns the underlying
+    /// handle.
+    pub fn into_handle(this: Self) -> Pin<Rx<T>> {
+        this.handle
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> Clone for PinnedMappedRx<T, U> {
+    fn clone(&self) -> Self {
+        PinnedMappedRx {
+            handle: self.handle.clone(),
+            ptr: self.ptr,
+        }
+    }
+}
+
+impl<T: Notify + ?Sized, U: ?Sized> Deref for PinnedMappedRx<T, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        // SAFETY: handle keeps the payload alive, and the
+        // payload is never moved.
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: Notify + ?Sized, U: fmt::Debug + ?Sized> fmt::Debug for PinnedMappedRx<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
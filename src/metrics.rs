@@ -0,0 +1,40 @@
+//! Lifecycle counts exported through the `metrics` facade.
+
+use ::metrics::{counter, gauge, Label};
+use alloc::vec::Vec;
+
+/// Gauge: allocations that have not been freed.
+const LIVE: &str = "splitrc_live_allocations";
+/// Counter: handles created by cloning.
+const CLONES: &str = "splitrc_clones_total";
+/// Counter: last-tx and last-rx notifications, labeled by half.
+const NOTIFICATIONS: &str = "splitrc_notifications_total";
+
+fn labels(label: Option<&'static str>) -> Vec<Label> {
+    // Unlabeled allocations are reported without a label rather than
+    // under an empty one.
+    label
+        .map(|label| Label::from_static_parts("label", label))
+        .into_iter()
+        .collect()
+}
+
+fn notified(label: Option<&'static str>, half: &'static str) {
+    let mut labels = labels(label);
+    labels.push(Label::from_static_parts("half", half));
+    counter!(NOTIFICATIONS, labels).increment(1);
+}
+
+/// Records an event named as in `lifecycle_event!`, for an
+/// allocation created with `label`.
+#[inline]
+pub(crate) fn record(label: Option<&'static str>, event: &'static str) {
+    match event {
+        "allocate" => gauge!(LIVE, labels(label)).increment(1.0),
+        "deallocate" => gauge!(LIVE, labels(label)).decrement(1.0),
+        "clone tx" | "clone rx" => counter!(CLONES, labels(label)).increment(1),
+        "last tx dropped" => notified(label, "tx"),
+        "last rx dropped" => notified(label, "rx"),
+        _ => {}
+    }
+}
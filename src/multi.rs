@@ -0,0 +1,250 @@
+//! Split reference counts with more than two halves.
+//!
+//! [new] returns one [Handle] per role, such as producers, consumers,
+//! and a control plane. Handles clone within their role, and when the
+//! last handle of a role is dropped while another role still has
+//! handles, [Notify::last_role_did_drop] is called with that role.
+//!
+//! The roles share one packed atomic count, so each role's count is
+//! narrower than a [crate::Tx]'s: `64 / N` bits, of which half are
+//! usable before cloning panics.
+//!
+//! ```
+//! use std::sync::atomic::{AtomicBool, Ordering};
+//!
+//! const CONTROL: usize = 2;
+//!
+//! #[derive(Default)]
+//! struct Pipeline {
+//!     control_dropped: AtomicBool,
+//! }
+//!
+//! impl splitrc::multi::Notify for Pipeline {
+//!     fn last_role_did_drop(&self, role: usize) {
+//!         if role == CONTROL {
+//!             self.control_dropped.store(true, Ordering::Release);
+//!         }
+//!     }
+//! }
+//!
+//! let [producer, consumer, control] = splitrc::multi::new::<_, 3>(Pipeline::default());
+//! drop(control);
+//! assert!(producer.control_dropped.load(Ordering::Acquire));
+//! assert!(splitrc::multi::Handle::is_closed(&consumer, CONTROL));
+//! ```
+
+use crate::{abort, AtomicU64, AtomicUsize};
+use alloc::boxed::Box;
+use core::borrow::Borrow;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::ptr::NonNull;
+use core::sync::atomic::Ordering;
+
+/// Per-role notifications for [new].
+pub trait Notify {
+    /// Called when the last handle of `role` is dropped while
+    /// another role still has handles.
+    ///
+    /// Like [crate::Notify], the callback runs on the dropping
+    /// thread, and the allocation is not freed until it returns.
+    fn last_role_did_drop(&self, role: usize) {
+        let _ = role;
+    }
+}
+
+// Role r's count occupies bits [r * WIDTH, (r + 1) * WIDTH) of the
+// packed count. Roles finish once: after their count reaches zero
+// and any notification returns, they increment `finished`, and the
+// last role to finish deallocates.
+struct Inner<T, const N: usize> {
+    count: AtomicU64,
+    finished: AtomicUsize,
+    data: T,
+}
+
+struct Roles<const N: usize>;
+
+impl<const N: usize> Roles<N> {
+    const WIDTH: u32 = {
+        assert!(N >= 2 && N <= 8, "splitrc::multi supports 2 to 8 roles");
+        (64 / N) as u32
+    };
+    const MASK: u64 = (1 << Self::WIDTH) - 1;
+    // As with the two-halved count, increments that land in the
+    // panic range are undone, and the abort range leaves room for
+    // racing increments before one carries into the next role.
+    const OVERFLOW_PANIC: u64 = 1 << (Self::WIDTH - 1);
+    const OVERFLOW_ABORT: u64 = (1 << Self::WIDTH) - (1 << (Self::WIDTH - 3));
+
+    fn inc(role: usize) -> u64 {
+        1 << (role as u32 * Self::WIDTH)
+    }
+
+    fn count(c: u64, role: usize) -> u64 {
+        (c >> (role as u32 * Self::WIDTH)) & Self::MASK
+    }
+}
+
+/// One role's handle to an allocation created by [new].
+pub struct Handle<T: Notify, const N: usize> {
+    ptr: NonNull<Inner<T, N>>,
+    role: usize,
+    phantom: PhantomData<T>,
+}
+
+unsafe impl<T: Sync + Send + Notify, const N: usize> Send for Handle<T, N> {}
+unsafe impl<T: Sync + Send + Notify, const N: usize> Sync for Handle<T, N> {}
+
+impl<T: Notify, const N: usize> Handle<T, N> {
+    fn inner(&self) -> &Inner<T, N> {
+        // SAFETY: We know ptr is valid and do not create &mut.
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Returns this handle's role, its index in the array returned
+    /// by [new].
+    pub fn role(this: &Self) -> usize {
+        this.role
+    }
+
+    /// Returns the number of live handles in `role`.
+    ///
+    /// # Panics
+    ///
+    /// If `role` is not less than `N`.
+    pub fn count(this: &Self, role: usize) -> u32 {
+        assert!(role < N, "role out of range");
+        let c = this.inner().count.load(Ordering::Acquire);
+        Roles::<N>::count(c, role) as u32
+    }
+
+    /// Returns true once the last handle of `role` has been dropped.
+    ///
+    /// # Panics
+    ///
+    /// If `role` is not less than `N`.
+    pub fn is_closed(this: &Self, role: usize) -> bool {
+        Handle::count(this, role) == 0
+    }
+
+    /// Returns true if both handles point to the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr == other.ptr
+    }
+
+    #[cold]
+    fn inc_overflow(&self, old: u64) {
+        if Roles::<N>::count(old, self.role) >= Roles::<N>::OVERFLOW_ABORT {
+            abort()
+        } else {
+            self.inner()
+                .count
+                .fetch_sub(Roles::<N>::inc(self.role), Ordering::Relaxed);
+            panic!("role count overflow")
+        }
+    }
+
+    #[cold]
+    fn drop_last(&self, others: u64) {
+        // Pairs with the release in drop so the last handle of this
+        // role observes every write to data.
+        crate::fence(Ordering::Acquire);
+        // Finishes even if the notification panics, so the allocation
+        // is freed exactly once.
+        struct Finish<T: Notify, const N: usize>(NonNull<Inner<T, N>>);
+        impl<T: Notify, const N: usize> Drop for Finish<T, N> {
+            fn drop(&mut self) {
+                // SAFETY: This role has not finished, so the
+                // allocation is live.
+                let finished = unsafe { self.0.as_ref() }
+                    .finished
+                    .fetch_add(1, Ordering::AcqRel);
+                if finished == N - 1 {
+                    // SAFETY: Every role has finished, so this is the
+                    // last access.
+                    drop(unsafe { Box::from_raw(self.0.as_ptr()) })
+                }
+            }
+        }
+        let _finish = Finish(self.ptr);
+        if others != 0 {
+            self.inner().data.last_role_did_drop(self.role);
+        }
+    }
+}
+
+impl<T: Notify, const N: usize> Clone for Handle<T, N> {
+    fn clone(&self) -> Self {
+        // See SplitCount::inc_tx.
+        let old = self
+            .inner()
+            .count
+            .fetch_add(Roles::<N>::inc(self.role), Ordering::Relaxed);
+        if Roles::<N>::count(old, self.role) >= Roles::<N>::OVERFLOW_PANIC {
+            self.inc_overflow(old)
+        }
+        Handle { ..*self }
+    }
+}
+
+impl<T: Notify, const N: usize> Drop for Handle<T, N> {
+    fn drop(&mut self) {
+        let inc = Roles::<N>::inc(self.role);
+        let old = self.inner().count.fetch_sub(inc, Ordering::Release);
+        if Roles::<N>::count(old, self.role) == 1 {
+            self.drop_last(old - inc)
+        }
+    }
+}
+
+impl<T: Notify, const N: usize> Deref for Handle<T, N> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner().data
+    }
+}
+
+impl<T: Notify, const N: usize> AsRef<T> for Handle<T, N> {
+    fn as_ref(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify, const N: usize> Borrow<T> for Handle<T, N> {
+    fn borrow(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + fmt::Debug, const N: usize> fmt::Debug for Handle<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_ref(), f)
+    }
+}
+
+impl<T: Notify + fmt::Display, const N: usize> fmt::Display for Handle<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_ref(), f)
+    }
+}
+
+/// Allocates a pointer holding `data` and returns one handle for
+/// each of `N` roles, in role order.
+///
+/// `N` must be between 2 and 8.
+pub fn new<T: Notify, const N: usize>(data: T) -> [Handle<T, N>; N] {
+    let init = (0..N).map(Roles::<N>::inc).sum();
+    let ptr = NonNull::from(Box::leak(Box::new(Inner::<T, N> {
+        count: AtomicU64::new(init),
+        finished: AtomicUsize::new(0),
+        data,
+    })));
+    core::array::from_fn(|role| Handle {
+        ptr,
+        role,
+        phantom: PhantomData,
+    })
+}
@@ -0,0 +1,86 @@
+//! Unsizing coercions and `self: Tx<Self>` receivers, like [alloc::sync::Arc]'s.
+//! Requires a nightly compiler.
+//!
+//! ```
+//! trait Plugin: splitrc::Notify {
+//!     fn name(self: splitrc::Rx<Self>) -> &'static str;
+//! }
+//!
+//! struct Echo;
+//! impl splitrc::Notify for Echo {}
+//! impl Plugin for Echo {
+//!     fn name(self: splitrc::Rx<Self>) -> &'static str {
+//!         "echo"
+//!     }
+//! }
+//!
+//! let (tx, rx) = splitrc::new(Echo);
+//! let (_tx, rx): (splitrc::Tx<dyn Plugin>, splitrc::Rx<dyn Plugin>) = (tx, rx);
+//! assert_eq!("echo", rx.name());
+//! ```
+//!
+//! Handles do not get `Arc`'s drop-check eyepatch. Dropping the last
+//! handle of a half notifies the payload, which may read anything it
+//! borrows, so a borrowed payload must outlive its handles:
+//!
+//! ```compile_fail
+//! struct Borrowed<'a>(&'a str);
+//! impl splitrc::Notify for Borrowed<'_> {
+//!     fn last_rx_did_drop(&self) {
+//!         println!("{} closed", self.0);
+//!     }
+//! }
+//!
+//! let (tx, rx);
+//! let name = String::from("reader");
+//! (tx, rx) = splitrc::new(Borrowed(&name));
+//! drop(tx);
+//! // name is dropped before rx, whose drop reads it.
+//! ```
+
+use crate::{Notify, Rx, Tx};
+use allocator_api2::alloc::{Allocator, Global};
+use core::marker::Unsize;
+use core::ops::{CoerceUnsized, DispatchFromDyn, LegacyReceiver};
+use core::pin::PinCoerceUnsized;
+
+impl<T, U, A> CoerceUnsized<Tx<U, A>> for Tx<T, A>
+where
+    T: Notify + Unsize<U> + ?Sized,
+    U: Notify + ?Sized,
+    A: Allocator,
+{
+}
+
+impl<T, U> DispatchFromDyn<Tx<U, Global>> for Tx<T, Global>
+where
+    T: Notify + Unsize<U> + ?Sized,
+    U: Notify + ?Sized,
+{
+}
+
+impl<T: Notify + ?Sized, A: Allocator> LegacyReceiver for Tx<T, A> {}
+
+// SAFETY: Dereferencing always returns the same payload address, and
+// Tx has no DerefMut.
+unsafe impl<T: Notify + ?Sized, A: Allocator> PinCoerceUnsized for Tx<T, A> {}
+
+impl<T, U, A> CoerceUnsized<Rx<U, A>> for Rx<T, A>
+where
+    T: Notify + Unsize<U> + ?Sized,
+    U: Notify + ?Sized,
+    A: Allocator,
+{
+}
+
+impl<T, U> DispatchFromDyn<Rx<U, Global>> for Rx<T, Global>
+where
+    T: Notify + Unsize<U> + ?Sized,
+    U: Notify + ?Sized,
+{
+}
+
+impl<T: Notify + ?Sized, A: Allocator> LegacyReceiver for Rx<T, A> {}
+
+// SAFETY: See Tx.
+unsafe impl<T: Notify + ?Sized, A: Allocator> PinCoerceUnsized for Rx<T, A> {}
@@ -0,0 +1,182 @@
+//! Handles that point at the payload instead of the header.
+
+use crate::{Inner, Notify, Rx, Tx};
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ops::Deref;
+use core::ptr::NonNull;
+
+/// A [Tx] whose pointer targets the payload rather than the
+/// allocation's header, like `triomphe::OffsetArc`.
+///
+/// `OffsetTx<T>` has the layout of a non-null `*const T`, and
+/// `Option<OffsetTx<T>>` has the layout of a nullable one, so the
+/// handle can cross an FFI boundary as a plain pointer to `T` and be
+/// taken back. The counts are found at a fixed offset before the
+/// payload.
+///
+/// ```
+/// use splitrc::{OffsetTx, Tx};
+///
+/// #[repr(C)]
+/// struct Config { verbose: bool }
+/// impl splitrc::Notify for Config {}
+///
+/// extern "C" fn is_verbose(config: &Config) -> bool {
+///     config.verbose
+/// }
+///
+/// let (tx, rx) = splitrc::new(Config { verbose: true });
+/// let tx = OffsetTx::from(tx);
+/// assert!(is_verbose(&tx));
+///
+/// let ptr: *const Config = unsafe { std::mem::transmute(tx) };
+/// let tx: OffsetTx<Config> = unsafe { std::mem::transmute(ptr) };
+/// assert_eq!(1, Tx::rx_count(&OffsetTx::into_tx(tx)));
+/// # drop(rx);
+/// ```
+#[repr(transparent)]
+pub struct OffsetTx<T: Notify> {
+    ptr: NonNull<T>,
+    phantom: PhantomData<Inner<T>>,
+}
+
+unsafe impl<T: Sync + Send + Notify> Send for OffsetTx<T> {}
+unsafe impl<T: Sync + Send + Notify> Sync for OffsetTx<T> {}
+
+impl<T: Notify> OffsetTx<T> {
+    /// Returns the pointer to the payload the handle holds.
+    pub fn as_ptr(this: &Self) -> *const T {
+        this.ptr.as_ptr()
+    }
+
+    /// Converts back to a [Tx].
+    pub fn into_tx(this: Self) -> Tx<T> {
+        let this = ManuallyDrop::new(this);
+        // SAFETY: ptr came from Tx::into_raw, and this handle's count
+        // moves to the returned one.
+        unsafe { Tx::from_raw(this.ptr.as_ptr()) }
+    }
+
+    /// Calls `f` with a [Tx] borrowed from this handle, without
+    /// changing the counts.
+    pub fn with_tx<U>(this: &Self, f: impl FnOnce(&Tx<T>) -> U) -> U {
+        // SAFETY: The borrowed handle is never dropped.
+        f(&ManuallyDrop::new(unsafe {
+            Tx::from_raw(this.ptr.as_ptr())
+        }))
+    }
+}
+
+impl<T: Notify> From<Tx<T>> for OffsetTx<T> {
+    fn from(tx: Tx<T>) -> Self {
+        OffsetTx {
+            // SAFETY: The payload pointer is never null.
+            ptr: unsafe { NonNull::new_unchecked(Tx::into_raw(tx) as *mut T) },
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Notify> Drop for OffsetTx<T> {
+    fn drop(&mut self) {
+        // SAFETY: ptr came from Tx::into_raw, and this handle is
+        // never used again.
+        drop(unsafe { Tx::from_raw(self.ptr.as_ptr()) })
+    }
+}
+
+impl<T: Notify> Clone for OffsetTx<T> {
+    fn clone(&self) -> Self {
+        OffsetTx::with_tx(self, |tx| OffsetTx::from(tx.clone()))
+    }
+}
+
+impl<T: Notify> Deref for OffsetTx<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: The handle keeps the payload alive, and we do not
+        // create a &mut to it.
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: Notify + fmt::Debug> fmt::Debug for OffsetTx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A [Rx] whose pointer targets the payload rather than the
+/// allocation's header. See [OffsetTx].
+#[repr(transparent)]
+pub struct OffsetRx<T: Notify> {
+    ptr: NonNull<T>,
+    phantom: PhantomData<Inner<T>>,
+}
+
+unsafe impl<T: Sync + Send + Notify> Send for OffsetRx<T> {}
+unsafe impl<T: Sync + Send + Notify> Sync for OffsetRx<T> {}
+
+impl<T: Notify> OffsetRx<T> {
+    /// Returns the pointer to the payload the handle holds.
+    pub fn as_ptr(this: &Self) -> *const T {
+        this.ptr.as_ptr()
+    }
+
+    /// Converts back to a [Rx].
+    pub fn into_rx(this: Self) -> Rx<T> {
+        let this = ManuallyDrop::new(this);
+        // SAFETY: See OffsetTx::into_tx.
+        unsafe { Rx::from_raw(this.ptr.as_ptr()) }
+    }
+
+    /// Calls `f` with a [Rx] borrowed from this handle, without
+    /// changing the counts.
+    pub fn with_rx<U>(this: &Self, f: impl FnOnce(&Rx<T>) -> U) -> U {
+        // SAFETY: The borrowed handle is never dropped.
+        f(&ManuallyDrop::new(unsafe {
+            Rx::from_raw(this.ptr.as_ptr())
+        }))
+    }
+}
+
+impl<T: Notify> From<Rx<T>> for OffsetRx<T> {
+    fn from(rx: Rx<T>) -> Self {
+        OffsetRx {
+            // SAFETY: The payload pointer is never null.
+            ptr: unsafe { NonNull::new_unchecked(Rx::into_raw(rx) as *mut T) },
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Notify> Drop for OffsetRx<T> {
+    fn drop(&mut self) {
+        // SAFETY: See OffsetTx::drop.
+        drop(unsafe { Rx::from_raw(self.ptr.as_ptr()) })
+    }
+}
+
+impl<T: Notify> Clone for OffsetRx<T> {
+    fn clone(&self) -> Self {
+        OffsetRx::with_rx(self, |rx| OffsetRx::from(rx.clone()))
+    }
+}
+
+impl<T: Notify> Deref for OffsetRx<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: See OffsetTx::deref.
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: Notify + fmt::Debug> fmt::Debug for OffsetRx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,161 @@
+//! Move-only halves for payloads that are [Send] but not [Sync].
+
+use crate::{release_rx, release_tx, Counts, Notify, Rx, Tx};
+use allocator_api2::alloc::Global;
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ptr;
+
+/// The write half of a pair created by [new_once].
+///
+/// Unlike [Tx], it cannot be cloned and does not dereference to the
+/// payload. The payload is only reachable through
+/// [TxOnce::get_mut] or [TxOnce::into_inner], once the [RxOnce] has
+/// been dropped, so the two halves never share it and it need not be
+/// [Sync].
+pub struct TxOnce<T: Notify>(ManuallyDrop<Tx<T>>);
+
+// SAFETY: At most one thread can reach the payload at a time: the
+// notification runs on the dropping thread before the other half can
+// observe that it is alone, and dropping skips the observer hooks,
+// which could otherwise run on both threads at once.
+unsafe impl<T: Notify + Send> Send for TxOnce<T> {}
+// SAFETY: Shared references only read the counts.
+unsafe impl<T: Notify + Send> Sync for TxOnce<T> {}
+
+impl<T: Notify> TxOnce<T> {
+    /// Returns true once the [RxOnce] has been dropped.
+    pub fn is_closed(this: &Self) -> bool {
+        Tx::rx_count(&this.0) == 0
+    }
+
+    /// Returns a snapshot of both reference counts.
+    pub fn counts(this: &Self) -> Counts {
+        Tx::counts(&this.0)
+    }
+
+    /// Returns a mutable reference to the payload once the [RxOnce]
+    /// has been dropped and notified.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        Tx::get_mut(&mut this.0)
+    }
+
+    /// Drops the handle, returning the payload if the [RxOnce] has
+    /// already been dropped. Otherwise, notifies it.
+    pub fn into_inner(this: Self) -> Option<T> {
+        Tx::into_inner(TxOnce::into_raw_tx(this))
+    }
+
+    /// Converts into a [Tx], which requires a [Sync] payload because
+    /// it can be cloned and dereferenced.
+    pub fn into_tx(this: Self) -> Tx<T>
+    where
+        T: Sync,
+    {
+        TxOnce::into_raw_tx(this)
+    }
+
+    fn into_raw_tx(this: Self) -> Tx<T> {
+        let this = ManuallyDrop::new(this);
+        // SAFETY: The handle is moved out exactly once, and this is
+        // not dropped.
+        unsafe { ptr::read(&*this.0) }
+    }
+}
+
+impl<T: Notify> Drop for TxOnce<T> {
+    fn drop(&mut self) {
+        release_tx(self.0.ptr)
+    }
+}
+
+impl<T: Notify> fmt::Debug for TxOnce<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TxOnce")
+            .field("closed", &TxOnce::is_closed(self))
+            .finish()
+    }
+}
+
+/// The read half of a pair created by [new_once]. See [TxOnce].
+pub struct RxOnce<T: Notify>(ManuallyDrop<Rx<T>>);
+
+// SAFETY: See TxOnce.
+unsafe impl<T: Notify + Send> Send for RxOnce<T> {}
+// SAFETY: See TxOnce.
+unsafe impl<T: Notify + Send> Sync for RxOnce<T> {}
+
+impl<T: Notify> RxOnce<T> {
+    /// Returns true once the [TxOnce] has been dropped.
+    pub fn is_closed(this: &Self) -> bool {
+        Rx::tx_count(&this.0) == 0
+    }
+
+    /// Returns a snapshot of both reference counts.
+    pub fn counts(this: &Self) -> Counts {
+        Rx::counts(&this.0)
+    }
+
+    /// Returns a mutable reference to the payload once the [TxOnce]
+    /// has been dropped and notified.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        Rx::get_mut(&mut this.0)
+    }
+
+    /// Drops the handle, returning the payload if the [TxOnce] has
+    /// already been dropped. Otherwise, notifies it.
+    pub fn into_inner(this: Self) -> Option<T> {
+        Rx::into_inner(RxOnce::into_raw_rx(this))
+    }
+
+    /// Converts into an [Rx]. See [TxOnce::into_tx].
+    pub fn into_rx(this: Self) -> Rx<T>
+    where
+        T: Sync,
+    {
+        RxOnce::into_raw_rx(this)
+    }
+
+    fn into_raw_rx(this: Self) -> Rx<T> {
+        let this = ManuallyDrop::new(this);
+        // SAFETY: See TxOnce::into_raw_tx.
+        unsafe { ptr::read(&*this.0) }
+    }
+}
+
+impl<T: Notify> Drop for RxOnce<T> {
+    fn drop(&mut self) {
+        release_rx(self.0.ptr)
+    }
+}
+
+impl<T: Notify> fmt::Debug for RxOnce<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RxOnce")
+            .field("closed", &RxOnce::is_closed(self))
+            .finish()
+    }
+}
+
+/// Allocates a pointer holding `data` and returns its only two
+/// handles, which may be sent to other threads as long as `T` is
+/// [Send].
+///
+/// ```
+/// use std::cell::Cell;
+///
+/// struct Mailbox(Cell<u32>);
+/// impl splitrc::Notify for Mailbox {}
+///
+/// let (tx, mut rx) = splitrc::new_once(Mailbox(Cell::new(0)));
+/// std::thread::spawn(move || drop(tx)).join().unwrap();
+/// let mailbox = splitrc::RxOnce::get_mut(&mut rx).unwrap();
+/// mailbox.0.set(1);
+/// assert_eq!(1, splitrc::RxOnce::into_inner(rx).unwrap().0.get());
+/// ```
+pub fn new_once<T: Notify>(data: T) -> (TxOnce<T>, RxOnce<T>) {
+    // Built without a revival: a revived Rx could be cloned and
+    // dereferenced while the other half's handle is on another thread.
+    let (tx, rx) = crate::new_in(data, Global);
+    (TxOnce(ManuallyDrop::new(tx)), RxOnce(ManuallyDrop::new(rx)))
+}
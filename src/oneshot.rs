@@ -0,0 +1,146 @@
+//! A channel that sends a single value.
+//!
+//! The [Sender] is the tx half and the [Receiver] is the rx half.
+//! Sending stores the value and drops the sender, and that drop is
+//! what wakes the receiver, so a sender dropped without sending is
+//! detected the same way.
+//!
+//! ```
+//! let (tx, mut rx) = splitrc::oneshot::channel();
+//! tx.send(7).unwrap();
+//! assert_eq!(Ok(7), rx.try_recv());
+//! ```
+
+use crate::channel_core;
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+#[cfg(loom)]
+use loom::sync::{Mutex, MutexGuard};
+
+#[cfg(not(loom))]
+use std::sync::{Mutex, MutexGuard};
+
+struct Slot<T> {
+    value: Mutex<Option<T>>,
+}
+
+impl<T> Slot<T> {
+    fn lock(&self) -> MutexGuard<'_, Option<T>> {
+        // The slot is only ever filled or emptied, so a poisoned lock
+        // is still consistent.
+        self.value.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Returned by [Receiver] when the [Sender] was dropped without
+/// sending.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecvError;
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the sender was dropped without sending")
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+/// Returned by [Receiver::try_recv].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// The [Sender] has not sent yet.
+    Empty,
+    /// The [Sender] was dropped without sending, or the value has
+    /// already been received.
+    Closed,
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TryRecvError::Empty => "no value has been sent",
+            TryRecvError::Closed => "the sender was dropped without sending",
+        })
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+/// Sends one value to the [Receiver]. Created by [channel].
+pub struct Sender<T>(channel_core::Sender<Slot<T>>);
+
+impl<T> Sender<T> {
+    /// Sends `value`, consuming the sender. Returns the value if the
+    /// [Receiver] has been dropped.
+    pub fn send(self, value: T) -> Result<(), T> {
+        if self.0.is_rx_closed() {
+            return Err(value);
+        }
+        *self.0.lock() = Some(value);
+        // Dropping the last Tx wakes the receiver.
+        Ok(())
+    }
+
+    /// Returns true once the [Receiver] has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.0.is_rx_closed()
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+/// Receives the value from the [Sender]. Created by [channel].
+///
+/// Await it for the value, or [RecvError] if the sender was dropped
+/// without sending.
+pub struct Receiver<T>(channel_core::Receiver<Slot<T>>);
+
+impl<T> Receiver<T> {
+    /// Takes the value if it has been sent, without waiting.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        if let Some(value) = self.0.lock().take() {
+            return Ok(value);
+        }
+        if !self.0.is_tx_closed() {
+            return Err(TryRecvError::Empty);
+        }
+        // The value is stored before the sender drops, so look again.
+        self.0.lock().take().ok_or(TryRecvError::Closed)
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.0.poll_recv(cx, |slot| slot.lock().take()) {
+            Poll::Ready(Some(value)) => Poll::Ready(Ok(value)),
+            Poll::Ready(None) => Poll::Ready(Err(RecvError)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Receiver")
+    }
+}
+
+/// Creates a connected [Sender] and [Receiver] sharing one
+/// allocation.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let (tx, rx) = channel_core::new(Slot {
+        value: Mutex::new(None),
+    });
+    (Sender(tx), Receiver(rx))
+}
@@ -0,0 +1,87 @@
+//! Keeping the counts and the payload on separate cache lines.
+
+use crate::{new, Notify, Rx, Tx};
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+/// Aligns a payload to the start of a cache line.
+///
+/// Because the counts precede the payload in the allocation, aligning
+/// the payload also moves it off the counts' cache line. Threads
+/// reading the payload then do not contend with threads cloning and
+/// dropping handles. Created by [new_padded].
+///
+/// x86-64 and AArch64 prefetch cache lines in pairs, so the alignment
+/// is 128 bytes there and 64 bytes elsewhere.
+#[cfg_attr(
+    any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "powerpc64"
+    ),
+    repr(align(128))
+)]
+#[cfg_attr(
+    not(any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "powerpc64"
+    )),
+    repr(align(64))
+)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CachePadded<T> {
+    value: T,
+}
+
+impl<T> CachePadded<T> {
+    /// Wraps `value`.
+    pub const fn new(value: T) -> Self {
+        CachePadded { value }
+    }
+
+    /// Unwraps the payload.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Notify> Notify for CachePadded<T> {
+    forward_pinned!(value);
+}
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for CachePadded<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CachePadded<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.value, f)
+    }
+}
+
+/// Allocates a pointer holding `data` on its own cache line and
+/// returns a pair of references.
+///
+/// Useful when many threads dereference the payload while others
+/// clone and drop handles. The allocation is larger than [new]'s.
+///
+/// ```
+/// # struct MyValue {}
+/// # impl splitrc::Notify for MyValue {}
+/// let (tx, rx) = splitrc::new_padded(MyValue {});
+/// # drop((tx, rx));
+/// ```
+pub fn new_padded<T: Notify>(data: T) -> (Tx<CachePadded<T>>, Rx<CachePadded<T>>) {
+    new(CachePadded::new(data))
+}
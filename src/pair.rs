@@ -0,0 +1,99 @@
+//! Named bundles of both halves.
+
+use crate::{new, pin, Notify, Rx, Tx};
+use core::pin::Pin;
+
+/// Both halves of a split reference count. Created by [new_pair].
+///
+/// Useful as a return type when a tuple would obscure which half is
+/// which.
+///
+/// ```
+/// # struct Chan {}
+/// # impl splitrc::Notify for Chan {}
+/// struct Sender(splitrc::Tx<Chan>);
+/// struct Receiver(splitrc::Rx<Chan>);
+///
+/// fn channel() -> (Sender, Receiver) {
+///     splitrc::new_pair(Chan {}).map(Sender, Receiver)
+/// }
+/// # drop(channel());
+/// ```
+#[derive(Debug)]
+pub struct Pair<T: Notify> {
+    /// The write half.
+    pub tx: Tx<T>,
+    /// The read half.
+    pub rx: Rx<T>,
+}
+
+impl<T: Notify> Pair<T> {
+    /// Allocates a pointer holding `data`. Equivalent to [new_pair].
+    pub fn new(data: T) -> Self {
+        new_pair(data)
+    }
+
+    /// Allocates a pointer holding `data` and pins both halves.
+    ///
+    /// The rules are the same as [crate::pin].
+    pub fn pinned(data: T) -> PinnedPair<T> {
+        let (tx, rx) = pin(data);
+        PinnedPair { tx, rx }
+    }
+
+    /// Returns both halves.
+    pub fn split(self) -> (Tx<T>, Rx<T>) {
+        (self.tx, self.rx)
+    }
+
+    /// Converts each half, such as into the endpoints of a channel.
+    pub fn map<U, V>(self, tx: impl FnOnce(Tx<T>) -> U, rx: impl FnOnce(Rx<T>) -> V) -> (U, V) {
+        (tx(self.tx), rx(self.rx))
+    }
+}
+
+impl<T: Notify> From<(Tx<T>, Rx<T>)> for Pair<T> {
+    fn from((tx, rx): (Tx<T>, Rx<T>)) -> Self {
+        Pair { tx, rx }
+    }
+}
+
+impl<T: Notify> From<Pair<T>> for (Tx<T>, Rx<T>) {
+    fn from(pair: Pair<T>) -> Self {
+        pair.split()
+    }
+}
+
+/// Both halves of a pinned split reference count. Created by
+/// [Pair::pinned].
+#[derive(Debug)]
+pub struct PinnedPair<T: Notify> {
+    /// The write half.
+    pub tx: Pin<Tx<T>>,
+    /// The read half.
+    pub rx: Pin<Rx<T>>,
+}
+
+impl<T: Notify> PinnedPair<T> {
+    /// Returns both halves.
+    pub fn split(self) -> (Pin<Tx<T>>, Pin<Rx<T>>) {
+        (self.tx, self.rx)
+    }
+
+    /// Converts each half, such as into the endpoints of a channel.
+    pub fn map<U, V>(
+        self,
+        tx: impl FnOnce(Pin<Tx<T>>) -> U,
+        rx: impl FnOnce(Pin<Rx<T>>) -> V,
+    ) -> (U, V) {
+        (tx(self.tx), rx(self.rx))
+    }
+}
+
+/// Allocates a pointer holding `data` and returns both halves as a
+/// [Pair].
+///
+/// The rules are the same as [crate::new].
+pub fn new_pair<T: Notify>(data: T) -> Pair<T> {
+    new(data).into()
+}
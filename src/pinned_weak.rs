@@ -0,0 +1,99 @@
+//! Weak references that upgrade to pinned handles.
+
+use crate::{Notify, Rx, RxWeak, SplitrcId, Tx, TxWeak};
+use core::fmt;
+use core::pin::Pin;
+
+/// A weak reference to the write half of a pinned split reference
+/// count. Obtained from [Tx::downgrade_pinned].
+///
+/// Like [TxWeak], but upgrades to a `Pin<Tx<T>>`, so payloads that
+/// must not move, like futures or intrusive list nodes, can hold weak
+/// references to each other.
+pub struct PinnedTxWeak<T: Notify + ?Sized>(TxWeak<T>);
+
+impl<T: Notify + ?Sized> PinnedTxWeak<T> {
+    /// Returns the allocation's identity. See [TxWeak::id].
+    pub fn id(&self) -> SplitrcId {
+        self.0.id()
+    }
+
+    /// Attempts to upgrade to a pinned [Tx]. Returns [None] if the
+    /// last [Tx] has been dropped, even if [Rx] references remain.
+    pub fn upgrade(&self) -> Option<Pin<Tx<T>>> {
+        // SAFETY: The payload was pinned by the handle this was
+        // downgraded from, and stays pinned until it is dropped.
+        self.0.upgrade().map(|tx| unsafe { Pin::new_unchecked(tx) })
+    }
+}
+
+impl<T: Notify + ?Sized> Clone for PinnedTxWeak<T> {
+    fn clone(&self) -> Self {
+        PinnedTxWeak(self.0.clone())
+    }
+}
+
+impl<T: Notify + ?Sized> fmt::Debug for PinnedTxWeak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(PinnedTxWeak)")
+    }
+}
+
+/// A weak reference to the read half of a pinned split reference
+/// count. Obtained from [Rx::downgrade_pinned]. See [PinnedTxWeak].
+pub struct PinnedRxWeak<T: Notify + ?Sized>(RxWeak<T>);
+
+impl<T: Notify + ?Sized> PinnedRxWeak<T> {
+    /// Returns the allocation's identity. See [RxWeak::id].
+    pub fn id(&self) -> SplitrcId {
+        self.0.id()
+    }
+
+    /// Attempts to upgrade to a pinned [Rx]. Returns [None] if the
+    /// last [Rx] has been dropped, even if [Tx] references remain.
+    pub fn upgrade(&self) -> Option<Pin<Rx<T>>> {
+        // SAFETY: See PinnedTxWeak::upgrade.
+        self.0.upgrade().map(|rx| unsafe { Pin::new_unchecked(rx) })
+    }
+}
+
+impl<T: Notify + ?Sized> Clone for PinnedRxWeak<T> {
+    fn clone(&self) -> Self {
+        PinnedRxWeak(self.0.clone())
+    }
+}
+
+impl<T: Notify + ?Sized> fmt::Debug for PinnedRxWeak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(PinnedRxWeak)")
+    }
+}
+
+impl<T: Notify + ?Sized> Tx<T> {
+    /// Creates a [PinnedTxWeak] pointing to this pinned allocation.
+    ///
+    /// ```
+    /// # struct MyFuture {}
+    /// # impl splitrc::Notify for MyFuture {}
+    /// let (tx, rx) = splitrc::pin(MyFuture {});
+    /// let weak = splitrc::Tx::downgrade_pinned(&tx);
+    /// let tx: std::pin::Pin<splitrc::Tx<MyFuture>> = weak.upgrade().unwrap();
+    /// # drop((tx, rx));
+    /// ```
+    pub fn downgrade_pinned(this: &Pin<Self>) -> PinnedTxWeak<T> {
+        // SAFETY: Pin is repr(transparent), and the handle is only
+        // used to count the weak reference.
+        let tx = unsafe { &*(this as *const Pin<Self> as *const Self) };
+        PinnedTxWeak(Tx::downgrade(tx))
+    }
+}
+
+impl<T: Notify + ?Sized> Rx<T> {
+    /// Creates a [PinnedRxWeak] pointing to this pinned allocation.
+    /// See [Tx::downgrade_pinned].
+    pub fn downgrade_pinned(this: &Pin<Self>) -> PinnedRxWeak<T> {
+        // SAFETY: See Tx::downgrade_pinned.
+        let rx = unsafe { &*(this as *const Pin<Self> as *const Self) };
+        PinnedRxWeak(Rx::downgrade(rx))
+    }
+}
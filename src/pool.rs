@@ -0,0 +1,154 @@
+//! Recycled allocations.
+//!
+//! A [Pool] hands out pairs of references like [crate::new]. When
+//! both halves and every weak reference are gone, the memory goes back
+//! to the pool instead of the allocator, so per-request shared state
+//! does not churn the heap.
+//!
+//! ```
+//! # struct Request {}
+//! # impl splitrc::Notify for Request {}
+//! let pool = splitrc::pool::Pool::new();
+//! let (tx, rx) = pool.alloc(Request {});
+//! drop((tx, rx));
+//! assert_eq!(1, pool.idle());
+//! let (tx, rx) = pool.alloc(Request {});
+//! assert_eq!(0, pool.idle());
+//! # drop((tx, rx));
+//! ```
+
+use crate::{new_in, Inner, Notify, Rx, Shared, Tx};
+use alloc::vec::Vec;
+use allocator_api2::alloc::{AllocError, Allocator, Global};
+use core::alloc::Layout;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ptr::{self, NonNull};
+use std::sync::PoisonError;
+
+#[cfg(loom)]
+use loom::sync::{Arc, Mutex, MutexGuard};
+#[cfg(not(loom))]
+use std::sync::{Arc, Mutex, MutexGuard};
+
+struct Blocks {
+    layout: Layout,
+    max_idle: usize,
+    free: Mutex<Vec<NonNull<u8>>>,
+}
+
+// SAFETY: The free blocks are unused memory owned by the pool.
+unsafe impl Send for Blocks {}
+unsafe impl Sync for Blocks {}
+
+impl Blocks {
+    fn free(&self) -> MutexGuard<'_, Vec<NonNull<u8>>> {
+        // Pushing and popping cannot leave the list inconsistent.
+        self.free.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for Blocks {
+    fn drop(&mut self) {
+        let layout = self.layout;
+        for block in self.free().drain(..) {
+            // SAFETY: Every free block came from Global with layout.
+            unsafe { Global.deallocate(block, layout) }
+        }
+    }
+}
+
+/// The allocator of handles created by a [Pool]. It keeps the pool's
+/// memory alive until every handle is gone.
+#[derive(Clone)]
+pub struct PoolAlloc {
+    blocks: Arc<Blocks>,
+}
+
+impl fmt::Debug for PoolAlloc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PoolAlloc")
+    }
+}
+
+// SAFETY: Blocks are either fresh from Global or were returned by
+// deallocate with the same layout.
+unsafe impl Allocator for PoolAlloc {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout == self.blocks.layout {
+            if let Some(block) = self.blocks.free().pop() {
+                let block = ptr::slice_from_raw_parts_mut(block.as_ptr(), layout.size());
+                // SAFETY: block was not null.
+                return Ok(unsafe { NonNull::new_unchecked(block) });
+            }
+        }
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout == self.blocks.layout {
+            let mut free = self.blocks.free();
+            if free.len() < self.blocks.max_idle {
+                free.push(ptr);
+                return;
+            }
+        }
+        Global.deallocate(ptr, layout)
+    }
+}
+
+/// Hands out pairs of references and recycles their memory.
+///
+/// Dropping the pool frees its idle memory. Outstanding handles keep
+/// the rest alive, and it is freed when they go away.
+pub struct Pool<T: Notify> {
+    alloc: PoolAlloc,
+    phantom: PhantomData<fn(T)>,
+}
+
+impl<T: Notify> Pool<T> {
+    /// Creates a pool that keeps every returned allocation.
+    pub fn new() -> Self {
+        Self::with_max_idle(usize::MAX)
+    }
+
+    /// Creates a pool that keeps at most `max_idle` returned
+    /// allocations and frees the rest.
+    pub fn with_max_idle(max_idle: usize) -> Self {
+        Pool {
+            alloc: PoolAlloc {
+                blocks: Arc::new(Blocks {
+                    layout: Layout::new::<Inner<T, Shared, PoolAlloc>>(),
+                    max_idle,
+                    free: Mutex::new(Vec::new()),
+                }),
+            },
+            phantom: PhantomData,
+        }
+    }
+
+    /// Places `data` in a recycled allocation, or a new one if none
+    /// is idle, and returns a pair of references.
+    ///
+    /// The rules are the same as [crate::new].
+    pub fn alloc(&self, data: T) -> (Tx<T, PoolAlloc>, Rx<T, PoolAlloc>) {
+        new_in(data, self.alloc.clone())
+    }
+
+    /// Returns the number of allocations waiting to be reused.
+    pub fn idle(&self) -> usize {
+        self.alloc.blocks.free().len()
+    }
+}
+
+impl<T: Notify> Default for Pool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Notify> fmt::Debug for Pool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pool").field("idle", &self.idle()).finish()
+    }
+}
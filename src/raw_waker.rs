@@ -0,0 +1,127 @@
+//! Wakers backed by a [Tx] or [Rx] instead of an `Arc`.
+
+use crate::{drop_rx, drop_tx, Inner, Notify, Rx, Tx};
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+use core::task::{RawWaker, RawWakerVTable, Waker};
+
+/// A payload that can wake a task. Implement it to build a [Waker]
+/// with [Tx::into_waker] or [Rx::into_waker].
+///
+/// Unlike `std::task::Wake`, which requires an `Arc`, the waker holds
+/// a split reference, so dropping the last waker drops that half.
+pub trait Wake {
+    /// Wakes the task. Called by both [Waker::wake] and
+    /// [Waker::wake_by_ref].
+    fn wake(&self);
+}
+
+// One vtable per payload type and half. The data pointer is the
+// Inner allocation, and each waker owns one reference.
+struct TxVTable<T>(PhantomData<T>);
+
+impl<T: Wake + Notify + Send + Sync + 'static> TxVTable<T> {
+    const VTABLE: RawWakerVTable =
+        RawWakerVTable::new(Self::clone, Self::wake, Self::wake_by_ref, Self::drop);
+
+    fn raw(ptr: NonNull<Inner<T>>) -> RawWaker {
+        RawWaker::new(ptr.as_ptr() as *const (), &Self::VTABLE)
+    }
+
+    unsafe fn clone(data: *const ()) -> RawWaker {
+        let ptr = NonNull::new_unchecked(data as *mut Inner<T>);
+        ptr.as_ref().inc_tx();
+        Self::raw(ptr)
+    }
+
+    unsafe fn wake(data: *const ()) {
+        Self::wake_by_ref(data);
+        Self::drop(data);
+    }
+
+    unsafe fn wake_by_ref(data: *const ()) {
+        (*(data as *const Inner<T>)).data.wake()
+    }
+
+    unsafe fn drop(data: *const ()) {
+        drop_tx(NonNull::new_unchecked(data as *mut Inner<T>))
+    }
+}
+
+struct RxVTable<T>(PhantomData<T>);
+
+impl<T: Wake + Notify + Send + Sync + 'static> RxVTable<T> {
+    const VTABLE: RawWakerVTable =
+        RawWakerVTable::new(Self::clone, Self::wake, Self::wake_by_ref, Self::drop);
+
+    fn raw(ptr: NonNull<Inner<T>>) -> RawWaker {
+        RawWaker::new(ptr.as_ptr() as *const (), &Self::VTABLE)
+    }
+
+    unsafe fn clone(data: *const ()) -> RawWaker {
+        let ptr = NonNull::new_unchecked(data as *mut Inner<T>);
+        ptr.as_ref().inc_rx();
+        Self::raw(ptr)
+    }
+
+    unsafe fn wake(data: *const ()) {
+        Self::wake_by_ref(data);
+        Self::drop(data);
+    }
+
+    unsafe fn wake_by_ref(data: *const ()) {
+        (*(data as *const Inner<T>)).data.wake()
+    }
+
+    unsafe fn drop(data: *const ()) {
+        drop_rx(NonNull::new_unchecked(data as *mut Inner<T>))
+    }
+}
+
+impl<T: Wake + Notify + Send + Sync + 'static> Tx<T> {
+    /// Converts into a [Waker] that counts as a [Tx]. Cloning the
+    /// waker increments the tx count, and dropping it decrements it.
+    ///
+    /// ```
+    /// struct Task;
+    /// impl splitrc::Notify for Task {}
+    /// impl splitrc::Wake for Task {
+    ///     fn wake(&self) {}
+    /// }
+    ///
+    /// let (tx, rx) = splitrc::new(Task);
+    /// let waker = splitrc::Tx::into_waker(tx);
+    /// assert_eq!(1, splitrc::Rx::counts(&rx).tx);
+    /// waker.wake();
+    /// assert_eq!(0, splitrc::Rx::counts(&rx).tx);
+    /// ```
+    pub fn into_waker(this: Self) -> Waker {
+        let this = ManuallyDrop::new(this);
+        // SAFETY: The vtable upholds the RawWaker contract, and the
+        // waker takes over this reference.
+        unsafe { Waker::from_raw(TxVTable::<T>::raw(this.ptr)) }
+    }
+}
+
+impl<T: Wake + Notify + Send + Sync + 'static> Rx<T> {
+    /// Converts into a [Waker] that counts as an [Rx]. See
+    /// [Tx::into_waker].
+    pub fn into_waker(this: Self) -> Waker {
+        let this = ManuallyDrop::new(this);
+        // SAFETY: See Tx::into_waker.
+        unsafe { Waker::from_raw(RxVTable::<T>::raw(this.ptr)) }
+    }
+}
+
+impl<T: Wake + Notify + Send + Sync + 'static> From<Tx<T>> for Waker {
+    fn from(tx: Tx<T>) -> Waker {
+        Tx::into_waker(tx)
+    }
+}
+
+impl<T: Wake + Notify + Send + Sync + 'static> From<Rx<T>> for Waker {
+    fn from(rx: Rx<T>) -> Waker {
+        Rx::into_waker(rx)
+    }
+}
@@ -0,0 +1,169 @@
+//! Reopening a half from its own notification.
+//!
+//! Reviving a closed half takes two references: one for the returned
+//! handle, and one held for the running notification, which is
+//! released when it returns. So the payload stays alive until the
+//! notification returns, even if the revived handle is dropped first
+//! on another thread.
+//!
+//! The other half may drop its last handle while the notification
+//! runs. If it does so before the revival, it sees the closed half,
+//! is not notified, and counts itself finished, so the revived
+//! handles are the last, and the last of them frees the allocation
+//! without another notification. If it does so after, it is notified
+//! as usual.
+
+use crate::{DropContext, Inner, Notify, Rx, Tx};
+use core::fmt;
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::ptr::NonNull;
+
+/// The revivable notifications of one payload type, stored in each
+/// allocation that can mint [Tx] and [Rx] handles.
+pub(crate) struct Revival {
+    pub(crate) last_tx_did_drop: unsafe fn(NonNull<u8>, DropContext),
+    pub(crate) last_rx_did_drop: unsafe fn(NonNull<u8>, DropContext),
+}
+
+struct RevivalOf<T>(PhantomData<T>);
+
+impl<T: Notify> RevivalOf<T> {
+    const REVIVAL: Revival = Revival {
+        last_tx_did_drop: Self::last_tx_did_drop,
+        last_rx_did_drop: Self::last_rx_did_drop,
+    };
+
+    /// Safety: `ptr` must point to a live `Inner<T>` whose last [Tx]
+    /// was just released.
+    unsafe fn last_tx_did_drop(ptr: NonNull<u8>, ctx: DropContext) {
+        let ptr = ptr.cast::<Inner<T>>();
+        let reviver = TxReviver {
+            ptr,
+            phantom: PhantomData,
+        };
+        // SAFETY: We do not create a &mut to Inner, and data is never
+        // moved.
+        unsafe { Pin::new_unchecked(&*ptr.as_ref().data) }.last_tx_did_drop_revivable(ctx, &reviver)
+    }
+
+    /// Safety: See last_tx_did_drop.
+    unsafe fn last_rx_did_drop(ptr: NonNull<u8>, ctx: DropContext) {
+        let ptr = ptr.cast::<Inner<T>>();
+        let reviver = RxReviver {
+            ptr,
+            phantom: PhantomData,
+        };
+        // SAFETY: See last_tx_did_drop.
+        unsafe { Pin::new_unchecked(&*ptr.as_ref().data) }.last_rx_did_drop_revivable(ctx, &reviver)
+    }
+}
+
+impl Revival {
+    pub(crate) fn of<T: Notify>() -> &'static Revival {
+        &RevivalOf::<T>::REVIVAL
+    }
+}
+
+/// Reopens the tx half from [Notify::last_tx_did_drop_revivable]. See
+/// [RxReviver].
+pub struct TxReviver<'a, T: Notify> {
+    ptr: NonNull<Inner<T>>,
+    // Only lives as long as the notification.
+    phantom: PhantomData<&'a T>,
+}
+
+impl<T: Notify> TxReviver<'_, T> {
+    /// Returns a new [Tx], reopening the tx half. May be called more
+    /// than once.
+    pub fn revive(&self) -> Tx<T> {
+        // SAFETY: The notification keeps the allocation alive, and we
+        // do not create a &mut to Inner.
+        let inner = unsafe { self.ptr.as_ref() };
+        inner.count.revive_tx();
+        lifecycle_event!(self.ptr, "revive tx");
+        #[cfg(feature = "observer")]
+        inner.data.tx_did_clone();
+        Tx {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Notify> fmt::Debug for TxReviver<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(TxReviver)")
+    }
+}
+
+/// Reopens the rx half from [Notify::last_rx_did_drop_revivable].
+///
+/// Until the notification returns, the rx half is reported closed to
+/// anything that checks, and [crate::RxWeak::upgrade] fails even if
+/// it is revived. Once it returns, a revived half is open as though
+/// it had never closed: waiters and drop subscribers are not told it
+/// closed, and the next time its last handle is dropped, the
+/// notification runs again.
+///
+/// ```
+/// use std::pin::Pin;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::sync::Mutex;
+///
+/// struct Entry {
+///     // Whether the cache keeps the entry once its readers are gone.
+///     keep: AtomicBool,
+///     cached: Mutex<Option<splitrc::Rx<Entry>>>,
+/// }
+///
+/// impl splitrc::Notify for Entry {
+///     fn last_rx_did_drop_revivable(
+///         self: Pin<&Self>,
+///         _ctx: splitrc::DropContext,
+///         reviver: &splitrc::RxReviver<'_, Self>,
+///     ) {
+///         if self.keep.swap(false, Ordering::AcqRel) {
+///             *self.cached.lock().unwrap() = Some(reviver.revive());
+///         }
+///     }
+/// }
+///
+/// let (tx, rx) = splitrc::new(Entry {
+///     keep: AtomicBool::new(true),
+///     cached: Mutex::new(None),
+/// });
+/// drop(rx);
+/// assert_eq!(1, splitrc::Tx::rx_count(&tx));
+/// // Evict the entry.
+/// let cached = tx.cached.lock().unwrap().take();
+/// drop(cached);
+/// assert_eq!(0, splitrc::Tx::rx_count(&tx));
+/// ```
+pub struct RxReviver<'a, T: Notify> {
+    ptr: NonNull<Inner<T>>,
+    phantom: PhantomData<&'a T>,
+}
+
+impl<T: Notify> RxReviver<'_, T> {
+    /// Returns a new [Rx], reopening the rx half. May be called more
+    /// than once.
+    pub fn revive(&self) -> Rx<T> {
+        // SAFETY: See TxReviver::revive.
+        let inner = unsafe { self.ptr.as_ref() };
+        inner.count.revive_rx();
+        lifecycle_event!(self.ptr, "revive rx");
+        #[cfg(feature = "observer")]
+        inner.data.rx_did_clone();
+        Rx {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Notify> fmt::Debug for RxReviver<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(RxReviver)")
+    }
+}
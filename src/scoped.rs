@@ -0,0 +1,148 @@
+//! Split reference counts allocated on the stack.
+
+use crate::{Counts, Inner, Notify, Rx, Tx};
+use allocator_api2::alloc::Global;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ops::Deref;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::Ordering;
+
+// Invariant in 'scope, like std::thread::Scope, so handles cannot be
+// coerced to outlive the call to scope.
+type ScopeMarker<'scope> = PhantomData<&'scope mut &'scope ()>;
+
+/// The write half of a stack-allocated split reference count.
+/// Created by [scope].
+pub struct ScopedTx<'scope, T: Notify> {
+    // Never exposed, so no weak reference can escape the scope.
+    tx: Tx<T>,
+    scope: ScopeMarker<'scope>,
+}
+
+impl<T: Notify> ScopedTx<'_, T> {
+    /// Returns a snapshot of both reference counts.
+    pub fn counts(this: &Self) -> Counts {
+        Tx::counts(&this.tx)
+    }
+}
+
+impl<T: Notify> Clone for ScopedTx<'_, T> {
+    fn clone(&self) -> Self {
+        ScopedTx {
+            tx: self.tx.clone(),
+            scope: PhantomData,
+        }
+    }
+}
+
+impl<T: Notify> Deref for ScopedTx<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.tx
+    }
+}
+
+impl<T: Notify + fmt::Debug> fmt::Debug for ScopedTx<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// The read half of a stack-allocated split reference count.
+/// Created by [scope].
+pub struct ScopedRx<'scope, T: Notify> {
+    // Never exposed, so no weak reference can escape the scope.
+    rx: Rx<T>,
+    scope: ScopeMarker<'scope>,
+}
+
+impl<T: Notify> ScopedRx<'_, T> {
+    /// Returns a snapshot of both reference counts.
+    pub fn counts(this: &Self) -> Counts {
+        Rx::counts(&this.rx)
+    }
+}
+
+impl<T: Notify> Clone for ScopedRx<'_, T> {
+    fn clone(&self) -> Self {
+        ScopedRx {
+            rx: self.rx.clone(),
+            scope: PhantomData,
+        }
+    }
+}
+
+impl<T: Notify> Deref for ScopedRx<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.rx
+    }
+}
+
+impl<T: Notify + fmt::Debug> fmt::Debug for ScopedRx<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Places `data` on the stack and calls `f` with a pair of references
+/// to it, avoiding a heap allocation.
+///
+/// The handles cannot outlive the call, but may be shared with
+/// threads spawned by [std::thread::scope]. Notifications follow the
+/// same rules as [crate::new]. If any handle is leaked, `data` is
+/// dropped when `f` returns.
+///
+/// ```
+/// # struct MyValue {}
+/// # impl splitrc::Notify for MyValue {}
+/// splitrc::scope(MyValue {}, |tx, rx| {
+///     std::thread::scope(|s| {
+///         s.spawn(move || drop(tx));
+///         s.spawn(move || drop(rx));
+///     });
+/// });
+/// ```
+pub fn scope<T: Notify, R>(
+    data: T,
+    f: impl for<'scope> FnOnce(ScopedTx<'scope, T>, ScopedRx<'scope, T>) -> R,
+) -> R {
+    // Built without a revival: a revived Rx would be unscoped and
+    // could outlive the stack slot.
+    let mut inner = Inner::new_in(data, Global);
+    // The scope holds a weak reference, so dropping the last handle
+    // drops data but never frees the stack slot.
+    inner.weak.inc();
+    let ptr = NonNull::from(&mut inner);
+    lifecycle_event!(ptr, "allocate");
+    let tx = ScopedTx {
+        tx: Tx {
+            ptr,
+            phantom: PhantomData,
+        },
+        scope: PhantomData,
+    };
+    let rx = ScopedRx {
+        rx: Rx {
+            ptr,
+            phantom: PhantomData,
+        },
+        scope: PhantomData,
+    };
+    let result = f(tx, rx);
+    // Every handle is dropped or leaked, and none can be used again.
+    // If the weak count was not released, data is still alive.
+    //
+    // SAFETY: Access through ptr to preserve its provenance.
+    unsafe {
+        if (*ptr.as_ptr()).weak.load(Ordering::Acquire) != 1 {
+            lifecycle_event!(ptr, "deallocate");
+            ManuallyDrop::drop(&mut *ptr::addr_of_mut!((*ptr.as_ptr()).data));
+        }
+    }
+    result
+}
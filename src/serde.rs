@@ -0,0 +1,82 @@
+//! Serde support. Handles serialize as their payload.
+
+use crate::{new, Notify, Rx, Tx};
+use ::serde::de::{Deserialize, DeserializeSeed, Deserializer};
+use ::serde::ser::{Serialize, Serializer};
+use core::fmt;
+use core::marker::PhantomData;
+
+impl<T: Notify + Serialize + ?Sized> Serialize for Tx<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<T: Notify + Serialize + ?Sized> Serialize for Rx<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+/// Deserializes a payload into a fresh allocation, producing both
+/// halves.
+///
+/// A lone [Tx] or [Rx] cannot implement [Deserialize], because the
+/// other half would be dropped immediately. Use this seed with
+/// [DeserializeSeed::deserialize] or [deserialize_pair].
+pub struct PairSeed<T>(PhantomData<fn() -> T>);
+
+impl<T> PairSeed<T> {
+    /// Creates a seed.
+    pub fn new() -> Self {
+        PairSeed(PhantomData)
+    }
+}
+
+impl<T> Default for PairSeed<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for PairSeed<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for PairSeed<T> {}
+
+impl<T> fmt::Debug for PairSeed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PairSeed")
+    }
+}
+
+impl<'de, T: Notify + Deserialize<'de>> DeserializeSeed<'de> for PairSeed<T> {
+    type Value = (Tx<T>, Rx<T>);
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        T::deserialize(deserializer).map(new)
+    }
+}
+
+/// Deserializes a payload into a fresh allocation and returns a pair
+/// of references.
+///
+/// ```
+/// # #[derive(serde::Deserialize)]
+/// # struct Config { port: u16 }
+/// # impl splitrc::Notify for Config {}
+/// let mut de = serde_json::Deserializer::from_str(r#"{"port": 80}"#);
+/// let (tx, rx) = splitrc::deserialize_pair::<Config, _>(&mut de).unwrap();
+/// assert_eq!(80, rx.port);
+/// # drop(tx);
+/// ```
+pub fn deserialize_pair<'de, T, D>(deserializer: D) -> Result<(Tx<T>, Rx<T>), D::Error>
+where
+    T: Notify + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    PairSeed::new().deserialize(deserializer)
+}
@@ -0,0 +1,336 @@
+//! Split reference counts spread across per-thread shards.
+//!
+//! Under heavy clone and drop churn from many cores, a single atomic
+//! count bounces between caches. Sharded handles instead count
+//! themselves in one of several cache-padded shards, chosen by the
+//! thread that creates them. A shard holds one reference in the
+//! shared count while it is nonzero, so the shared count is only
+//! touched when a shard becomes occupied or empty.
+//!
+//! Notifications are the same as for [crate::Tx] and [crate::Rx]: the
+//! last shard of a half to empty releases the last shared reference.
+//! Each allocation carries a shard for every available core, up to
+//! 64, so this trades memory for scalability.
+//!
+//! ```
+//! # struct MyValue {}
+//! # impl splitrc::Notify for MyValue {}
+//! let (tx, rx) = splitrc::sharded::new(MyValue {});
+//! let workers: Vec<_> = (0..4)
+//!     .map(|_| {
+//!         let tx = tx.clone();
+//!         std::thread::spawn(move || drop(tx.clone()))
+//!     })
+//!     .collect();
+//! for worker in workers {
+//!     worker.join().unwrap();
+//! }
+//! # drop((tx, rx));
+//! ```
+
+use crate::{abort, release_rx, release_tx, Inner, Notify};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::Deref;
+use core::ptr::NonNull;
+use core::sync::atomic::Ordering;
+
+#[cfg(loom)]
+use loom::sync::atomic::AtomicUsize;
+
+#[cfg(not(loom))]
+use core::sync::atomic::AtomicUsize;
+
+const MAX_SHARDS: usize = 64;
+
+// As with the split count, increments in the panic range are undone,
+// and the abort range leaves room for racing increments.
+const OVERFLOW_PANIC: usize = isize::MAX as usize;
+const OVERFLOW_ABORT: usize = usize::MAX - (1 << 16);
+
+#[cfg(not(loom))]
+static NEXT_SHARD: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(loom)]
+loom::lazy_static! {
+    static ref NEXT_SHARD: AtomicUsize = AtomicUsize::new(0);
+}
+
+#[cfg(not(loom))]
+std::thread_local! {
+    static THREAD_SHARD: usize = NEXT_SHARD.fetch_add(1, Ordering::Relaxed);
+}
+
+#[cfg(loom)]
+loom::thread_local! {
+    static THREAD_SHARD: usize = NEXT_SHARD.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Returns the calling thread's shard among `len`.
+fn current_shard(len: usize) -> usize {
+    // Threads are numbered in the order they first create a handle,
+    // so a pool of workers spreads evenly across shards.
+    THREAD_SHARD.with(|index| *index) % len
+}
+
+fn shard_count() -> usize {
+    std::thread::available_parallelism()
+        .map_or(1, usize::from)
+        .min(MAX_SHARDS)
+}
+
+struct Shard {
+    tx: AtomicUsize,
+    rx: AtomicUsize,
+}
+
+impl Shard {
+    /// Returns true if the shard was empty, in which case the caller
+    /// must take a shared reference for it.
+    fn inc(count: &AtomicUsize) -> bool {
+        // See SplitCount::inc_tx.
+        let old = count.fetch_add(1, Ordering::Relaxed);
+        if old >= OVERFLOW_PANIC {
+            Shard::inc_overflow(count, old)
+        }
+        old == 0
+    }
+
+    #[cold]
+    fn inc_overflow(count: &AtomicUsize, old: usize) {
+        if old >= OVERFLOW_ABORT {
+            abort()
+        } else {
+            count.fetch_sub(1, Ordering::Relaxed);
+            panic!("shard count overflow")
+        }
+    }
+
+    /// Returns true if the shard is now empty, in which case the
+    /// caller must release its shared reference.
+    fn dec(count: &AtomicUsize) -> bool {
+        // Release publishes this handle's writes to whichever handle
+        // empties the shard, and Acquire lets that handle pass them
+        // on through the shared count.
+        count.fetch_sub(1, Ordering::AcqRel) == 1
+    }
+}
+
+// An empty shard only becomes occupied by cloning a live handle. If
+// that handle is in the same shard, the shard was not empty; if not,
+// its own shard holds a shared reference. So occupying a shard never
+// revives a closed half, and a shard cannot empty before the clone
+// that occupied it has taken its shared reference.
+struct Shards<T> {
+    shards: Box<[crate::CachePadded<Shard>]>,
+    data: T,
+}
+
+impl<T: Notify> Notify for Shards<T> {
+    forward_pinned!(data);
+}
+
+/// The write half of a split reference count, counted in a shard.
+pub struct Tx<T: Notify> {
+    shard: usize,
+    ptr: NonNull<Inner<Shards<T>>>,
+    phantom: PhantomData<T>,
+}
+
+unsafe impl<T: Sync + Send + Notify> Send for Tx<T> {}
+unsafe impl<T: Sync + Send + Notify> Sync for Tx<T> {}
+
+impl<T: Notify> Tx<T> {
+    fn inner(&self) -> &Inner<Shards<T>> {
+        // SAFETY: We know ptr is valid and do not create &mut.
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Returns true if both handles point to the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr == other.ptr
+    }
+}
+
+impl<T: Notify> Clone for Tx<T> {
+    fn clone(&self) -> Self {
+        let inner = self.inner();
+        let shard = current_shard(inner.data.shards.len());
+        if Shard::inc(&inner.data.shards[shard].tx) {
+            inner.count.inc_tx();
+        }
+        #[cfg(feature = "observer")]
+        inner.data.data.tx_did_clone();
+        Tx {
+            shard,
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Notify> Drop for Tx<T> {
+    fn drop(&mut self) {
+        let inner = self.inner();
+        #[cfg(feature = "observer")]
+        inner.data.data.tx_did_drop_one();
+        if Shard::dec(&inner.data.shards[self.shard].tx) {
+            release_tx(self.ptr)
+        }
+    }
+}
+
+impl<T: Notify> Deref for Tx<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner().data.data
+    }
+}
+
+impl<T: Notify> AsRef<T> for Tx<T> {
+    fn as_ref(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify> Borrow<T> for Tx<T> {
+    fn borrow(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + fmt::Debug> fmt::Debug for Tx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_ref(), f)
+    }
+}
+
+impl<T: Notify + fmt::Display> fmt::Display for Tx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_ref(), f)
+    }
+}
+
+/// The read half of a split reference count, counted in a shard.
+pub struct Rx<T: Notify> {
+    shard: usize,
+    ptr: NonNull<Inner<Shards<T>>>,
+    phantom: PhantomData<T>,
+}
+
+unsafe impl<T: Sync + Send + Notify> Send for Rx<T> {}
+unsafe impl<T: Sync + Send + Notify> Sync for Rx<T> {}
+
+impl<T: Notify> Rx<T> {
+    fn inner(&self) -> &Inner<Shards<T>> {
+        // SAFETY: We know ptr is valid and do not create &mut.
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Returns true if both handles point to the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr == other.ptr
+    }
+}
+
+impl<T: Notify> Clone for Rx<T> {
+    fn clone(&self) -> Self {
+        let inner = self.inner();
+        let shard = current_shard(inner.data.shards.len());
+        if Shard::inc(&inner.data.shards[shard].rx) {
+            inner.count.inc_rx();
+        }
+        #[cfg(feature = "observer")]
+        inner.data.data.rx_did_clone();
+        Rx {
+            shard,
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Notify> Drop for Rx<T> {
+    fn drop(&mut self) {
+        let inner = self.inner();
+        #[cfg(feature = "observer")]
+        inner.data.data.rx_did_drop_one();
+        if Shard::dec(&inner.data.shards[self.shard].rx) {
+            release_rx(self.ptr)
+        }
+    }
+}
+
+impl<T: Notify> Deref for Rx<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner().data.data
+    }
+}
+
+impl<T: Notify> AsRef<T> for Rx<T> {
+    fn as_ref(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify> Borrow<T> for Rx<T> {
+    fn borrow(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + fmt::Debug> fmt::Debug for Rx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_ref(), f)
+    }
+}
+
+impl<T: Notify + fmt::Display> fmt::Display for Rx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_ref(), f)
+    }
+}
+
+/// Allocates a pointer holding `data` and returns a pair of sharded
+/// references.
+///
+/// The rules are the same as [crate::new].
+pub fn new<T: Notify>(data: T) -> (Tx<T>, Rx<T>) {
+    let shards: Vec<_> = (0..shard_count())
+        .map(|_| {
+            crate::CachePadded::new(Shard {
+                tx: AtomicUsize::new(0),
+                rx: AtomicUsize::new(0),
+            })
+        })
+        .collect();
+    let shards = shards.into_boxed_slice();
+    let shard = current_shard(shards.len());
+    // Both handles start in the calling thread's shard, which takes
+    // over the initial shared references.
+    shards[shard].tx.fetch_add(1, Ordering::Relaxed);
+    shards[shard].rx.fetch_add(1, Ordering::Relaxed);
+    let (tx, rx) = crate::new(Shards { shards, data });
+    let ptr = tx.ptr;
+    mem::forget(tx);
+    mem::forget(rx);
+    (
+        Tx {
+            shard,
+            ptr,
+            phantom: PhantomData,
+        },
+        Rx {
+            shard,
+            ptr,
+            phantom: PhantomData,
+        },
+    )
+}
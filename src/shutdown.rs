@@ -0,0 +1,122 @@
+//! Graceful shutdown.
+//!
+//! Clone a [ShutdownGuard] into each worker. A [ShutdownWatcher]
+//! waits until every guard has been dropped, so a server can drain
+//! in-flight work before exiting.
+//!
+//! ```
+//! let (guard, watcher) = splitrc::shutdown::new();
+//! for _ in 0..4 {
+//!     let guard = guard.clone();
+//!     std::thread::spawn(move || {
+//!         // ... do work ...
+//!         drop(guard);
+//!     });
+//! }
+//! drop(guard);
+//! watcher.wait();
+//! ```
+
+use crate::signal::Signal;
+use crate::{Rx, Tx};
+use core::fmt;
+
+#[cfg(not(loom))]
+use core::time::Duration;
+
+#[cfg(feature = "async")]
+use crate::RxClosed;
+#[cfg(feature = "async")]
+use core::future::Future;
+#[cfg(feature = "async")]
+use core::pin::Pin;
+#[cfg(feature = "async")]
+use core::task::{Context, Poll};
+
+/// Keeps shutdown from completing while alive.
+#[derive(Clone)]
+pub struct ShutdownGuard(Tx<Signal>);
+
+impl ShutdownGuard {
+    /// Returns true while any [ShutdownWatcher] remains.
+    pub fn is_watched(&self) -> bool {
+        Tx::rx_count(&self.0) != 0
+    }
+}
+
+impl fmt::Debug for ShutdownGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ShutdownGuard")
+    }
+}
+
+/// Waits for every [ShutdownGuard] to be dropped.
+#[derive(Clone)]
+pub struct ShutdownWatcher(Rx<Signal>);
+
+impl ShutdownWatcher {
+    /// Returns true once every [ShutdownGuard] has been dropped.
+    pub fn is_complete(&self) -> bool {
+        self.0.is_set()
+    }
+
+    /// Returns the number of live [ShutdownGuard]s.
+    pub fn guard_count(&self) -> u32 {
+        Rx::tx_count(&self.0)
+    }
+
+    /// Blocks the current thread until every [ShutdownGuard] has
+    /// been dropped.
+    pub fn wait(&self) {
+        self.0.wait()
+    }
+
+    /// Blocks the current thread until every [ShutdownGuard] has
+    /// been dropped or `timeout` elapses. Returns true if shutdown
+    /// completed.
+    #[cfg(not(loom))]
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        self.0.wait_timeout(timeout)
+    }
+
+    /// Returns a future that resolves when every [ShutdownGuard] has
+    /// been dropped.
+    #[cfg(feature = "async")]
+    pub fn drained(&self) -> Drained {
+        Drained(Rx::closed(&self.0))
+    }
+}
+
+impl fmt::Debug for ShutdownWatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShutdownWatcher")
+            .field("guards", &self.guard_count())
+            .finish()
+    }
+}
+
+/// Future returned by [ShutdownWatcher::drained].
+#[cfg(feature = "async")]
+pub struct Drained(RxClosed<Signal>);
+
+#[cfg(feature = "async")]
+impl Future for Drained {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+#[cfg(feature = "async")]
+impl fmt::Debug for Drained {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Drained")
+    }
+}
+
+/// Creates a connected [ShutdownGuard] and [ShutdownWatcher].
+pub fn new() -> (ShutdownGuard, ShutdownWatcher) {
+    let (tx, rx) = crate::new(Signal::default());
+    (ShutdownGuard(tx), ShutdownWatcher(rx))
+}
@@ -0,0 +1,59 @@
+//! A blocking flag set when the last [crate::Tx] drops.
+
+use crate::Notify;
+
+#[cfg(loom)]
+use loom::sync::{Condvar, Mutex};
+
+#[cfg(not(loom))]
+use std::sync::{Condvar, Mutex};
+
+#[cfg(not(loom))]
+use core::time::Duration;
+#[cfg(not(loom))]
+use std::time::Instant;
+
+#[derive(Default)]
+pub(crate) struct Signal {
+    set: Mutex<bool>,
+    condvar: Condvar,
+}
+
+impl Signal {
+    pub(crate) fn is_set(&self) -> bool {
+        *self.set.lock().unwrap()
+    }
+
+    pub(crate) fn wait(&self) {
+        let mut set = self.set.lock().unwrap();
+        while !*set {
+            set = self.condvar.wait(set).unwrap();
+        }
+    }
+
+    // Loom has no timed waits.
+    #[cfg(not(loom))]
+    pub(crate) fn wait_timeout(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now().checked_add(timeout);
+        let mut set = self.set.lock().unwrap();
+        while !*set {
+            let remaining = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(remaining) => remaining,
+                    None => return false,
+                },
+                // Too far in the future to represent.
+                None => Duration::MAX,
+            };
+            set = self.condvar.wait_timeout(set, remaining).unwrap().0;
+        }
+        true
+    }
+}
+
+impl Notify for Signal {
+    fn last_tx_did_drop(&self) {
+        *self.set.lock().unwrap() = true;
+        self.condvar.notify_all();
+    }
+}
@@ -0,0 +1,76 @@
+//! Slice and string payloads stored inline.
+
+use crate::header_slice::allocate_header_slice;
+use crate::{Inner, Notify, Rx, Tx};
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+fn pair<T: Notify + ?Sized>(ptr: NonNull<Inner<T>>) -> (Tx<T>, Rx<T>) {
+    (
+        Tx {
+            ptr,
+            phantom: PhantomData,
+        },
+        Rx {
+            ptr,
+            phantom: PhantomData,
+        },
+    )
+}
+
+fn allocate_slice<T>(len: usize, items: impl Iterator<Item = T>) -> NonNull<Inner<[T]>> {
+    let ptr = allocate_header_slice((), len, items);
+    // SAFETY: With a zero-sized header, HeaderSlice<(), [T]> has the
+    // same layout as [T], and the cast keeps the length.
+    let ptr = unsafe { NonNull::new_unchecked(ptr.as_ptr() as *mut Inner<[T]>) };
+    #[cfg(feature = "leak-track")]
+    // SAFETY: We do not create a &mut to Inner.
+    unsafe { ptr.as_ref() }
+        .leak
+        .set_type_name(core::any::type_name::<[T]>());
+    ptr
+}
+
+/// Allocates a pointer holding the items of `items` inline and
+/// returns a pair of references.
+///
+/// ```
+/// let (tx, rx) = splitrc::from_iter((0..4).map(|i| i * 2));
+/// assert_eq!([0, 2, 4, 6], *rx);
+/// # drop(tx);
+/// ```
+///
+/// Panics if the iterator reports the wrong length.
+pub fn from_iter<T, I>(items: I) -> (Tx<[T]>, Rx<[T]>)
+where
+    I: IntoIterator<Item = T>,
+    I::IntoIter: ExactSizeIterator,
+{
+    let items = items.into_iter();
+    pair(allocate_slice(items.len(), items))
+}
+
+/// Allocates a pointer holding clones of `items` inline and returns
+/// a pair of references.
+pub fn from_slice<T: Clone>(items: &[T]) -> (Tx<[T]>, Rx<[T]>) {
+    from_iter(items.iter().cloned())
+}
+
+/// Allocates a pointer holding a copy of `s` inline and returns a
+/// pair of references.
+///
+/// ```
+/// let (tx, rx) = splitrc::from_str("hello");
+/// assert_eq!("hello", &*tx);
+/// # drop(rx);
+/// ```
+pub fn from_str(s: &str) -> (Tx<str>, Rx<str>) {
+    let ptr = allocate_slice(s.len(), s.bytes());
+    // SAFETY: The bytes were copied from a str, and str has the same
+    // layout as [u8].
+    let ptr = unsafe { NonNull::new_unchecked(ptr.as_ptr() as *mut Inner<str>) };
+    #[cfg(feature = "leak-track")]
+    // SAFETY: We do not create a &mut to Inner.
+    unsafe { ptr.as_ref() }.leak.set_type_name("str");
+    pair(ptr)
+}
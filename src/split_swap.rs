@@ -0,0 +1,232 @@
+//! A [Tx] that readers can borrow while writers replace it.
+
+use crate::{Notify, Tx};
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop};
+use core::ops::Deref;
+use core::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, SeqCst};
+
+#[cfg(loom)]
+use loom::sync::atomic::{fence, AtomicPtr, AtomicUsize};
+#[cfg(loom)]
+use loom::sync::{Mutex, MutexGuard};
+
+#[cfg(not(loom))]
+use core::sync::atomic::{fence, AtomicPtr, AtomicUsize};
+#[cfg(not(loom))]
+use std::sync::{Mutex, MutexGuard};
+
+/// Holds the current [Tx] for a value that is read often and
+/// replaced rarely, like a configuration.
+///
+/// [SplitSwap::load] borrows the current value without locking or
+/// touching its reference counts. [SplitSwap::store] publishes a new
+/// value without waiting for readers: the old [Tx] is retired and
+/// dropped once every reader that might still see it has finished,
+/// at which point the usual notifications fire on its [crate::Rx]
+/// handles.
+///
+/// Readers are counted per epoch, and each store starts a new one.
+/// A retired value is dropped once each epoch's count has been seen
+/// at zero since it was retired, so a steady stream of new readers
+/// holds it back only until the next store moves them to the other
+/// epoch. A reader that keeps a guard alive delays the drop of every
+/// value retired while it was registered.
+///
+/// ```
+/// struct Config {
+///     verbose: bool,
+/// }
+/// impl splitrc::Notify for Config {}
+///
+/// let (tx, rx) = splitrc::new(Config { verbose: false });
+/// let config = splitrc::SplitSwap::new(tx);
+/// assert!(!config.load().verbose);
+///
+/// let (next, _next_rx) = splitrc::new(Config { verbose: true });
+/// config.store(next);
+/// assert!(config.load().verbose);
+/// // No reader saw the old value, so it was dropped right away.
+/// assert_eq!(0, splitrc::Rx::tx_count(&rx));
+/// ```
+pub struct SplitSwap<T: Notify> {
+    // From Tx::into_raw. Owns one tx reference.
+    ptr: AtomicPtr<T>,
+    // Which of `readers` new readers register in. Only writers flip
+    // it, and they hold `retired` while they do.
+    epoch: AtomicUsize,
+    readers: [AtomicUsize; 2],
+    // Replaced handles that readers might still see.
+    retired: Mutex<Vec<Retired<T>>>,
+    phantom: PhantomData<Tx<T>>,
+}
+
+impl<T: Notify> SplitSwap<T> {
+    /// Creates a container holding `tx`.
+    pub fn new(tx: Tx<T>) -> Self {
+        SplitSwap {
+            ptr: AtomicPtr::new(Tx::into_raw(tx) as *mut T),
+            epoch: AtomicUsize::new(0),
+            readers: [AtomicUsize::new(0), AtomicUsize::new(0)],
+            retired: Mutex::new(Vec::new()),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Borrows the current value. The guard keeps it alive but does
+    /// not block writers.
+    pub fn load(&self) -> SplitSwapGuard<'_, T> {
+        let epoch = loop {
+            let epoch = self.epoch.load(Relaxed);
+            self.readers[epoch].fetch_add(1, Relaxed);
+            // Pairs with the fence in store: either the writer sees
+            // this registration, or we see its new epoch and pointer.
+            fence(SeqCst);
+            // If the writer missed the registration, register again
+            // in the new epoch.
+            if self.epoch.load(Relaxed) == epoch {
+                break epoch;
+            }
+            self.leave(epoch);
+        };
+        SplitSwapGuard {
+            swap: self,
+            epoch,
+            // Acquire observes the payload written before the store.
+            ptr: self.ptr.load(Acquire),
+        }
+    }
+
+    /// Returns a new [Tx] to the current value.
+    pub fn load_full(&self) -> Tx<T> {
+        self.load().to_tx()
+    }
+
+    /// Publishes `tx`, retiring the previous [Tx].
+    pub fn store(&self, tx: Tx<T>) {
+        let mut retired = self.lock();
+        let old = self.ptr.swap(Tx::into_raw(tx) as *mut T, AcqRel);
+        // Writers hold the lock, so nobody else flips the epoch.
+        let epoch = self.epoch.load(Relaxed);
+        self.epoch.store(epoch ^ 1, Relaxed);
+        // See load.
+        fence(SeqCst);
+        retired.push(Retired {
+            // SAFETY: The pointer came from Tx::into_raw, and swapping
+            // it out transferred its reference to us.
+            tx: unsafe { Tx::from_raw(old) },
+            drained: [false; 2],
+        });
+        // A reader registered in either epoch may have loaded the old
+        // pointer, even one registered before an earlier store, so
+        // check both.
+        let mut reclaimed = self.reclaim(&mut retired, 0);
+        reclaimed.extend(self.reclaim(&mut retired, 1));
+        drop(retired);
+        // Dropping may notify, so do it outside of the lock.
+        drop(reclaimed);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Retired<T>>> {
+        // Handles are dropped outside of the lock, so a poisoned list
+        // is still consistent.
+        self.retired.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // If none of `epoch`'s readers remain, marks it drained for every
+    // retired handle, and returns the handles drained in both epochs.
+    // Checking under the lock orders the check after every
+    // retirement, and the store that retired a handle checked both
+    // counts after its fence, so later checks see every reader that
+    // might have loaded it. Anything retired was already
+    // unpublished, so readers registered later cannot see it.
+    fn reclaim(&self, retired: &mut Vec<Retired<T>>, epoch: usize) -> Vec<Tx<T>> {
+        // Acquire pairs with the release in leave, so readers are done
+        // with the payload.
+        if self.readers[epoch].load(Acquire) != 0 {
+            return Vec::new();
+        }
+        for r in retired.iter_mut() {
+            r.drained[epoch] = true;
+        }
+        let (drained, kept): (Vec<_>, Vec<_>) = mem::take(retired)
+            .into_iter()
+            .partition(|r| r.drained == [true; 2]);
+        *retired = kept;
+        drained.into_iter().map(|r| r.tx).collect()
+    }
+
+    fn leave(&self, epoch: usize) {
+        if self.readers[epoch].fetch_sub(1, AcqRel) == 1 {
+            let mut retired = self.lock();
+            let reclaimed = self.reclaim(&mut retired, epoch);
+            drop(retired);
+            drop(reclaimed);
+        }
+    }
+}
+
+impl<T: Notify> Drop for SplitSwap<T> {
+    fn drop(&mut self) {
+        // SAFETY: See store. No guards remain, and retired handles
+        // are dropped with the container.
+        drop(unsafe { Tx::from_raw(self.ptr.load(Relaxed)) });
+    }
+}
+
+impl<T: Notify + fmt::Debug> fmt::Debug for SplitSwap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SplitSwap")
+            .field("value", &*self.load())
+            .finish()
+    }
+}
+
+struct Retired<T: Notify> {
+    tx: Tx<T>,
+    // Whether each epoch's count has been seen at zero since the
+    // handle was retired.
+    drained: [bool; 2],
+}
+
+/// A borrow of the value in a [SplitSwap]. Obtained from
+/// [SplitSwap::load].
+pub struct SplitSwapGuard<'a, T: Notify> {
+    swap: &'a SplitSwap<T>,
+    epoch: usize,
+    ptr: *const T,
+}
+
+impl<T: Notify> SplitSwapGuard<'_, T> {
+    /// Returns a new [Tx] to the borrowed value, which may no longer
+    /// be current.
+    pub fn to_tx(&self) -> Tx<T> {
+        // SAFETY: The borrowed value's Tx is either current or
+        // retired, and either way is not dropped while this guard
+        // lives. Cloning through it adds a reference of our own.
+        unsafe { Tx::clone(&ManuallyDrop::new(Tx::from_raw(self.ptr))) }
+    }
+}
+
+impl<T: Notify> Deref for SplitSwapGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: See to_tx.
+        unsafe { &*self.ptr }
+    }
+}
+
+impl<T: Notify> Drop for SplitSwapGuard<'_, T> {
+    fn drop(&mut self) {
+        self.swap.leave(self.epoch)
+    }
+}
+
+impl<T: Notify + fmt::Debug> fmt::Debug for SplitSwapGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
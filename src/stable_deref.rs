@@ -0,0 +1,12 @@
+//! [StableDeref] for handles, so they can own the target of
+//! self-referential borrows.
+
+use crate::{Allocator, Notify, Rx, Tx};
+use stable_deref_trait::{CloneStableDeref, StableDeref};
+
+// SAFETY: The payload lives in the shared allocation, so moving or
+// cloning a handle does not move it.
+unsafe impl<T: Notify + ?Sized, A: Allocator> StableDeref for Tx<T, A> {}
+unsafe impl<T: Notify + ?Sized, A: Allocator> CloneStableDeref for Tx<T, A> {}
+unsafe impl<T: Notify + ?Sized, A: Allocator> StableDeref for Rx<T, A> {}
+unsafe impl<T: Notify + ?Sized, A: Allocator> CloneStableDeref for Rx<T, A> {}
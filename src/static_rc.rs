@@ -0,0 +1,79 @@
+//! Split reference counts on values with static storage.
+
+use crate::{AtomicPacked, AtomicWeak, Inner, Notify, Rx, SplitCount, Tx, WeakCount, RC_INIT};
+use allocator_api2::alloc::Global;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+
+/// Storage for a payload in a `static`, with no heap allocation.
+///
+/// The storage itself permanently holds one [Tx] reference and one
+/// [Rx] reference. Neither count reaches zero, so notifications never
+/// fire and the payload is never dropped or freed.
+///
+/// ```
+/// # struct Config { verbose: bool }
+/// # impl splitrc::Notify for Config {}
+/// static CONFIG: splitrc::StaticSplitRc<Config> =
+///     splitrc::StaticSplitRc::new(Config { verbose: false });
+///
+/// let (tx, rx) = CONFIG.handles();
+/// assert!(!rx.verbose);
+/// # drop(tx);
+/// ```
+pub struct StaticSplitRc<T: Notify> {
+    inner: Inner<T>,
+}
+
+impl<T: Notify> StaticSplitRc<T> {
+    /// Creates storage holding `data`.
+    pub const fn new(data: T) -> Self {
+        StaticSplitRc {
+            inner: Inner {
+                // The storage's own references.
+                count: SplitCount(AtomicPacked::new(RC_INIT)),
+                weak: WeakCount(AtomicWeak::new(1)),
+                #[cfg(feature = "async")]
+                wakers: crate::closed::Wakers::new(),
+                #[cfg(feature = "event-listener")]
+                events: crate::listener::Events::new(),
+                #[cfg(feature = "drop-callbacks")]
+                subscribers: crate::subscribers::Subscribers::new(),
+                #[cfg(feature = "leak-track")]
+                leak: crate::leak::Entry::untracked(),
+                #[cfg(feature = "metrics")]
+                metrics_label: None,
+                #[cfg(feature = "revive")]
+                revival: None,
+                alloc: Global,
+                data: ManuallyDrop::new(data),
+            },
+        }
+    }
+
+    /// Returns a new pair of references to the payload.
+    pub fn handles(&'static self) -> (Tx<T>, Rx<T>) {
+        self.inner.inc_tx();
+        self.inner.inc_rx();
+        // Handles never free the allocation or mutate through ptr.
+        let ptr = NonNull::from(&self.inner);
+        (
+            Tx {
+                ptr,
+                phantom: PhantomData,
+            },
+            Rx {
+                ptr,
+                phantom: PhantomData,
+            },
+        )
+    }
+}
+
+impl<T: Notify + fmt::Debug> fmt::Debug for StaticSplitRc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.inner.data, f)
+    }
+}
@@ -0,0 +1,117 @@
+//! Split reference counts in caller-provided memory.
+
+use crate::{Inner, Notify, Rx, Shared, Tx};
+use allocator_api2::alloc::{AllocError, Allocator};
+use core::alloc::Layout;
+use core::fmt;
+use core::marker::{PhantomData, PhantomPinned};
+use core::mem::MaybeUninit;
+use core::pin::Pin;
+use core::ptr::NonNull;
+
+/// Memory for one split reference count and its payload, supplied by
+/// the caller to [new_in_place].
+#[repr(C)]
+pub struct SplitStorage<T: Notify + 'static> {
+    // Must be first: StorageRelease recovers the storage from the
+    // address of Inner.
+    inner: MaybeUninit<Inner<T, Shared, StorageRelease<T>>>,
+    // Handles point into the storage.
+    _pin: PhantomPinned,
+}
+
+impl<T: Notify + 'static> SplitStorage<T> {
+    /// Creates empty storage.
+    pub const fn new() -> Self {
+        SplitStorage {
+            inner: MaybeUninit::uninit(),
+            _pin: PhantomPinned,
+        }
+    }
+}
+
+impl<T: Notify + 'static> Default for SplitStorage<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Notify + 'static> fmt::Debug for SplitStorage<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SplitStorage")
+    }
+}
+
+/// The allocator of handles created by [new_in_place]. Instead of
+/// freeing memory, it returns the [SplitStorage] to a callback.
+pub struct StorageRelease<T: Notify + 'static> {
+    release: fn(Pin<&'static mut SplitStorage<T>>),
+}
+
+impl<T: Notify + 'static> fmt::Debug for StorageRelease<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StorageRelease")
+    }
+}
+
+// SAFETY: allocate never hands out memory, and deallocate is only
+// called by release_weak with the Inner written by new_in_place.
+unsafe impl<T: Notify + 'static> Allocator for StorageRelease<T> {
+    fn allocate(&self, _layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        Err(AllocError)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, _layout: Layout) {
+        let storage = ptr.cast::<SplitStorage<T>>().as_ptr();
+        // SAFETY: No handle refers to the storage anymore, so the
+        // original exclusive borrow can be handed back.
+        (self.release)(Pin::new_unchecked(&mut *storage))
+    }
+}
+
+/// Places `data` in `storage` and returns a pair of references.
+///
+/// Notifications follow the same rules as [crate::new]. When every
+/// handle and weak reference is gone, `release` receives the storage
+/// back instead of the memory being freed, for example to return it
+/// to a fixed pool.
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// # struct MyValue {}
+/// # impl splitrc::Notify for MyValue {}
+/// let storage = Pin::static_mut(Box::leak(Box::new(splitrc::SplitStorage::new())));
+/// let (tx, rx) = splitrc::new_in_place(storage, MyValue {}, |storage| {
+///     // Reuse or recycle the storage.
+///     # drop(unsafe { Box::from_raw(Pin::get_unchecked_mut(storage)) });
+/// });
+/// # drop((tx, rx));
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn new_in_place<T: Notify + 'static>(
+    storage: Pin<&'static mut SplitStorage<T>>,
+    data: T,
+    release: fn(Pin<&'static mut SplitStorage<T>>),
+) -> (Tx<T, StorageRelease<T>>, Rx<T, StorageRelease<T>>) {
+    // SAFETY: The storage is not moved out of; Inner is written in
+    // place and handles keep pointing at it.
+    let storage = unsafe { Pin::get_unchecked_mut(storage) };
+    let ptr = storage.inner.as_mut_ptr();
+    // SAFETY: ptr is valid for writes. Any previous Inner was fully
+    // released before the storage was handed back.
+    unsafe { ptr.write(Inner::new_in(data, StorageRelease { release })) };
+    // SAFETY: ptr comes from a reference.
+    let ptr = unsafe { NonNull::new_unchecked(ptr) };
+    lifecycle_event!(ptr, "allocate");
+    (
+        Tx {
+            ptr,
+            phantom: PhantomData,
+        },
+        Rx {
+            ptr,
+            phantom: PhantomData,
+        },
+    )
+}
@@ -0,0 +1,104 @@
+//! [Stream] and [Sink] through pinned handles.
+
+use crate::{Notify, Rx, Tx};
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_core::Stream;
+use futures_sink::Sink;
+
+/// A stream that is polled through a shared reference, so the
+/// payload of a [Rx] can produce items.
+///
+/// A `Pin<Rx<T>>` is a [Stream] of the payload's items. Payloads that
+/// implement [Unpin] can be pinned with [Pin::new].
+///
+/// ```
+/// use std::pin::Pin;
+/// use std::sync::atomic::{AtomicU32, Ordering};
+/// use std::task::{Context, Poll};
+///
+/// struct Countdown(AtomicU32);
+/// impl splitrc::Notify for Countdown {}
+///
+/// impl splitrc::SharedStream for Countdown {
+///     type Item = u32;
+///
+///     fn poll_next(self: Pin<&Self>, _cx: &mut Context<'_>) -> Poll<Option<u32>> {
+///         let prev = self.0.fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1));
+///         Poll::Ready(prev.ok())
+///     }
+/// }
+///
+/// fn assert_stream<S: futures_core::Stream<Item = u32>>(_: &S) {}
+///
+/// let (tx, rx) = splitrc::new(Countdown(AtomicU32::new(3)));
+/// let rx = Pin::new(rx);
+/// assert_stream(&rx);
+/// # drop(tx);
+/// ```
+pub trait SharedStream {
+    /// See [Stream::Item].
+    type Item;
+
+    /// See [Stream::poll_next].
+    fn poll_next(self: Pin<&Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>>;
+
+    /// See [Stream::size_hint].
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+/// A sink that is driven through a shared reference, so the payload
+/// of a [Tx] can accept items.
+///
+/// A `Pin<Tx<T>>` is a [Sink] for the payload's items. See
+/// [SharedStream].
+pub trait SharedSink<Item> {
+    /// See [Sink::Error].
+    type Error;
+
+    /// See [Sink::poll_ready].
+    fn poll_ready(self: Pin<&Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>>;
+
+    /// See [Sink::start_send].
+    fn start_send(self: Pin<&Self>, item: Item) -> Result<(), Self::Error>;
+
+    /// See [Sink::poll_flush].
+    fn poll_flush(self: Pin<&Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>>;
+
+    /// See [Sink::poll_close].
+    fn poll_close(self: Pin<&Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>>;
+}
+
+impl<T: Notify + SharedStream + ?Sized> Stream for Pin<Rx<T>> {
+    type Item = T::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T::Item>> {
+        self.as_ref().get_ref().as_ref().poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (**self).size_hint()
+    }
+}
+
+impl<T: Notify + SharedSink<Item> + ?Sized, Item> Sink<Item> for Pin<Tx<T>> {
+    type Error = T::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), T::Error>> {
+        self.as_ref().get_ref().as_ref().poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), T::Error> {
+        self.as_ref().get_ref().as_ref().start_send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), T::Error>> {
+        self.as_ref().get_ref().as_ref().poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), T::Error>> {
+        self.as_ref().get_ref().as_ref().poll_close(cx)
+    }
+}
@@ -0,0 +1,173 @@
+//! Callbacks registered at runtime, called when a half closes.
+
+use crate::{Notify, Rx, Tx};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use allocator_api2::alloc::Allocator;
+use core::mem;
+
+#[cfg(loom)]
+use loom::sync::{Mutex, MutexGuard};
+
+#[cfg(not(loom))]
+use std::sync::{Mutex, MutexGuard};
+
+/// Identifies a callback registered with [Rx::on_tx_drop] or
+/// [Tx::on_rx_drop].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+type Callback = Box<dyn FnOnce() + Send>;
+
+struct List {
+    next_id: u64,
+    fired: bool,
+    callbacks: Vec<(SubscriptionId, Callback)>,
+}
+
+impl List {
+    #[cfg(not(loom))]
+    const fn new() -> Self {
+        List {
+            next_id: 0,
+            fired: false,
+            callbacks: Vec::new(),
+        }
+    }
+
+    #[cfg(loom)]
+    fn new() -> Self {
+        List {
+            next_id: 0,
+            fired: false,
+            callbacks: Vec::new(),
+        }
+    }
+}
+
+// Like the wakers, each list is emptied when its half closes, or
+// when the allocation is dropped, so it holds no memory after.
+pub(crate) struct Subscribers {
+    tx_dropped: Mutex<List>,
+    rx_dropped: Mutex<List>,
+}
+
+impl Subscribers {
+    #[cfg(not(loom))]
+    pub(crate) const fn new() -> Self {
+        Subscribers {
+            tx_dropped: Mutex::new(List::new()),
+            rx_dropped: Mutex::new(List::new()),
+        }
+    }
+
+    #[cfg(loom)]
+    pub(crate) fn new() -> Self {
+        Subscribers {
+            tx_dropped: Mutex::new(List::new()),
+            rx_dropped: Mutex::new(List::new()),
+        }
+    }
+
+    pub(crate) fn fire_tx_dropped(&self) {
+        fire(&self.tx_dropped)
+    }
+
+    pub(crate) fn fire_rx_dropped(&self) {
+        fire(&self.rx_dropped)
+    }
+}
+
+impl Default for Subscribers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn lock(list: &Mutex<List>) -> MutexGuard<'_, List> {
+    // Callbacks run outside of the lock, so a poisoned list is still
+    // consistent.
+    list.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn subscribe(list: &Mutex<List>, callback: Callback) -> SubscriptionId {
+    let mut guard = lock(list);
+    let id = SubscriptionId(guard.next_id);
+    guard.next_id += 1;
+    if guard.fired {
+        drop(guard);
+        callback();
+    } else {
+        guard.callbacks.push((id, callback));
+    }
+    id
+}
+
+fn unsubscribe(list: &Mutex<List>, id: SubscriptionId) -> bool {
+    let mut guard = lock(list);
+    match guard.callbacks.iter().position(|(other, _)| *other == id) {
+        Some(index) => {
+            let (_, callback) = guard.callbacks.remove(index);
+            drop(guard);
+            // Drop captures outside of the lock.
+            drop(callback);
+            true
+        }
+        None => false,
+    }
+}
+
+fn fire(list: &Mutex<List>) {
+    let callbacks = {
+        let mut guard = lock(list);
+        guard.fired = true;
+        mem::take(&mut guard.callbacks)
+    };
+    // Call outside of the lock so callbacks may subscribe.
+    for (_, callback) in callbacks {
+        callback();
+    }
+}
+
+impl<T: Notify + ?Sized, A: Allocator> Tx<T, A> {
+    /// Registers `callback` to be called when the last [Rx] is
+    /// dropped. Calls it immediately if that has already happened.
+    ///
+    /// Callbacks run in registration order, after
+    /// [Notify::last_rx_did_drop], on the thread that dropped the last
+    /// [Rx]. If the last [Tx] goes first, they run when the
+    /// allocation is dropped instead.
+    pub fn on_rx_drop(this: &Self, callback: impl FnOnce() + Send + 'static) -> SubscriptionId {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        subscribe(&inner.subscribers.rx_dropped, Box::new(callback))
+    }
+
+    /// Removes a callback registered with [Tx::on_rx_drop]. Returns
+    /// false if it has already been called or removed.
+    pub fn unsubscribe_rx_drop(this: &Self, id: SubscriptionId) -> bool {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        unsubscribe(&inner.subscribers.rx_dropped, id)
+    }
+}
+
+impl<T: Notify + ?Sized, A: Allocator> Rx<T, A> {
+    /// Registers `callback` to be called when the last [Tx] is
+    /// dropped. Calls it immediately if that has already happened.
+    ///
+    /// See [Tx::on_rx_drop].
+    pub fn on_tx_drop(this: &Self, callback: impl FnOnce() + Send + 'static) -> SubscriptionId {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        subscribe(&inner.subscribers.tx_dropped, Box::new(callback))
+    }
+
+    /// Removes a callback registered with [Rx::on_tx_drop]. Returns
+    /// false if it has already been called or removed.
+    pub fn unsubscribe_tx_drop(this: &Self, id: SubscriptionId) -> bool {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        unsubscribe(&inner.subscribers.tx_dropped, id)
+    }
+}
@@ -0,0 +1,252 @@
+//! Handles that carry a few user bits in the pointer's alignment.
+
+use crate::{Inner, Notify, Rx, Tx};
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop};
+use core::ops::Deref;
+use core::ptr::NonNull;
+
+/// Returns the mask of the low `bits` bits, failing to compile if the
+/// allocation's alignment does not leave that many free.
+const fn tag_mask(bits: u8, align: usize) -> usize {
+    assert!(
+        (bits as u32) < usize::BITS && 1 << bits <= align,
+        "the allocation's alignment does not leave BITS free bits"
+    );
+    (1 << bits) - 1
+}
+
+/// Adds `tag` to the address, keeping the allocation's provenance.
+fn with_tag<T: Notify>(ptr: NonNull<Inner<T>>, tag: usize) -> NonNull<Inner<T>> {
+    // SAFETY: tag is smaller than the alignment, so the result stays
+    // within the allocation and cannot be null.
+    unsafe { NonNull::new_unchecked(ptr.as_ptr().cast::<u8>().wrapping_add(tag).cast()) }
+}
+
+/// Splits a tagged pointer into the allocation and its tag.
+fn untag<T: Notify>(ptr: NonNull<Inner<T>>, mask: usize) -> (NonNull<Inner<T>>, usize) {
+    let tag = ptr.as_ptr() as usize & mask;
+    // SAFETY: Subtracting the tag restores the original address.
+    let ptr = unsafe { NonNull::new_unchecked(ptr.as_ptr().cast::<u8>().wrapping_sub(tag).cast()) };
+    (ptr, tag)
+}
+
+/// A [Tx] that stores a `BITS`-bit tag in the low bits of its
+/// pointer, so a state machine can carry a discriminant without
+/// widening the handle.
+///
+/// Allocations are aligned to at least their counts, leaving at least
+/// two free bits, and three on 64-bit targets without the
+/// `compact-counts` feature. Choosing more bits than the payload's
+/// alignment leaves fails to compile.
+///
+/// ```
+/// use splitrc::TaggedTx;
+///
+/// struct Connection {}
+/// impl splitrc::Notify for Connection {}
+///
+/// const IDLE: usize = 0;
+/// const BUSY: usize = 1;
+///
+/// let (tx, rx) = splitrc::new(Connection {});
+/// let mut tx: TaggedTx<Connection, 1> = TaggedTx::new(tx, IDLE);
+/// TaggedTx::set_tag(&mut tx, BUSY);
+/// assert_eq!(BUSY, TaggedTx::tag(&tx));
+/// assert_eq!(std::mem::size_of::<splitrc::Tx<Connection>>(), std::mem::size_of_val(&tx));
+/// # drop(rx);
+/// ```
+///
+/// ```compile_fail
+/// # struct Connection {}
+/// # impl splitrc::Notify for Connection {}
+/// let (tx, rx) = splitrc::new(Connection {});
+/// let tx: splitrc::TaggedTx<Connection, 7> = splitrc::TaggedTx::from(tx);
+/// ```
+pub struct TaggedTx<T: Notify, const BITS: u8> {
+    // Tagged; never dereferenced directly.
+    ptr: NonNull<Inner<T>>,
+    phantom: PhantomData<Inner<T>>,
+}
+
+unsafe impl<T: Sync + Send + Notify, const BITS: u8> Send for TaggedTx<T, BITS> {}
+unsafe impl<T: Sync + Send + Notify, const BITS: u8> Sync for TaggedTx<T, BITS> {}
+
+impl<T: Notify, const BITS: u8> TaggedTx<T, BITS> {
+    const MASK: usize = tag_mask(BITS, mem::align_of::<Inner<T>>());
+
+    /// Tags `tx` with `tag`.
+    ///
+    /// Panics if `tag` does not fit in `BITS` bits.
+    pub fn new(tx: Tx<T>, tag: usize) -> Self {
+        assert!(tag <= Self::MASK, "tag does not fit in {} bits", BITS);
+        let tx = ManuallyDrop::new(tx);
+        TaggedTx {
+            ptr: with_tag(tx.ptr, tag),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the tag.
+    pub fn tag(this: &Self) -> usize {
+        untag(this.ptr, Self::MASK).1
+    }
+
+    /// Replaces the tag.
+    ///
+    /// Panics if `tag` does not fit in `BITS` bits.
+    pub fn set_tag(this: &mut Self, tag: usize) {
+        assert!(tag <= Self::MASK, "tag does not fit in {} bits", BITS);
+        this.ptr = with_tag(untag(this.ptr, Self::MASK).0, tag);
+    }
+
+    /// Discards the tag and returns the handle.
+    pub fn into_tx(this: Self) -> Tx<T> {
+        let this = ManuallyDrop::new(this);
+        Tx {
+            ptr: untag(this.ptr, Self::MASK).0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Borrows the untagged handle.
+    fn tx(&self) -> ManuallyDrop<Tx<T>> {
+        ManuallyDrop::new(Tx {
+            ptr: untag(self.ptr, Self::MASK).0,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<T: Notify, const BITS: u8> From<Tx<T>> for TaggedTx<T, BITS> {
+    /// Tags `tx` with zero.
+    fn from(tx: Tx<T>) -> Self {
+        TaggedTx::new(tx, 0)
+    }
+}
+
+impl<T: Notify, const BITS: u8> Drop for TaggedTx<T, BITS> {
+    fn drop(&mut self) {
+        drop(ManuallyDrop::into_inner(self.tx()))
+    }
+}
+
+impl<T: Notify, const BITS: u8> Clone for TaggedTx<T, BITS> {
+    fn clone(&self) -> Self {
+        TaggedTx::new(Tx::clone(&self.tx()), TaggedTx::tag(self))
+    }
+}
+
+impl<T: Notify, const BITS: u8> Deref for TaggedTx<T, BITS> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: We know ptr is valid, and we do not create a &mut
+        // to Inner.
+        &unsafe { untag(self.ptr, Self::MASK).0.as_ref() }.data
+    }
+}
+
+impl<T: Notify + fmt::Debug, const BITS: u8> fmt::Debug for TaggedTx<T, BITS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaggedTx")
+            .field("tag", &TaggedTx::tag(self))
+            .field("data", &**self)
+            .finish()
+    }
+}
+
+/// A [Rx] that stores a `BITS`-bit tag in the low bits of its
+/// pointer. See [TaggedTx].
+pub struct TaggedRx<T: Notify, const BITS: u8> {
+    // Tagged; never dereferenced directly.
+    ptr: NonNull<Inner<T>>,
+    phantom: PhantomData<Inner<T>>,
+}
+
+unsafe impl<T: Sync + Send + Notify, const BITS: u8> Send for TaggedRx<T, BITS> {}
+unsafe impl<T: Sync + Send + Notify, const BITS: u8> Sync for TaggedRx<T, BITS> {}
+
+impl<T: Notify, const BITS: u8> TaggedRx<T, BITS> {
+    const MASK: usize = tag_mask(BITS, mem::align_of::<Inner<T>>());
+
+    /// Tags `rx` with `tag`.
+    ///
+    /// Panics if `tag` does not fit in `BITS` bits.
+    pub fn new(rx: Rx<T>, tag: usize) -> Self {
+        assert!(tag <= Self::MASK, "tag does not fit in {} bits", BITS);
+        let rx = ManuallyDrop::new(rx);
+        TaggedRx {
+            ptr: with_tag(rx.ptr, tag),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the tag.
+    pub fn tag(this: &Self) -> usize {
+        untag(this.ptr, Self::MASK).1
+    }
+
+    /// Replaces the tag.
+    ///
+    /// Panics if `tag` does not fit in `BITS` bits.
+    pub fn set_tag(this: &mut Self, tag: usize) {
+        assert!(tag <= Self::MASK, "tag does not fit in {} bits", BITS);
+        this.ptr = with_tag(untag(this.ptr, Self::MASK).0, tag);
+    }
+
+    /// Discards the tag and returns the handle.
+    pub fn into_rx(this: Self) -> Rx<T> {
+        let this = ManuallyDrop::new(this);
+        Rx {
+            ptr: untag(this.ptr, Self::MASK).0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Borrows the untagged handle.
+    fn rx(&self) -> ManuallyDrop<Rx<T>> {
+        ManuallyDrop::new(Rx {
+            ptr: untag(self.ptr, Self::MASK).0,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<T: Notify, const BITS: u8> From<Rx<T>> for TaggedRx<T, BITS> {
+    /// Tags `rx` with zero.
+    fn from(rx: Rx<T>) -> Self {
+        TaggedRx::new(rx, 0)
+    }
+}
+
+impl<T: Notify, const BITS: u8> Drop for TaggedRx<T, BITS> {
+    fn drop(&mut self) {
+        drop(ManuallyDrop::into_inner(self.rx()))
+    }
+}
+
+impl<T: Notify, const BITS: u8> Clone for TaggedRx<T, BITS> {
+    fn clone(&self) -> Self {
+        TaggedRx::new(Rx::clone(&self.rx()), TaggedRx::tag(self))
+    }
+}
+
+impl<T: Notify, const BITS: u8> Deref for TaggedRx<T, BITS> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: See TaggedTx::deref.
+        &unsafe { untag(self.ptr, Self::MASK).0.as_ref() }.data
+    }
+}
+
+impl<T: Notify + fmt::Debug, const BITS: u8> fmt::Debug for TaggedRx<T, BITS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaggedRx")
+            .field("tag", &TaggedRx::tag(self))
+            .field("data", &**self)
+            .finish()
+    }
+}
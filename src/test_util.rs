@@ -0,0 +1,166 @@
+//! [Notify] fixtures for testing code that relies on notifications.
+//!
+//! ```
+//! use splitrc::test_util::TrackNotify;
+//!
+//! let (tx, rx) = splitrc::new(TrackNotify::default());
+//! drop(tx);
+//! assert_eq!((true, false), rx.access());
+//! ```
+
+use crate::Notify;
+use alloc::sync::Arc;
+use core::fmt;
+use core::sync::atomic::Ordering;
+
+#[cfg(loom)]
+use loom::sync::atomic::{AtomicBool, AtomicUsize};
+
+#[cfg(not(loom))]
+use core::sync::atomic::{AtomicBool, AtomicUsize};
+
+/// Ignores every notification.
+#[derive(Debug)]
+pub struct Unit;
+
+impl Notify for Unit {}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt("Unit", f)
+    }
+}
+
+/// Records whether each half's last handle has been dropped.
+#[derive(Debug, Default)]
+pub struct TrackNotify {
+    /// Set when the last [crate::Tx] is dropped.
+    pub tx_did_drop: AtomicBool,
+    /// Set when the last [crate::Rx] is dropped.
+    pub rx_did_drop: AtomicBool,
+}
+
+impl Notify for TrackNotify {
+    fn last_tx_did_drop(&self) {
+        self.tx_did_drop.store(true, Ordering::Release);
+    }
+
+    fn last_rx_did_drop(&self) {
+        self.rx_did_drop.store(true, Ordering::Release);
+    }
+}
+
+impl TrackNotify {
+    /// Returns whether the tx and rx halves have been notified.
+    pub fn access(&self) -> (bool, bool) {
+        (
+            self.tx_did_drop.load(Ordering::Acquire),
+            self.rx_did_drop.load(Ordering::Acquire),
+        )
+    }
+}
+
+/// Panics from the selected notifications, for testing unwinding
+/// out of a drop.
+#[derive(Debug)]
+pub struct PanickingNotify {
+    /// Panic when the last [crate::Tx] is dropped.
+    pub panic_on_tx_drop: bool,
+    /// Panic when the last [crate::Rx] is dropped.
+    pub panic_on_rx_drop: bool,
+}
+
+impl PanickingNotify {
+    /// Panics from both notifications.
+    pub fn new() -> Self {
+        PanickingNotify {
+            panic_on_tx_drop: true,
+            panic_on_rx_drop: true,
+        }
+    }
+}
+
+impl Default for PanickingNotify {
+    fn default() -> Self {
+        PanickingNotify::new()
+    }
+}
+
+impl Notify for PanickingNotify {
+    fn last_tx_did_drop(&self) {
+        if self.panic_on_tx_drop {
+            panic!("PanickingNotify: last tx dropped")
+        }
+    }
+
+    fn last_rx_did_drop(&self) {
+        if self.panic_on_rx_drop {
+            panic!("PanickingNotify: last rx dropped")
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    tx_drops: AtomicUsize,
+    rx_drops: AtomicUsize,
+    deallocations: AtomicUsize,
+}
+
+/// Counts notifications across every allocation holding one of its
+/// clones.
+///
+/// Clones share their counts, so a test can keep one and read the
+/// counts after the allocations holding the others are freed.
+///
+/// ```
+/// use splitrc::test_util::CountingNotify;
+///
+/// let counts = CountingNotify::new();
+/// for _ in 0..3 {
+///     let (tx, rx) = splitrc::new(counts.clone());
+///     drop(tx);
+///     drop(rx);
+/// }
+/// assert_eq!(3, counts.tx_drops());
+/// assert_eq!(0, counts.rx_drops());
+/// assert_eq!(3, counts.deallocations());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CountingNotify(Arc<Counters>);
+
+impl CountingNotify {
+    /// Returns a new set of counts, all zero.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the number of last-tx notifications.
+    pub fn tx_drops(&self) -> usize {
+        self.0.tx_drops.load(Ordering::Acquire)
+    }
+
+    /// Returns the number of last-rx notifications.
+    pub fn rx_drops(&self) -> usize {
+        self.0.rx_drops.load(Ordering::Acquire)
+    }
+
+    /// Returns the number of [Notify::will_deallocate] calls.
+    pub fn deallocations(&self) -> usize {
+        self.0.deallocations.load(Ordering::Acquire)
+    }
+}
+
+impl Notify for CountingNotify {
+    fn last_tx_did_drop(&self) {
+        self.0.tx_drops.fetch_add(1, Ordering::AcqRel);
+    }
+
+    fn last_rx_did_drop(&self) {
+        self.0.rx_drops.fetch_add(1, Ordering::AcqRel);
+    }
+
+    fn will_deallocate(&self) {
+        self.0.deallocations.fetch_add(1, Ordering::AcqRel);
+    }
+}
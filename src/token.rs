@@ -0,0 +1,100 @@
+//! Cancellation tokens.
+//!
+//! A [CancelSource] is the tx half and a [CancelWatcher] is the rx
+//! half. When the last [CancelSource] is dropped, every watcher is
+//! cancelled.
+//!
+//! ```
+//! let (source, watcher) = splitrc::token::new();
+//! let worker = std::thread::spawn(move || watcher.wait());
+//! drop(source);
+//! worker.join().unwrap();
+//! ```
+
+use crate::signal::Signal;
+use crate::{Rx, Tx};
+use core::fmt;
+
+#[cfg(feature = "async")]
+use crate::RxClosed;
+#[cfg(feature = "async")]
+use core::future::Future;
+#[cfg(feature = "async")]
+use core::pin::Pin;
+#[cfg(feature = "async")]
+use core::task::{Context, Poll};
+
+/// Cancels every [CancelWatcher] when the last source is dropped.
+#[derive(Clone)]
+pub struct CancelSource(Tx<Signal>);
+
+impl CancelSource {
+    /// Returns true while any [CancelWatcher] remains.
+    pub fn has_watchers(&self) -> bool {
+        Tx::rx_count(&self.0) != 0
+    }
+}
+
+impl fmt::Debug for CancelSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CancelSource")
+    }
+}
+
+/// Observes whether the last [CancelSource] has been dropped.
+#[derive(Clone)]
+pub struct CancelWatcher(Rx<Signal>);
+
+impl CancelWatcher {
+    /// Returns true once the last [CancelSource] has been dropped.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_set()
+    }
+
+    /// Blocks the current thread until the last [CancelSource] has
+    /// been dropped.
+    pub fn wait(&self) {
+        self.0.wait()
+    }
+
+    /// Returns a future that resolves when the last [CancelSource]
+    /// has been dropped.
+    #[cfg(feature = "async")]
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled(Rx::closed(&self.0))
+    }
+}
+
+impl fmt::Debug for CancelWatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancelWatcher")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// Future returned by [CancelWatcher::cancelled].
+#[cfg(feature = "async")]
+pub struct Cancelled(RxClosed<Signal>);
+
+#[cfg(feature = "async")]
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+#[cfg(feature = "async")]
+impl fmt::Debug for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Cancelled")
+    }
+}
+
+/// Creates a connected [CancelSource] and [CancelWatcher].
+pub fn new() -> (CancelSource, CancelWatcher) {
+    let (tx, rx) = crate::new(Signal::default());
+    (CancelSource(tx), CancelWatcher(rx))
+}
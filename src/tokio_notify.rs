@@ -0,0 +1,127 @@
+//! A [Notify] adapter that signals tokio primitives.
+
+use crate::{Notify, Rx, Tx};
+use core::fmt;
+use core::future::Future;
+use core::ops::Deref;
+use tokio::sync::watch;
+
+/// Which halves have been fully dropped. Published by [TokioNotify].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct HalvesDropped {
+    /// The last [Tx] was dropped.
+    pub tx: bool,
+    /// The last [Rx] was dropped.
+    pub rx: bool,
+}
+
+/// A payload that signals a [tokio::sync::Notify] and a
+/// [tokio::sync::watch] channel when the last [Tx] or last [Rx] is
+/// dropped.
+///
+/// ```
+/// # async fn example() {
+/// let (tx, rx) = splitrc::new(splitrc::TokioNotify::new(()));
+/// let closed = splitrc::Tx::closed_tokio(&tx);
+/// drop(rx);
+/// closed.await;
+/// # }
+/// ```
+pub struct TokioNotify<T> {
+    data: T,
+    notify: tokio::sync::Notify,
+    state: watch::Sender<HalvesDropped>,
+}
+
+impl<T> TokioNotify<T> {
+    /// Wraps `data`.
+    pub fn new(data: T) -> Self {
+        TokioNotify {
+            data,
+            notify: tokio::sync::Notify::new(),
+            state: watch::Sender::new(HalvesDropped::default()),
+        }
+    }
+
+    /// Returns the [tokio::sync::Notify] whose waiters are woken each
+    /// time a half is dropped.
+    pub fn notify(&self) -> &tokio::sync::Notify {
+        &self.notify
+    }
+
+    /// Returns a receiver that observes each half being dropped.
+    ///
+    /// The receiver outlives the payload. Once the payload is dropped,
+    /// [watch::Receiver::changed] returns an error.
+    pub fn subscribe(&self) -> watch::Receiver<HalvesDropped> {
+        self.state.subscribe()
+    }
+
+    /// Returns true if the last [Tx] has been dropped.
+    pub fn is_tx_dropped(&self) -> bool {
+        self.state.borrow().tx
+    }
+
+    /// Returns true if the last [Rx] has been dropped.
+    pub fn is_rx_dropped(&self) -> bool {
+        self.state.borrow().rx
+    }
+
+    fn signal(&self, f: impl FnOnce(&mut HalvesDropped)) {
+        self.state.send_modify(f);
+        self.notify.notify_waiters();
+    }
+}
+
+impl<T> Notify for TokioNotify<T> {
+    fn last_tx_did_drop(&self) {
+        self.signal(|s| s.tx = true)
+    }
+
+    fn last_rx_did_drop(&self) {
+        self.signal(|s| s.rx = true)
+    }
+}
+
+impl<T> Deref for TokioNotify<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for TokioNotify<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.data, f)
+    }
+}
+
+// If both halves drop together, no notification runs, but dropping
+// the payload closes the channel, which also means the half is gone.
+async fn wait_dropped(
+    mut state: watch::Receiver<HalvesDropped>,
+    dropped: impl Fn(&HalvesDropped) -> bool,
+) {
+    let _ = state.wait_for(dropped).await;
+}
+
+impl<T> Tx<TokioNotify<T>> {
+    /// Returns a future that resolves when the last [Rx] is dropped.
+    ///
+    /// The future does not keep the allocation alive and may be
+    /// awaited on any tokio runtime.
+    pub fn closed_tokio(this: &Self) -> impl Future<Output = ()> + Send + 'static {
+        wait_dropped(this.subscribe(), |s| s.rx)
+    }
+}
+
+impl<T> Rx<TokioNotify<T>> {
+    /// Returns a future that resolves when the last [Tx] is dropped.
+    ///
+    /// The future does not keep the allocation alive and may be
+    /// awaited on any tokio runtime.
+    pub fn closed_tokio(this: &Self) -> impl Future<Output = ()> + Send + 'static {
+        wait_dropped(this.subscribe(), |s| s.tx)
+    }
+}
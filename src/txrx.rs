@@ -0,0 +1,160 @@
+//! A single handle counted as both halves.
+
+use crate::{drop_rx, drop_tx, new, same_allocation, Counts, Inner, Notify, Rx, Tx};
+use core::borrow::Borrow;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::Deref;
+use core::ptr::NonNull;
+
+/// A handle that holds one [Tx] reference and one [Rx] reference to
+/// the same allocation.
+///
+/// For components that both produce and consume. It is one pointer
+/// wide, and cloning it increments both counts with one atomic
+/// operation. Dropping it releases the tx reference, then the rx
+/// reference, so the usual notifications apply.
+///
+/// ```
+/// # struct MyValue {}
+/// # impl splitrc::Notify for MyValue {}
+/// let both = splitrc::TxRx::new(MyValue {});
+/// let (tx, rx) = splitrc::TxRx::split(both.clone());
+/// # drop((both, tx, rx));
+/// ```
+pub struct TxRx<T: Notify + ?Sized> {
+    ptr: NonNull<Inner<T>>,
+    phantom: PhantomData<T>,
+}
+
+unsafe impl<T: Sync + Send + Notify + ?Sized> Send for TxRx<T> {}
+unsafe impl<T: Sync + Send + Notify + ?Sized> Sync for TxRx<T> {}
+
+impl<T: Notify + ?Sized> Drop for TxRx<T> {
+    fn drop(&mut self) {
+        drop_tx(self.ptr);
+        drop_rx(self.ptr);
+    }
+}
+
+impl<T: Notify> TxRx<T> {
+    /// Allocates a pointer holding `data` and returns a handle
+    /// counted as both halves.
+    pub fn new(data: T) -> Self {
+        Self::join(new(data)).unwrap_or_else(|_| unreachable!())
+    }
+}
+
+impl<T: Notify + ?Sized> TxRx<T> {
+    /// Combines halves of the same allocation. Returns them unchanged
+    /// if they are not.
+    #[allow(clippy::type_complexity)]
+    pub fn join((tx, rx): (Tx<T>, Rx<T>)) -> Result<Self, (Tx<T>, Rx<T>)> {
+        if !same_allocation(&tx, &rx) {
+            return Err((tx, rx));
+        }
+        let ptr = tx.ptr;
+        mem::forget(tx);
+        mem::forget(rx);
+        Ok(TxRx {
+            ptr,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Separates into halves, each keeping its reference.
+    pub fn split(this: Self) -> (Tx<T>, Rx<T>) {
+        let ptr = this.ptr;
+        mem::forget(this);
+        (
+            Tx {
+                ptr,
+                phantom: PhantomData,
+            },
+            Rx {
+                ptr,
+                phantom: PhantomData,
+            },
+        )
+    }
+
+    /// Returns a new [Tx] to the same allocation.
+    pub fn tx(this: &Self) -> Tx<T> {
+        // SAFETY: We do not create a &mut to Inner.
+        unsafe { this.ptr.as_ref() }.inc_tx();
+        Tx {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns a new [Rx] to the same allocation.
+    pub fn rx(this: &Self) -> Rx<T> {
+        // SAFETY: We do not create a &mut to Inner.
+        unsafe { this.ptr.as_ref() }.inc_rx();
+        Rx {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns a snapshot of both reference counts.
+    pub fn counts(this: &Self) -> Counts {
+        // SAFETY: We do not create a &mut to Inner.
+        unsafe { this.ptr.as_ref() }.count.counts()
+    }
+}
+
+impl<T: Notify + ?Sized> From<TxRx<T>> for (Tx<T>, Rx<T>) {
+    fn from(both: TxRx<T>) -> Self {
+        TxRx::split(both)
+    }
+}
+
+impl<T: Notify + ?Sized> Clone for TxRx<T> {
+    fn clone(&self) -> Self {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { self.ptr.as_ref() };
+        inner.count.inc_both();
+        #[cfg(feature = "observer")]
+        {
+            inner.data.tx_did_clone();
+            inner.data.rx_did_clone();
+        }
+        TxRx { ..*self }
+    }
+}
+
+impl<T: Notify + ?Sized> Deref for TxRx<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: We know ptr is valid and do not create &mut.
+        &unsafe { self.ptr.as_ref() }.data
+    }
+}
+
+impl<T: Notify + ?Sized> AsRef<T> for TxRx<T> {
+    fn as_ref(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + ?Sized> Borrow<T> for TxRx<T> {
+    fn borrow(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + fmt::Debug + ?Sized> fmt::Debug for TxRx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_ref(), f)
+    }
+}
+
+impl<T: Notify + fmt::Display + ?Sized> fmt::Display for TxRx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_ref(), f)
+    }
+}
@@ -0,0 +1,92 @@
+//! Exclusive access to a payload before it is shared.
+
+use crate::{allocate, drop_data, Inner, Notify, Rx, Tx};
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+
+/// The only handle to an allocation that has not been split yet.
+/// Created by [new_unique].
+///
+/// Because no [Tx] or [Rx] exists, the payload may be mutated freely.
+/// Dropping the handle drops the payload without notifying.
+pub struct UniqueHandle<T: Notify> {
+    ptr: NonNull<Inner<T>>,
+    phantom: PhantomData<T>,
+}
+
+// SAFETY: Like Box, the handle owns the payload exclusively.
+unsafe impl<T: Notify + Send> Send for UniqueHandle<T> {}
+unsafe impl<T: Notify + Sync> Sync for UniqueHandle<T> {}
+
+impl<T: Notify> UniqueHandle<T> {
+    /// Publishes the payload, returning a pair of references.
+    pub fn split(this: Self) -> (Tx<T>, Rx<T>) {
+        let ptr = this.ptr;
+        // The counts were initialized for one Tx and one Rx.
+        mem::forget(this);
+        (
+            Tx {
+                ptr,
+                phantom: PhantomData,
+            },
+            Rx {
+                ptr,
+                phantom: PhantomData,
+            },
+        )
+    }
+}
+
+impl<T: Notify> Drop for UniqueHandle<T> {
+    fn drop(&mut self) {
+        lifecycle_event!(self.ptr, "deallocate");
+        drop_data(self.ptr)
+    }
+}
+
+impl<T: Notify> Deref for UniqueHandle<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: We know ptr is valid.
+        &unsafe { self.ptr.as_ref() }.data
+    }
+}
+
+impl<T: Notify> DerefMut for UniqueHandle<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: No other handle to the allocation exists.
+        &mut unsafe { self.ptr.as_mut() }.data
+    }
+}
+
+impl<T: Notify + fmt::Debug> fmt::Debug for UniqueHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Allocates a pointer holding `data` and returns its only handle.
+///
+/// Mutate the payload through the handle, then call
+/// [UniqueHandle::split] to publish it.
+///
+/// ```
+/// # #[derive(Default)]
+/// # struct MyValue { ready: bool }
+/// # impl splitrc::Notify for MyValue {}
+/// let mut unique = splitrc::new_unique(MyValue::default());
+/// unique.ready = true;
+/// let (tx, rx) = splitrc::UniqueHandle::split(unique);
+/// assert!(rx.ready);
+/// # drop(tx);
+/// ```
+pub fn new_unique<T: Notify>(data: T) -> UniqueHandle<T> {
+    UniqueHandle {
+        ptr: allocate(data),
+        phantom: PhantomData,
+    }
+}
@@ -0,0 +1,350 @@
+//! Blocking until a half closes by waiting on the count word itself.
+//!
+//! Waiters park on the 32-bit word of the count that changes when
+//! the opposite half closes: futex on Linux, WaitOnAddress on
+//! Windows, and ulock on macOS. Other platforms poll.
+
+use crate::{rx_count, tx_count, AtomicPacked, Notify, Packed, Rx, Tx};
+use allocator_api2::alloc::Allocator;
+use core::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+#[cfg(not(any(loom, shuttle)))]
+use core::sync::atomic::{fence, AtomicUsize};
+
+// Wakes are skipped unless a thread may be waiting, so notifications
+// only pay for a fence and a load.
+#[cfg(not(any(loom, shuttle)))]
+static WAITERS: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(not(any(loom, shuttle)))]
+struct Waiter;
+
+#[cfg(not(any(loom, shuttle)))]
+impl Waiter {
+    fn register() -> Self {
+        WAITERS.fetch_add(1, Ordering::Relaxed);
+        // Pairs with the fence in wake: either the waiter observes
+        // the closed half, or the notifier observes the waiter.
+        fence(Ordering::SeqCst);
+        Waiter
+    }
+}
+
+#[cfg(not(any(loom, shuttle)))]
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        WAITERS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+// Which half of the count a waiter parks on. With 64-bit counts, the
+// tx count lives in the high word and the rx count in the low word.
+#[derive(Clone, Copy)]
+enum Half {
+    Tx,
+    Rx,
+}
+
+#[cfg(not(feature = "compact-counts"))]
+fn word(count: &AtomicPacked, half: Half) -> *const u32 {
+    let high = matches!(half, Half::Tx);
+    let index = (high == cfg!(target_endian = "little")) as usize;
+    // SAFETY: The count is eight bytes, so both words are in bounds.
+    unsafe { (count as *const AtomicPacked as *const u32).add(index) }
+}
+
+#[cfg(feature = "compact-counts")]
+fn word(count: &AtomicPacked, _half: Half) -> *const u32 {
+    count as *const AtomicPacked as *const u32
+}
+
+#[cfg(not(feature = "compact-counts"))]
+fn expected(c: Packed, half: Half) -> u32 {
+    match half {
+        Half::Tx => (c >> 32) as u32,
+        Half::Rx => c as u32,
+    }
+}
+
+#[cfg(feature = "compact-counts")]
+fn expected(c: Packed, _half: Half) -> u32 {
+    c
+}
+
+/// Called after the last [Tx] is dropped, while the allocation is
+/// still live.
+pub(crate) fn wake_tx_closed(count: &AtomicPacked) {
+    wake(count, Half::Tx)
+}
+
+/// See [wake_tx_closed].
+pub(crate) fn wake_rx_closed(count: &AtomicPacked) {
+    wake(count, Half::Rx)
+}
+
+#[cfg(not(any(loom, shuttle)))]
+fn wake(count: &AtomicPacked, half: Half) {
+    fence(Ordering::SeqCst);
+    if WAITERS.load(Ordering::Relaxed) != 0 {
+        sys::wake(word(count, half));
+    }
+}
+
+#[cfg(any(loom, shuttle))]
+fn wake(_count: &AtomicPacked, _half: Half) {}
+
+/// Returns true once `is_closed` holds, or false if `timeout` elapses
+/// first.
+fn wait_closed(
+    count: &AtomicPacked,
+    half: Half,
+    is_closed: fn(Packed) -> bool,
+    timeout: Option<Duration>,
+) -> bool {
+    if is_closed(count.load(Ordering::Acquire)) {
+        return true;
+    }
+    // A timeout too large to represent waits forever.
+    let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+    #[cfg(not(any(loom, shuttle)))]
+    let _waiter = Waiter::register();
+    loop {
+        let c = count.load(Ordering::Acquire);
+        if is_closed(c) {
+            return true;
+        }
+        let remaining = match deadline {
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return false;
+                }
+                Some(deadline - now)
+            }
+            None => None,
+        };
+        // Spurious wakeups and unrelated changes to the word return
+        // early, so check again.
+        sys::wait(word(count, half), expected(c, half), remaining);
+    }
+}
+
+impl<T: Notify + ?Sized, A: Allocator> Tx<T, A> {
+    /// Blocks the current thread until the last [Rx] is dropped or
+    /// `timeout` elapses. Returns false on timeout.
+    ///
+    /// Waits on the reference count itself, so it allocates nothing.
+    pub fn wait_rx_closed(this: &Self, timeout: Option<Duration>) -> bool {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        wait_closed(&inner.count.0, Half::Rx, |c| rx_count(c) == 0, timeout)
+    }
+}
+
+impl<T: Notify + ?Sized, A: Allocator> Rx<T, A> {
+    /// Blocks the current thread until the last [Tx] is dropped or
+    /// `timeout` elapses. Returns false on timeout.
+    ///
+    /// Waits on the reference count itself, so it allocates nothing.
+    pub fn wait_tx_closed(this: &Self, timeout: Option<Duration>) -> bool {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        wait_closed(&inner.count.0, Half::Tx, |c| tx_count(c) == 0, timeout)
+    }
+}
+
+#[cfg(all(
+    not(any(loom, shuttle)),
+    any(not(miri), feature = "compact-counts"),
+    any(target_os = "linux", target_os = "android"),
+    any(
+        all(target_arch = "x86_64", target_pointer_width = "64"),
+        target_arch = "x86",
+        target_arch = "arm",
+        target_arch = "aarch64",
+        target_arch = "riscv64",
+    )
+))]
+mod sys {
+    use core::ptr;
+    use std::os::raw::{c_int, c_long};
+    use std::time::Duration;
+
+    extern "C" {
+        fn syscall(num: c_long, ...) -> c_long;
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    const SYS_FUTEX: c_long = 202;
+    #[cfg(any(target_arch = "x86", target_arch = "arm"))]
+    const SYS_FUTEX: c_long = 240;
+    #[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
+    const SYS_FUTEX: c_long = 98;
+
+    const FUTEX_WAIT_PRIVATE: c_int = 128;
+    const FUTEX_WAKE_PRIVATE: c_int = 129;
+
+    #[repr(C)]
+    struct Timespec {
+        tv_sec: c_long,
+        tv_nsec: c_long,
+    }
+
+    pub(super) fn wait(addr: *const u32, expected: u32, timeout: Option<Duration>) {
+        let timespec = timeout.map(|timeout| Timespec {
+            tv_sec: c_long::try_from(timeout.as_secs()).unwrap_or(c_long::MAX),
+            tv_nsec: timeout.subsec_nanos() as c_long,
+        });
+        let timespec = timespec
+            .as_ref()
+            .map_or(ptr::null(), |timespec| timespec as *const Timespec);
+        // SAFETY: addr points to a live, aligned word. The kernel
+        // compares it with expected before sleeping.
+        unsafe { syscall(SYS_FUTEX, addr, FUTEX_WAIT_PRIVATE, expected, timespec) };
+    }
+
+    pub(super) fn wake(addr: *const u32) {
+        // SAFETY: Waking never dereferences addr.
+        unsafe { syscall(SYS_FUTEX, addr, FUTEX_WAKE_PRIVATE, i32::MAX) };
+    }
+}
+
+#[cfg(all(
+    not(any(loom, shuttle)),
+    any(not(miri), feature = "compact-counts"),
+    windows
+))]
+mod sys {
+    use std::os::raw::c_void;
+    use std::time::Duration;
+
+    #[link(name = "synchronization")]
+    extern "system" {
+        fn WaitOnAddress(
+            address: *const c_void,
+            compare: *const c_void,
+            size: usize,
+            milliseconds: u32,
+        ) -> i32;
+        fn WakeByAddressAll(address: *const c_void);
+    }
+
+    const INFINITE: u32 = u32::MAX;
+
+    pub(super) fn wait(addr: *const u32, expected: u32, timeout: Option<Duration>) {
+        // Round up so short timeouts do not spin.
+        let milliseconds = timeout.map_or(INFINITE, |timeout| {
+            let ms = (timeout.as_nanos() + 999_999) / 1_000_000;
+            u32::try_from(ms).unwrap_or(INFINITE - 1).min(INFINITE - 1)
+        });
+        // SAFETY: addr points to a live, aligned word.
+        unsafe {
+            WaitOnAddress(
+                addr as *const c_void,
+                &expected as *const u32 as *const c_void,
+                4,
+                milliseconds,
+            )
+        };
+    }
+
+    pub(super) fn wake(addr: *const u32) {
+        // SAFETY: Waking never dereferences addr.
+        unsafe { WakeByAddressAll(addr as *const c_void) };
+    }
+}
+
+#[cfg(all(
+    not(any(loom, shuttle)),
+    any(not(miri), feature = "compact-counts"),
+    any(target_os = "macos", target_os = "ios")
+))]
+mod sys {
+    use std::os::raw::{c_int, c_void};
+    use std::time::Duration;
+
+    extern "C" {
+        fn __ulock_wait(operation: u32, addr: *mut c_void, value: u64, timeout_us: u32) -> c_int;
+        fn __ulock_wake(operation: u32, addr: *mut c_void, wake_value: u64) -> c_int;
+    }
+
+    const UL_COMPARE_AND_WAIT: u32 = 1;
+    const ULF_WAKE_ALL: u32 = 0x100;
+
+    pub(super) fn wait(addr: *const u32, expected: u32, timeout: Option<Duration>) {
+        // Zero waits forever, so round up.
+        let timeout_us = timeout.map_or(0, |timeout| {
+            let us = (timeout.as_nanos() + 999) / 1_000;
+            u32::try_from(us).unwrap_or(u32::MAX).max(1)
+        });
+        // SAFETY: addr points to a live, aligned word.
+        unsafe {
+            __ulock_wait(
+                UL_COMPARE_AND_WAIT,
+                addr as *mut c_void,
+                expected.into(),
+                timeout_us,
+            )
+        };
+    }
+
+    pub(super) fn wake(addr: *const u32) {
+        // SAFETY: Waking never dereferences addr.
+        unsafe { __ulock_wake(UL_COMPARE_AND_WAIT | ULF_WAKE_ALL, addr as *mut c_void, 0) };
+    }
+}
+
+// Miri rejects the kernel's 32-bit compare against a 64-bit count as
+// a mixed-size access, so it polls too.
+#[cfg(all(
+    not(any(loom, shuttle)),
+    any(
+        all(miri, not(feature = "compact-counts")),
+        not(any(
+            all(
+                any(target_os = "linux", target_os = "android"),
+                any(
+                    all(target_arch = "x86_64", target_pointer_width = "64"),
+                    target_arch = "x86",
+                    target_arch = "arm",
+                    target_arch = "aarch64",
+                    target_arch = "riscv64",
+                )
+            ),
+            windows,
+            target_os = "macos",
+            target_os = "ios",
+        ))
+    )
+))]
+mod sys {
+    use std::thread;
+    use std::time::Duration;
+
+    const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+    pub(super) fn wait(_addr: *const u32, _expected: u32, timeout: Option<Duration>) {
+        thread::sleep(timeout.map_or(POLL_INTERVAL, |timeout| timeout.min(POLL_INTERVAL)));
+    }
+
+    pub(super) fn wake(_addr: *const u32) {}
+}
+
+#[cfg(loom)]
+mod sys {
+    use std::time::Duration;
+
+    pub(super) fn wait(_addr: *const u32, _expected: u32, _timeout: Option<Duration>) {
+        loom::thread::yield_now();
+    }
+}
+
+#[cfg(shuttle)]
+mod sys {
+    use std::time::Duration;
+
+    pub(super) fn wait(_addr: *const u32, _expected: u32, _timeout: Option<Duration>) {
+        shuttle::thread::yield_now();
+    }
+}
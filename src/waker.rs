@@ -0,0 +1,156 @@
+//! A [Notify] adapter that wakes tasks.
+
+use crate::Notify;
+use core::fmt;
+use core::ops::Deref;
+use core::task::{Context, Poll, Waker};
+
+#[cfg(loom)]
+use loom::sync::Mutex;
+
+#[cfg(not(loom))]
+use std::sync::Mutex;
+
+// Holds at most one waker. Registering replaces the previous waker.
+#[derive(Default)]
+struct WakerSlot {
+    state: Mutex<SlotState>,
+}
+
+#[derive(Default)]
+struct SlotState {
+    dropped: bool,
+    waker: Option<Waker>,
+}
+
+impl WakerSlot {
+    fn register(&self, waker: &Waker) {
+        let mut state = self.state.lock().unwrap();
+        if state.dropped {
+            drop(state);
+            waker.wake_by_ref();
+            return;
+        }
+        match &state.waker {
+            Some(old) if old.will_wake(waker) => {}
+            _ => state.waker = Some(waker.clone()),
+        }
+    }
+
+    fn poll(&self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        // Checking under the lock ensures we either observe the drop
+        // or are registered before the wake.
+        if state.dropped {
+            Poll::Ready(())
+        } else {
+            match &state.waker {
+                Some(old) if old.will_wake(cx.waker()) => {}
+                _ => state.waker = Some(cx.waker().clone()),
+            }
+            Poll::Pending
+        }
+    }
+
+    fn is_dropped(&self) -> bool {
+        self.state.lock().unwrap().dropped
+    }
+
+    fn wake(&self) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            state.dropped = true;
+            state.waker.take()
+        };
+        // Wake outside of the lock.
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// A payload that wakes a registered [Waker] when the last [crate::Tx]
+/// or last [crate::Rx] is dropped.
+///
+/// Each half has a single slot: registering a new waker replaces the
+/// previous one, like `AtomicWaker` in the futures crate.
+///
+/// ```
+/// let (tx, rx) = splitrc::new(splitrc::WakerNotify::new(()));
+/// drop(tx);
+/// assert!(rx.is_tx_dropped());
+/// ```
+pub struct WakerNotify<T> {
+    data: T,
+    tx: WakerSlot,
+    rx: WakerSlot,
+}
+
+impl<T> WakerNotify<T> {
+    /// Wraps `data` with empty waker slots.
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            tx: WakerSlot::default(),
+            rx: WakerSlot::default(),
+        }
+    }
+
+    /// Registers a waker to be woken when the last [crate::Tx] is
+    /// dropped. Wakes immediately if it already has been.
+    pub fn register_tx_waker(&self, waker: &Waker) {
+        self.tx.register(waker)
+    }
+
+    /// Registers a waker to be woken when the last [crate::Rx] is
+    /// dropped. Wakes immediately if it already has been.
+    pub fn register_rx_waker(&self, waker: &Waker) {
+        self.rx.register(waker)
+    }
+
+    /// Returns true once the last [crate::Tx] has been dropped.
+    pub fn is_tx_dropped(&self) -> bool {
+        self.tx.is_dropped()
+    }
+
+    /// Returns true once the last [crate::Rx] has been dropped.
+    pub fn is_rx_dropped(&self) -> bool {
+        self.rx.is_dropped()
+    }
+
+    /// Returns [Poll::Ready] if the last [crate::Tx] has been dropped.
+    /// Otherwise, registers the context's waker.
+    pub fn poll_tx_dropped(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.tx.poll(cx)
+    }
+
+    /// Returns [Poll::Ready] if the last [crate::Rx] has been dropped.
+    /// Otherwise, registers the context's waker.
+    pub fn poll_rx_dropped(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.rx.poll(cx)
+    }
+}
+
+impl<T> Notify for WakerNotify<T> {
+    fn last_tx_did_drop(&self) {
+        self.tx.wake()
+    }
+
+    fn last_rx_did_drop(&self) {
+        self.rx.wake()
+    }
+}
+
+impl<T> Deref for WakerNotify<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for WakerNotify<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.data, f)
+    }
+}
@@ -0,0 +1,84 @@
+//! Non-atomic counts for single-threaded WebAssembly.
+//!
+//! Without the `atomics` target feature, wasm32 has no shared memory
+//! and no threads, so no other thread can observe the counts. These
+//! types replace the atomics with [Cell]s, mirroring the subset of
+//! the atomic API the crate uses.
+
+// Which methods are used depends on the enabled features.
+#![allow(dead_code)]
+
+use core::cell::Cell;
+use core::sync::atomic::Ordering;
+
+pub(crate) fn fence(_order: Ordering) {}
+
+macro_rules! unsync_atomic {
+    ($name:ident, $t:ty) => {
+        #[derive(Debug, Default)]
+        #[repr(transparent)]
+        pub(crate) struct $name(Cell<$t>);
+
+        // SAFETY: The target has no threads.
+        unsafe impl Sync for $name {}
+
+        impl $name {
+            pub(crate) const fn new(v: $t) -> Self {
+                Self(Cell::new(v))
+            }
+
+            pub(crate) fn load(&self, _order: Ordering) -> $t {
+                self.0.get()
+            }
+
+            pub(crate) fn store(&self, v: $t, _order: Ordering) {
+                self.0.set(v)
+            }
+
+            pub(crate) fn swap(&self, v: $t, _order: Ordering) -> $t {
+                self.0.replace(v)
+            }
+        }
+    };
+}
+
+macro_rules! unsync_atomic_int {
+    ($name:ident, $t:ty) => {
+        unsync_atomic!($name, $t);
+
+        impl $name {
+            pub(crate) fn fetch_add(&self, v: $t, _order: Ordering) -> $t {
+                let old = self.0.get();
+                self.0.set(old.wrapping_add(v));
+                old
+            }
+
+            pub(crate) fn fetch_sub(&self, v: $t, _order: Ordering) -> $t {
+                let old = self.0.get();
+                self.0.set(old.wrapping_sub(v));
+                old
+            }
+
+            pub(crate) fn fetch_update<F: FnMut($t) -> Option<$t>>(
+                &self,
+                _set_order: Ordering,
+                _fetch_order: Ordering,
+                mut f: F,
+            ) -> Result<$t, $t> {
+                let old = self.0.get();
+                match f(old) {
+                    Some(new) => {
+                        self.0.set(new);
+                        Ok(old)
+                    }
+                    None => Err(old),
+                }
+            }
+        }
+    };
+}
+
+unsync_atomic!(AtomicBool, bool);
+unsync_atomic_int!(AtomicU32, u32);
+unsync_atomic_int!(AtomicU64, u64);
+unsync_atomic_int!(AtomicUsize, usize);
@@ -0,0 +1,308 @@
+//! A single-producer, multi-consumer channel that holds only the
+//! latest value.
+//!
+//! The [Sender] is the tx half and each [Receiver] is an rx half, so
+//! receivers observe the sender's drop through the usual
+//! notification, and the sender observes when every receiver is gone.
+//!
+//! ```
+//! let (tx, mut rx) = splitrc::watch::channel(0);
+//! tx.send(1).unwrap();
+//! assert!(rx.has_changed().unwrap());
+//! assert_eq!(1, *rx.borrow_and_update());
+//! drop(tx);
+//! assert!(rx.has_changed().is_err());
+//! ```
+
+use crate::channel_core::{self, Shared};
+use core::fmt;
+use core::future::Future;
+use core::ops::Deref;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+#[cfg(loom)]
+use loom::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[cfg(not(loom))]
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+struct Versioned<T> {
+    value: T,
+    // Incremented by every send.
+    version: u64,
+}
+
+struct State<T> {
+    current: RwLock<Versioned<T>>,
+}
+
+impl<T> State<T> {
+    // A panic in send_modify still leaves a valid value, so ignore
+    // poison.
+    fn read(&self) -> RwLockReadGuard<'_, Versioned<T>> {
+        self.current.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Versioned<T>> {
+        self.current.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Returned by [Sender::send] when every [Receiver] has been dropped.
+/// Holds the value that was not sent.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SendError(..)")
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("every receiver has been dropped")
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+/// Returned by [Receiver::changed] and [Receiver::has_changed] when
+/// the [Sender] has been dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecvError;
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the sender has been dropped")
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+/// A borrow of the current value. Holds a read lock, so sends block
+/// until it is dropped.
+pub struct Ref<'a, T> {
+    guard: RwLockReadGuard<'a, Versioned<T>>,
+}
+
+impl<T> Deref for Ref<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Ref<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Publishes values to every [Receiver]. Created by [channel].
+pub struct Sender<T>(channel_core::Sender<State<T>>);
+
+impl<T> Sender<T> {
+    /// Replaces the value and notifies receivers. Fails, returning
+    /// the value, if every receiver has been dropped.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if self.0.is_rx_closed() {
+            return Err(SendError(value));
+        }
+        self.send_replace(value);
+        Ok(())
+    }
+
+    /// Replaces the value, notifies receivers, and returns the
+    /// previous value. Succeeds even without receivers.
+    pub fn send_replace(&self, value: T) -> T {
+        let mut old = value;
+        self.send_modify(|current| core::mem::swap(current, &mut old));
+        old
+    }
+
+    /// Modifies the value in place and notifies receivers.
+    pub fn send_modify(&self, modify: impl FnOnce(&mut T)) {
+        {
+            let mut current = self.0.write();
+            modify(&mut current.value);
+            current.version += 1;
+        }
+        self.0.wake_receivers();
+    }
+
+    /// Borrows the current value.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        Ref {
+            guard: self.0.read(),
+        }
+    }
+
+    /// Returns true once every [Receiver] has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.0.is_rx_closed()
+    }
+
+    /// Returns a new [Receiver] that has seen the current value, or
+    /// [None] if every receiver has been dropped.
+    pub fn subscribe(&self) -> Option<Receiver<T>> {
+        let rx = crate::Tx::receiver_handle(&self.0)?;
+        let seen = rx.read().version;
+        Some(Receiver { rx, seen })
+    }
+
+    /// Returns a future that resolves when every [Receiver] has been
+    /// dropped.
+    pub fn closed(&self) -> Closed<'_, T> {
+        Closed { sender: self }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("value", &*self.borrow())
+            .finish()
+    }
+}
+
+/// Observes the latest value published by the [Sender]. Created by
+/// [channel].
+///
+/// Each receiver tracks which version it has seen. Clones start from
+/// the same point.
+pub struct Receiver<T> {
+    rx: channel_core::Receiver<State<T>>,
+    seen: u64,
+}
+
+impl<T> Receiver<T> {
+    /// Borrows the current value without marking it seen.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        Ref {
+            guard: self.rx.read(),
+        }
+    }
+
+    /// Borrows the current value and marks it seen.
+    pub fn borrow_and_update(&mut self) -> Ref<'_, T> {
+        let guard = self.rx.read();
+        self.seen = guard.version;
+        Ref { guard }
+    }
+
+    /// Returns true if a value has been sent since this receiver last
+    /// marked one seen. Fails if no unseen value remains and the
+    /// [Sender] has been dropped.
+    pub fn has_changed(&self) -> Result<bool, RecvError> {
+        if self.rx.read().version != self.seen {
+            Ok(true)
+        } else if self.rx.is_tx_closed() {
+            Err(RecvError)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Returns a future that resolves when an unseen value is
+    /// available, marking it seen, or fails when the [Sender] is
+    /// dropped first.
+    pub fn changed(&mut self) -> Changed<'_, T> {
+        Changed { receiver: self }
+    }
+
+    fn poll_changed(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), RecvError>> {
+        let seen = self.seen;
+        match self.rx.poll_recv(cx, |state| {
+            let version = state.read().version;
+            (version != seen).then_some(version)
+        }) {
+            Poll::Ready(Some(version)) => {
+                self.seen = version;
+                Poll::Ready(Ok(()))
+            }
+            Poll::Ready(None) => Poll::Ready(Err(RecvError)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        Receiver {
+            rx: self.rx.clone(),
+            seen: self.seen,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("value", &*self.borrow())
+            .finish()
+    }
+}
+
+/// Future returned by [Receiver::changed].
+pub struct Changed<'a, T> {
+    receiver: &'a mut Receiver<T>,
+}
+
+impl<T> Future for Changed<'_, T> {
+    type Output = Result<(), RecvError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.receiver.poll_changed(cx)
+    }
+}
+
+impl<T> fmt::Debug for Changed<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Changed")
+    }
+}
+
+/// Future returned by [Sender::closed].
+pub struct Closed<'a, T> {
+    sender: &'a Sender<T>,
+}
+
+impl<T> Future for Closed<'_, T> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let shared: &Shared<State<T>> = &self.sender.0;
+        if shared.is_rx_closed() {
+            return Poll::Ready(());
+        }
+        shared.register_sender(cx.waker());
+        // Checking again after registering ensures we either observe
+        // the close or are registered before the wake.
+        if shared.is_rx_closed() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> fmt::Debug for Closed<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Closed")
+    }
+}
+
+/// Creates a watch channel holding `initial`, which receivers
+/// consider already seen.
+pub fn channel<T>(initial: T) -> (Sender<T>, Receiver<T>) {
+    let (tx, rx) = channel_core::new(State {
+        current: RwLock::new(Versioned {
+            value: initial,
+            version: 0,
+        }),
+    });
+    (Sender(tx), Receiver { rx, seen: 0 })
+}
@@ -0,0 +1,288 @@
+//! Split reference counts that clone without touching the atomic.
+//!
+//! Each weighted handle carries a weight, and the shared count holds
+//! the sum of the weights rather than the number of handles. Cloning
+//! splits the handle's weight between it and the clone, so under heavy
+//! clone churn the atomic count is only touched when a handle of
+//! weight one must borrow a fresh batch. Dropping a handle subtracts
+//! its weight with one atomic operation, and [Tx::merge] folds a
+//! handle back into another without any.
+//!
+//! While weighted handles exist, [crate::Counts] reports weights, not
+//! handles. Notifications are the same as for [crate::Tx] and
+//! [crate::Rx]. Handles may be sent to other threads, but since the
+//! weight is not atomic, they are not [Sync].
+//!
+//! ```
+//! # struct MyValue {}
+//! # impl splitrc::Notify for MyValue {}
+//! let (tx, rx) = splitrc::weighted::new(MyValue {});
+//! let workers: Vec<_> = (0..4).map(|_| tx.clone()).collect();
+//! for worker in workers {
+//!     splitrc::weighted::Tx::merge(&tx, worker);
+//! }
+//! # drop((tx, rx));
+//! ```
+
+use crate::{release_rx_by, release_tx_by, Inner, Notify, MAX_BATCH};
+use core::borrow::Borrow;
+use core::cell::Cell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::Deref;
+use core::ptr::NonNull;
+
+// The weight borrowed from the shared count when a handle of weight
+// one is cloned. Leaves room for about a thousand outstanding batches
+// before the count reaches the panic range.
+const BATCH: u32 = MAX_BATCH;
+
+/// Splits `weight`, borrowing a batch first if it cannot be split.
+/// Returns the weight to keep and the weight for the clone.
+fn split(weight: &Cell<u32>, borrow: impl FnOnce(u32)) -> u32 {
+    let mut w = weight.get();
+    if w == 1 {
+        borrow(BATCH);
+        w += BATCH;
+    }
+    let half = w / 2;
+    weight.set(w - half);
+    half
+}
+
+/// The write half of a split reference count, with a local weight.
+pub struct Tx<T: Notify + ?Sized> {
+    weight: Cell<u32>,
+    ptr: NonNull<Inner<T>>,
+    phantom: PhantomData<T>,
+}
+
+unsafe impl<T: Sync + Send + Notify + ?Sized> Send for Tx<T> {}
+
+impl<T: Notify + ?Sized> Drop for Tx<T> {
+    fn drop(&mut self) {
+        #[cfg(feature = "observer")]
+        self.tx_did_drop_one();
+        release_tx_by(self.ptr, self.weight.get())
+    }
+}
+
+impl<T: Notify + ?Sized> Tx<T> {
+    /// Returns the share of the tx count this handle holds.
+    pub fn weight(this: &Self) -> u32 {
+        this.weight.get()
+    }
+
+    /// Adds `other`'s weight to this handle without touching the
+    /// shared count.
+    ///
+    /// # Panics
+    ///
+    /// If the handles point to different allocations.
+    pub fn merge(this: &Self, other: Self) {
+        assert!(
+            Tx::ptr_eq(this, &other),
+            "merged handles must point to the same allocation"
+        );
+        this.weight.set(this.weight.get() + other.weight.get());
+        mem::forget(other);
+    }
+
+    /// Returns a thread-safe [crate::Tx] to the same allocation.
+    pub fn to_shared(this: &Self) -> crate::Tx<T> {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        inner.inc_tx();
+        crate::Tx {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns true if both handles point to the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr.cast::<u8>() == other.ptr.cast::<u8>()
+    }
+}
+
+impl<T: Notify + ?Sized> From<crate::Tx<T>> for Tx<T> {
+    fn from(tx: crate::Tx<T>) -> Self {
+        let ptr = tx.ptr;
+        // Takes over tx's reference as a weight of one.
+        mem::forget(tx);
+        Tx {
+            weight: Cell::new(1),
+            ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Notify + ?Sized> Clone for Tx<T> {
+    fn clone(&self) -> Self {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { self.ptr.as_ref() };
+        let weight = split(&self.weight, |n| inner.count.inc_tx_by(n));
+        #[cfg(feature = "observer")]
+        inner.data.tx_did_clone();
+        Tx {
+            weight: Cell::new(weight),
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Notify + ?Sized> Deref for Tx<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: We know ptr is valid and do not create &mut.
+        &unsafe { self.ptr.as_ref() }.data
+    }
+}
+
+impl<T: Notify + ?Sized> AsRef<T> for Tx<T> {
+    fn as_ref(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + ?Sized> Borrow<T> for Tx<T> {
+    fn borrow(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + fmt::Debug + ?Sized> fmt::Debug for Tx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_ref(), f)
+    }
+}
+
+impl<T: Notify + fmt::Display + ?Sized> fmt::Display for Tx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_ref(), f)
+    }
+}
+
+/// The read half of a split reference count, with a local weight.
+pub struct Rx<T: Notify + ?Sized> {
+    weight: Cell<u32>,
+    ptr: NonNull<Inner<T>>,
+    phantom: PhantomData<T>,
+}
+
+unsafe impl<T: Sync + Send + Notify + ?Sized> Send for Rx<T> {}
+
+impl<T: Notify + ?Sized> Drop for Rx<T> {
+    fn drop(&mut self) {
+        #[cfg(feature = "observer")]
+        self.rx_did_drop_one();
+        release_rx_by(self.ptr, self.weight.get())
+    }
+}
+
+impl<T: Notify + ?Sized> Rx<T> {
+    /// Returns the share of the rx count this handle holds.
+    pub fn weight(this: &Self) -> u32 {
+        this.weight.get()
+    }
+
+    /// See [Tx::merge].
+    pub fn merge(this: &Self, other: Self) {
+        assert!(
+            Rx::ptr_eq(this, &other),
+            "merged handles must point to the same allocation"
+        );
+        this.weight.set(this.weight.get() + other.weight.get());
+        mem::forget(other);
+    }
+
+    /// Returns a thread-safe [crate::Rx] to the same allocation.
+    pub fn to_shared(this: &Self) -> crate::Rx<T> {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { this.ptr.as_ref() };
+        inner.inc_rx();
+        crate::Rx {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns true if both handles point to the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr.cast::<u8>() == other.ptr.cast::<u8>()
+    }
+}
+
+impl<T: Notify + ?Sized> From<crate::Rx<T>> for Rx<T> {
+    fn from(rx: crate::Rx<T>) -> Self {
+        let ptr = rx.ptr;
+        // Takes over rx's reference as a weight of one.
+        mem::forget(rx);
+        Rx {
+            weight: Cell::new(1),
+            ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Notify + ?Sized> Clone for Rx<T> {
+    fn clone(&self) -> Self {
+        // SAFETY: We do not create a &mut to Inner.
+        let inner = unsafe { self.ptr.as_ref() };
+        let weight = split(&self.weight, |n| inner.count.inc_rx_by(n));
+        #[cfg(feature = "observer")]
+        inner.data.rx_did_clone();
+        Rx {
+            weight: Cell::new(weight),
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Notify + ?Sized> Deref for Rx<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: We know ptr is valid and do not create &mut.
+        &unsafe { self.ptr.as_ref() }.data
+    }
+}
+
+impl<T: Notify + ?Sized> AsRef<T> for Rx<T> {
+    fn as_ref(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + ?Sized> Borrow<T> for Rx<T> {
+    fn borrow(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: Notify + fmt::Debug + ?Sized> fmt::Debug for Rx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_ref(), f)
+    }
+}
+
+impl<T: Notify + fmt::Display + ?Sized> fmt::Display for Rx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_ref(), f)
+    }
+}
+
+/// Allocates a pointer holding `data` and returns a pair of weighted
+/// references.
+///
+/// The rules are the same as [crate::new].
+pub fn new<T: Notify>(data: T) -> (Tx<T>, Rx<T>) {
+    let (tx, rx) = crate::new(data);
+    (tx.into(), rx.into())
+}
@@ -0,0 +1,69 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+
+mod fixture;
+
+use fixture::TrackNotify;
+use fixture::Unit;
+
+#[test]
+fn store_and_take() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let cell = splitrc::AtomicTx::default();
+    assert!(!cell.is_some());
+    cell.store(Some(tx));
+    assert!(cell.is_some());
+    assert_eq!(1, splitrc::Rx::tx_count(&rx));
+    let tx = cell.take().unwrap();
+    assert!(!cell.is_some());
+    drop(tx);
+    assert!(rx.tx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn store_drops_old_handle() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let cell = splitrc::AtomicRx::from(rx);
+    let (_tx2, rx2) = splitrc::new(TrackNotify::default());
+    cell.store(Some(rx2));
+    assert!(tx.rx_did_drop.load(Ordering::Acquire));
+    assert!(cell.into_inner().is_some());
+}
+
+#[test]
+fn drop_releases_handle() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    drop(splitrc::AtomicRx::new(Some(rx)));
+    assert!(tx.rx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn concurrent_swaps_keep_every_handle() {
+    let (tx, rx) = splitrc::new(Unit);
+    let cell = Arc::new(splitrc::AtomicTx::new(Some(tx.clone())));
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let cell = cell.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let mut held = Some(tx);
+                for _ in 0..100 {
+                    held = cell.swap(held);
+                }
+                held
+            })
+        })
+        .collect();
+    let held: Vec<_> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+    // The cell is never empty, so every swap trades one handle for
+    // another.
+    assert!(held.iter().all(Option::is_some));
+    assert!(cell.is_some());
+    assert_eq!(6, splitrc::Rx::tx_count(&rx));
+    drop(held);
+    drop(tx);
+    assert_eq!(1, splitrc::Rx::tx_count(&rx));
+    drop(cell);
+    assert_eq!(0, splitrc::Rx::tx_count(&rx));
+}
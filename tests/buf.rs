@@ -0,0 +1,77 @@
+#![cfg(feature = "std")]
+
+use splitrc::buf::{self, SharedBuf};
+use splitrc::{Rx, Tx};
+use std::sync::atomic::Ordering;
+
+mod fixture;
+
+use fixture::TrackNotify;
+use fixture::Unit;
+
+#[test]
+fn append_is_visible_to_slices() {
+    let (tx, rx) = buf::with_capacity(8);
+    assert!(rx.is_empty());
+    assert_eq!(3, Tx::append(&tx, b"abc"));
+    let abc = Rx::bytes(&rx);
+    // Only what fits is appended.
+    assert_eq!(5, Tx::append(&tx, b"defghij"));
+    assert_eq!(0, Tx::append(&tx, b"k"));
+
+    assert_eq!(b"abc", &abc[..]);
+    assert_eq!(b"abcdefgh", rx.as_slice());
+    assert_eq!(b"abcdefgh", tx.as_slice());
+    let all = Rx::bytes(&rx);
+    assert_eq!(b"cde", &all.slice(2..5)[..]);
+    assert_eq!(b"d", &all.slice(2..5).slice(1..=1)[..]);
+    assert_eq!(abc.as_ptr(), all.as_ptr());
+    assert_eq!(3, Tx::rx_count(&tx));
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn slice_out_of_bounds_panics() {
+    let (tx, rx) = buf::with_capacity(4);
+    Tx::append(&tx, b"ab");
+    let _ = Rx::bytes(&rx).slice(1..3);
+}
+
+#[test]
+fn reclaim_after_last_reader() {
+    let (tx, rx) = splitrc::new(SharedBuf::with_capacity(4, TrackNotify::default()));
+    Tx::append(&tx, b"ab");
+    let bytes = Rx::bytes(&rx);
+    drop(rx);
+    let tx = Tx::try_reclaim(tx).unwrap_err();
+    assert!(!tx.notify().rx_did_drop.load(Ordering::Acquire));
+    drop(bytes);
+    assert!(tx.notify().rx_did_drop.load(Ordering::Acquire));
+
+    let mut storage = Tx::try_reclaim(tx).unwrap();
+    assert_eq!(b"ab", &storage[..]);
+    storage.clear();
+    let (tx, rx) = splitrc::new(SharedBuf::from_vec(storage, Unit));
+    assert_eq!(4, Tx::append(&tx, b"wxyz"));
+    assert_eq!(b"wxyz", &Rx::bytes(&rx)[..]);
+}
+
+#[test]
+fn appends_from_many_threads() {
+    let (tx, rx) = buf::with_capacity(1000);
+    std::thread::scope(|s| {
+        for i in 0..10u8 {
+            let tx = tx.clone();
+            s.spawn(move || {
+                for _ in 0..100 {
+                    Tx::append(&tx, &[i]);
+                }
+            });
+        }
+    });
+    let bytes = Rx::bytes(&rx);
+    assert_eq!(1000, bytes.len());
+    for i in 0..10u8 {
+        assert_eq!(100, bytes.iter().filter(|&&b| b == i).count());
+    }
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "capi")]
+
+use splitrc::capi::*;
+use std::ffi::c_void;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Default)]
+struct Calls {
+    tx: AtomicUsize,
+    rx: AtomicUsize,
+    free: AtomicUsize,
+}
+
+fn calls<'a>(user_data: *mut c_void) -> &'a Calls {
+    unsafe { &*(user_data as *const Calls) }
+}
+
+unsafe extern "C" fn on_last_tx(user_data: *mut c_void) {
+    calls(user_data).tx.fetch_add(1, Ordering::Relaxed);
+}
+
+unsafe extern "C" fn on_last_rx(user_data: *mut c_void) {
+    calls(user_data).rx.fetch_add(1, Ordering::Relaxed);
+}
+
+unsafe extern "C" fn on_free(user_data: *mut c_void) {
+    calls(user_data).free.fetch_add(1, Ordering::Relaxed);
+}
+
+fn callbacks(calls: &Calls) -> SplitrcCallbacks {
+    SplitrcCallbacks {
+        user_data: calls as *const Calls as *mut c_void,
+        last_tx_did_drop: Some(on_last_tx),
+        last_rx_did_drop: Some(on_last_rx),
+        free: Some(on_free),
+    }
+}
+
+fn counts(calls: &Calls) -> (usize, usize, usize) {
+    (
+        calls.tx.load(Ordering::Relaxed),
+        calls.rx.load(Ordering::Relaxed),
+        calls.free.load(Ordering::Relaxed),
+    )
+}
+
+#[test]
+fn c_handles_notify_and_free() {
+    let calls = Calls::default();
+    unsafe {
+        let pair = splitrc_create(callbacks(&calls));
+        let tx2 = splitrc_clone_tx(pair.tx);
+        let rx2 = splitrc_clone_rx(pair.rx);
+        assert_eq!(
+            &calls as *const Calls as *mut c_void,
+            splitrc_tx_user_data(tx2)
+        );
+        assert_eq!(splitrc_tx_user_data(tx2), splitrc_rx_user_data(rx2));
+
+        splitrc_drop_tx(pair.tx);
+        assert_eq!((0, 0, 0), counts(&calls));
+        splitrc_drop_tx(tx2);
+        assert_eq!((1, 0, 0), counts(&calls));
+        splitrc_drop_rx(pair.rx);
+        splitrc_drop_rx(rx2);
+    }
+    assert_eq!((1, 0, 1), counts(&calls));
+}
+
+#[test]
+fn null_callbacks_are_skipped() {
+    let calls = Calls::default();
+    let callbacks = SplitrcCallbacks {
+        last_tx_did_drop: None,
+        ..callbacks(&calls)
+    };
+    unsafe {
+        let pair = splitrc_create(callbacks);
+        splitrc_drop_tx(pair.tx);
+        splitrc_drop_rx(pair.rx);
+        splitrc_drop_rx(ptr::null_mut());
+    }
+    assert_eq!((0, 0, 1), counts(&calls));
+}
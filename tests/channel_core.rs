@@ -0,0 +1,69 @@
+#![cfg(feature = "std")]
+
+use splitrc::channel_core::{self, Receiver};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+type Queue = Mutex<VecDeque<u32>>;
+
+#[derive(Default)]
+struct CountWaker(AtomicUsize);
+
+impl Wake for CountWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+fn poll_recv(rx: &Receiver<Queue>, cx: &mut Context<'_>) -> Poll<Option<u32>> {
+    rx.poll_recv(cx, |queue| queue.lock().unwrap().pop_front())
+}
+
+#[test]
+fn closing_wakes_the_other_side() {
+    let (tx, rx) = channel_core::new(Queue::default());
+    let count = Arc::new(CountWaker::default());
+    let waker = Waker::from(count.clone());
+    rx.register_receiver(&waker);
+    tx.register_sender(&waker);
+    assert!(!rx.is_tx_closed());
+    drop(tx);
+    assert!(rx.is_tx_closed());
+    assert!(!rx.is_rx_closed());
+    assert_eq!(1, count.0.load(Ordering::Acquire));
+    drop(rx);
+}
+
+#[test]
+fn poll_recv_drains_before_reporting_close() {
+    let (tx, rx) = channel_core::new(Queue::default());
+    let count = Arc::new(CountWaker::default());
+    let waker = Waker::from(count.clone());
+    let mut cx = Context::from_waker(&waker);
+    assert_eq!(Poll::Pending, poll_recv(&rx, &mut cx));
+
+    tx.lock().unwrap().push_back(1);
+    tx.wake_receivers();
+    assert_eq!(1, count.0.load(Ordering::Acquire));
+    tx.lock().unwrap().push_back(2);
+    drop(tx);
+
+    assert_eq!(Poll::Ready(Some(1)), poll_recv(&rx, &mut cx));
+    assert_eq!(Poll::Ready(Some(2)), poll_recv(&rx, &mut cx));
+    assert_eq!(Poll::Ready(None), poll_recv(&rx, &mut cx));
+}
+
+#[test]
+fn handles_from_the_other_side() {
+    let (tx, rx) = channel_core::new(Queue::default());
+    let tx2 = splitrc::Rx::sender_handle(&rx).unwrap();
+    let rx2 = splitrc::Tx::receiver_handle(&tx).unwrap();
+    assert_eq!(2, splitrc::Tx::counts(&tx).tx);
+    assert_eq!(2, splitrc::Tx::counts(&tx).rx);
+    drop((tx, tx2));
+    assert!(splitrc::Rx::sender_handle(&rx).is_none());
+    let rx3 = splitrc::Rx::receiver_handle(&rx2);
+    drop((rx, rx2, rx3));
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "async")]
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+mod fixture;
+
+use fixture::Unit;
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark()
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    Pin::new(future).poll(&mut Context::from_waker(&waker))
+}
+
+#[test]
+fn tx_closed_pending_while_rx_alive() {
+    let (tx, rx) = splitrc::new(Unit);
+    let mut closed = splitrc::Tx::closed(&tx);
+    assert!(poll_once(&mut closed).is_pending());
+    drop(rx);
+    assert!(poll_once(&mut closed).is_ready());
+}
+
+#[test]
+fn rx_closed_resolves_from_another_thread() {
+    let (tx, rx) = splitrc::new(Unit);
+    let tx2 = tx.clone();
+    let handle = thread::spawn(move || {
+        drop(tx);
+        drop(tx2);
+    });
+    block_on(splitrc::Rx::closed(&rx));
+    handle.join().unwrap();
+}
+
+#[test]
+fn closed_outlives_both_halves() {
+    let (tx, rx) = splitrc::new(Unit);
+    let mut closed = splitrc::Tx::closed(&tx);
+    assert!(poll_once(&mut closed).is_pending());
+    drop(tx);
+    drop(rx);
+    assert!(poll_once(&mut closed).is_ready());
+}
+
+#[test]
+fn dropped_future_deregisters() {
+    let (tx, rx) = splitrc::new(Unit);
+    for _ in 0..3 {
+        let mut closed = splitrc::Rx::closed(&rx);
+        assert!(poll_once(&mut closed).is_pending());
+    }
+    let closed = splitrc::Rx::closed(&rx);
+    drop(tx);
+    block_on(closed);
+}
+
+// Miri reports a leak if the registered wakers outlive the
+// allocation.
+#[test]
+fn registered_wakers_are_freed() {
+    let (tx, rx) = splitrc::new(Unit);
+    let mut tx_closed = splitrc::Tx::closed(&tx);
+    let mut rx_closed = splitrc::Rx::closed(&rx);
+    assert!(poll_once(&mut tx_closed).is_pending());
+    assert!(poll_once(&mut rx_closed).is_pending());
+    drop((tx_closed, rx_closed));
+    drop((tx, rx));
+}
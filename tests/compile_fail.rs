@@ -0,0 +1,9 @@
+// Diagnostics differ between toolchains, so the expected output is
+// checked on stable only. CI runs the nightly feature on nightly.
+#[test]
+#[cfg_attr(any(miri, feature = "nightly"), ignore)]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+}
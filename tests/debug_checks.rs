@@ -0,0 +1,37 @@
+#![cfg(feature = "debug-checks")]
+
+use std::mem::ManuallyDrop;
+
+mod fixture;
+use fixture::Unit;
+
+// The corrupted counts leak the allocation.
+#[test]
+#[cfg_attr(miri, ignore)]
+#[should_panic(expected = "dropped a Tx whose count was already zero")]
+fn double_drop_is_caught() {
+    let (tx, _rx) = splitrc::new(Unit);
+    let raw = splitrc::Tx::into_raw(tx);
+    // SAFETY: Deliberately wrong. The rx half keeps the allocation
+    // alive, so the second drop only touches the counts.
+    unsafe {
+        drop(splitrc::Tx::from_raw(raw));
+        drop(splitrc::Tx::from_raw(raw));
+    }
+}
+
+#[test]
+#[should_panic(expected = "cloned an Rx with no live references")]
+fn clone_after_release_is_caught() {
+    let (tx, rx) = splitrc::new(Unit);
+    // The weak reference keeps the allocation, but not the payload.
+    let _weak = splitrc::Rx::downgrade(&rx);
+    let raw = splitrc::Rx::into_raw(rx);
+    drop(tx);
+    // SAFETY: Deliberately wrong. Releases the last Rx, then conjures
+    // another that is never dropped. Cloning it only touches the
+    // counts.
+    unsafe { drop(splitrc::Rx::from_raw(raw)) };
+    let stale = ManuallyDrop::new(unsafe { splitrc::Rx::from_raw(raw) });
+    let _ = stale.clone();
+}
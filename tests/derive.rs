@@ -0,0 +1,74 @@
+#![cfg(feature = "derive")]
+
+use std::marker::PhantomData;
+use std::sync::atomic::Ordering;
+
+mod fixture;
+
+use fixture::TrackNotify;
+use fixture::Unit;
+
+#[derive(splitrc::Notify)]
+struct Plain {
+    value: u32,
+}
+
+#[derive(splitrc::Notify)]
+struct Generic<T>(PhantomData<T>);
+
+#[derive(splitrc::Notify)]
+enum State {
+    Idle,
+    Running(u32),
+}
+
+#[derive(splitrc::Notify)]
+struct Wrapper<N> {
+    name: &'static str,
+    #[notify]
+    inner: N,
+}
+
+#[derive(splitrc::Notify)]
+struct Tuple(u32, #[notify] TrackNotify);
+
+#[test]
+fn derive_without_field_ignores_notifications() {
+    let (tx, rx) = splitrc::new(Plain { value: 7 });
+    drop(tx);
+    assert_eq!(7, rx.value);
+
+    let (tx, rx) = splitrc::new(Generic::<String>(PhantomData));
+    drop((rx, tx));
+
+    let (tx, rx) = splitrc::new(State::Running(1));
+    drop(tx);
+    assert!(matches!(*rx, State::Running(1)));
+    drop(splitrc::new(State::Idle));
+}
+
+#[test]
+fn derive_forwards_to_generic_field() {
+    let (tx, rx) = splitrc::new(Wrapper {
+        name: "unit",
+        inner: Unit,
+    });
+    drop((tx, rx));
+}
+
+#[test]
+fn derive_forwards_to_marked_field() {
+    let (tx, rx) = splitrc::new(Wrapper {
+        name: "w",
+        inner: TrackNotify::default(),
+    });
+    drop(tx);
+    assert_eq!("w", rx.name);
+    assert!(rx.inner.tx_did_drop.load(Ordering::Acquire));
+    assert!(!rx.inner.rx_did_drop.load(Ordering::Acquire));
+
+    let (tx, rx) = splitrc::new(Tuple(1, TrackNotify::default()));
+    drop(rx);
+    assert_eq!(1, tx.0);
+    assert_eq!((false, true), tx.1.access());
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "std")]
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn drained_when_guards_drop() {
+    let (guard, coordinator) = splitrc::drain::new(AtomicU32::new(0));
+    let guard2 = guard.clone();
+    assert_eq!(2, coordinator.guard_count());
+    guard.fetch_add(1, Ordering::Relaxed);
+    drop(guard);
+    assert!(!coordinator.is_drained());
+    guard2.fetch_add(1, Ordering::Relaxed);
+    drop(guard2);
+    assert!(coordinator.is_drained());
+    assert_eq!(0, coordinator.guard_count());
+    assert_eq!(2, coordinator.drain_blocking().into_inner());
+}
+
+#[test]
+fn drain_blocking_waits_for_workers() {
+    let (guard, coordinator) = splitrc::drain::new(Mutex::new(Vec::new()));
+    for i in 0..4 {
+        let guard = guard.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            guard.lock().unwrap().push(i);
+        });
+    }
+    drop(guard);
+    let mut done = coordinator.drain_blocking().into_inner().unwrap();
+    done.sort();
+    assert_eq!(vec![0, 1, 2, 3], done);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn drain_future() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let (guard, coordinator) = splitrc::drain::new(String::from("queue"));
+    let mut drain = coordinator.drain();
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    assert!(Pin::new(&mut drain).poll(&mut cx).is_pending());
+    assert_eq!("queue", &*guard);
+    drop(guard);
+    assert_eq!(
+        Poll::Ready(String::from("queue")),
+        Pin::new(&mut drain).poll(&mut cx)
+    );
+}
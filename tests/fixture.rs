@@ -4,7 +4,10 @@ use std::sync::atomic::Ordering;
 #[cfg(loom)]
 use loom::sync::atomic::AtomicBool;
 
-#[cfg(not(loom))]
+#[cfg(shuttle)]
+use shuttle::sync::atomic::AtomicBool;
+
+#[cfg(not(any(loom, shuttle)))]
 use std::sync::atomic::AtomicBool;
 
 #[derive(Debug)]
@@ -17,7 +20,7 @@ impl fmt::Display for Unit {
     }
 }
 
-#[derive(Default)]
+#[derive(Debug, Default)]
 pub struct TrackNotify {
     pub tx_did_drop: AtomicBool,
     pub rx_did_drop: AtomicBool,
@@ -0,0 +1,44 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+trait Actor: splitrc::Notify + Send + Sync {
+    fn handle(&self, message: u32);
+}
+
+#[derive(Default)]
+struct Summer {
+    sum: AtomicU32,
+}
+
+impl splitrc::Notify for Summer {}
+
+impl Actor for Summer {
+    fn handle(&self, message: u32) {
+        self.sum.fetch_add(message, Ordering::Relaxed);
+    }
+}
+
+splitrc::handle_methods! {
+    trait Address for splitrc::Tx<dyn Actor> {
+        fn send_and_close(self, message: u32,) {
+            self.handle(message);
+        }
+    }
+}
+
+splitrc::handle_methods! {
+    trait SummerMailbox for splitrc::Rx<Summer> {
+        fn into_sum(self) -> Option<u32> {
+            splitrc::Rx::into_inner(self).map(|summer| summer.sum.into_inner())
+        }
+    }
+}
+
+#[test]
+fn methods_dispatch_on_trait_objects() {
+    let (tx, rx) = splitrc::new(Summer::default());
+    // SAFETY: The cast only adds metadata.
+    let tx: splitrc::Tx<dyn Actor> = unsafe { splitrc::Tx::unsize(tx, |p| p as *const dyn Actor) };
+    tx.clone().send_and_close(2);
+    tx.send_and_close(3);
+    assert_eq!(Some(5), rx.into_sum());
+}
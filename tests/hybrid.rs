@@ -0,0 +1,57 @@
+use std::sync::atomic::Ordering;
+use std::thread;
+
+mod fixture;
+
+use fixture::TrackNotify;
+use fixture::Unit;
+
+#[test]
+fn new_and_delete() {
+    let (tx, rx) = splitrc::hybrid::new(Unit);
+    drop(tx);
+    drop(rx);
+}
+
+#[test]
+fn drop_tx_notifies_after_local_clones() {
+    let (tx, rx) = splitrc::hybrid::new(TrackNotify::default());
+    let tx2 = tx.clone();
+    drop(tx);
+    assert!(!rx.tx_did_drop.load(Ordering::Acquire));
+    drop(tx2);
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn shared_handle_keeps_half_alive() {
+    let (tx, rx) = splitrc::hybrid::new(TrackNotify::default());
+    let shared = splitrc::hybrid::Tx::to_shared(&tx);
+    drop(tx);
+    assert!(!rx.tx_did_drop.load(Ordering::Acquire));
+    thread::spawn(move || {
+        let tx = splitrc::hybrid::Tx::from(shared);
+        drop(tx.clone());
+    })
+    .join()
+    .unwrap();
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn drop_rx_notifies_across_threads() {
+    let (tx, rx) = splitrc::hybrid::new(TrackNotify::default());
+    let shared = splitrc::hybrid::Rx::to_shared(&rx);
+    drop(rx);
+    let rx = splitrc::hybrid::Rx::from(shared);
+    assert!(splitrc::hybrid::Rx::ptr_eq(&rx, &rx.clone()));
+    drop(rx);
+    assert_eq!((false, true), tx.access());
+}
+
+#[test]
+fn formatting() {
+    let (tx, rx) = splitrc::hybrid::new(Unit);
+    assert_eq!("Unit", format!("{:?}", tx));
+    assert_eq!("Unit", format!("{}", rx));
+}
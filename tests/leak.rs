@@ -0,0 +1,72 @@
+#![cfg(feature = "leak-track")]
+
+// Tests share the global registry, so each counts its own payload
+// type.
+
+fn live<T: ?Sized>() -> usize {
+    let name = std::any::type_name::<T>();
+    splitrc::leak_report()
+        .allocations
+        .iter()
+        .filter(|a| a.type_name == name)
+        .count()
+}
+
+#[test]
+fn allocation_is_live_until_freed() {
+    struct Tracked;
+    impl splitrc::Notify for Tracked {}
+
+    let (tx, rx) = splitrc::new(Tracked);
+    assert_eq!(1, live::<Tracked>());
+    drop(tx);
+    assert_eq!(1, live::<Tracked>());
+    drop(rx);
+    assert_eq!(0, live::<Tracked>());
+}
+
+#[test]
+fn weak_reference_keeps_allocation_live() {
+    struct Weakly;
+    impl splitrc::Notify for Weakly {}
+
+    let (tx, rx) = splitrc::new(Weakly);
+    let weak = splitrc::Tx::downgrade(&tx);
+    drop((tx, rx));
+    assert_eq!(1, live::<Weakly>());
+    drop(weak);
+    assert_eq!(0, live::<Weakly>());
+}
+
+#[test]
+fn report_groups_by_type() {
+    struct Grouped;
+    impl splitrc::Notify for Grouped {}
+
+    let pairs: Vec<_> = (0..3).map(|_| splitrc::new(Grouped)).collect();
+    let report = splitrc::leak_report();
+    let name = std::any::type_name::<Grouped>();
+    assert!(report.by_type().contains(&(name, 3)));
+    assert!(report.to_string().contains(&format!("3 {}", name)));
+    drop(pairs);
+    assert_eq!(0, live::<Grouped>());
+}
+
+#[test]
+fn slices_and_in_place_constructors_are_tracked() {
+    let (tx, rx) = splitrc::from_slice(&[1u8, 2, 3]);
+    assert_eq!(1, live::<[u8]>());
+    drop((tx, rx));
+    assert_eq!(0, live::<[u8]>());
+
+    let (tx, rx) = splitrc::from_str("leak");
+    assert_eq!(1, live::<str>());
+    drop((tx, rx));
+
+    struct Cyclic;
+    impl splitrc::Notify for Cyclic {}
+    let (tx, rx) = splitrc::new_cyclic(|_, _| Cyclic);
+    assert_eq!(1, live::<Cyclic>());
+    drop((tx, rx));
+    assert_eq!(0, live::<Cyclic>());
+}
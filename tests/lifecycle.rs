@@ -0,0 +1,77 @@
+#![cfg(feature = "std")]
+
+use splitrc::Event;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+
+#[derive(Default)]
+struct CountWaker(AtomicUsize);
+
+impl Wake for CountWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+#[test]
+fn reports_last_rx_then_deallocation() {
+    let (tx, rx, mut events) = splitrc::new_with_events("payload");
+    assert_eq!("payload", **tx);
+    assert_eq!(None, events.try_next());
+    let rx2 = rx.clone();
+    drop(rx);
+    assert_eq!(None, events.try_next());
+    drop(rx2);
+    assert_eq!(Some(Event::LastRxDropped), events.try_next());
+    drop(tx);
+    assert_eq!(Some(Event::Deallocated), events.try_next());
+    assert_eq!(None, events.next());
+}
+
+#[test]
+fn iteration_blocks_until_events_arrive() {
+    let (tx, rx, events) = splitrc::new_with_events(vec![1, 2, 3]);
+    let worker = thread::spawn(move || {
+        drop(tx);
+        drop(rx);
+    });
+    assert_eq!(
+        vec![Event::LastTxDropped, Event::Deallocated],
+        events.collect::<Vec<_>>()
+    );
+    worker.join().unwrap();
+}
+
+#[test]
+fn poll_next_wakes_on_event() {
+    let (tx, rx, mut events) = splitrc::new_with_events(());
+    let count = Arc::new(CountWaker::default());
+    let waker = Waker::from(count.clone());
+    let mut cx = Context::from_waker(&waker);
+    assert_eq!(Poll::Pending, events.poll_next(&mut cx));
+    drop(tx);
+    assert_eq!(1, count.0.load(Ordering::Acquire));
+    assert_eq!(
+        Poll::Ready(Some(Event::LastTxDropped)),
+        events.poll_next(&mut cx)
+    );
+    assert_eq!(Poll::Pending, events.poll_next(&mut cx));
+    drop(rx);
+    assert_eq!(2, count.0.load(Ordering::Acquire));
+    assert_eq!(
+        Poll::Ready(Some(Event::Deallocated)),
+        events.poll_next(&mut cx)
+    );
+    assert_eq!(Poll::Ready(None), events.poll_next(&mut cx));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn events_is_a_stream() {
+    fn assert_stream<S: futures_core::Stream<Item = Event>>(_: &S) {}
+    let (tx, rx, events) = splitrc::new_with_events(());
+    assert_stream(&events);
+    drop((tx, rx));
+}
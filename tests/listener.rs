@@ -0,0 +1,57 @@
+#![cfg(feature = "event-listener")]
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+mod fixture;
+
+use fixture::Unit;
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark()
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+fn poll_once<F: Future>(future: Pin<&mut F>) -> Poll<F::Output> {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    future.poll(&mut Context::from_waker(&waker))
+}
+
+#[test]
+fn rx_closed_resolves_when_last_rx_drops() {
+    let (tx, rx) = splitrc::new(Unit);
+    let mut closed = Box::pin(splitrc::Tx::rx_closed(&tx));
+    assert!(poll_once(closed.as_mut()).is_pending());
+    let worker = thread::spawn(move || drop(rx));
+    block_on(closed);
+    worker.join().unwrap();
+}
+
+#[test]
+fn tx_closed_resolves_when_allocation_drops() {
+    let (tx, rx) = splitrc::new(Unit);
+    let mut closed = Box::pin(splitrc::Rx::tx_closed(&rx));
+    // The last Rx goes first, so dropping the last Tx notifies no one.
+    drop(rx);
+    assert!(poll_once(closed.as_mut()).is_pending());
+    drop(tx);
+    block_on(closed);
+}
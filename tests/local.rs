@@ -0,0 +1,65 @@
+use std::pin::Pin;
+use std::sync::atomic::Ordering;
+
+mod fixture;
+
+use fixture::TrackNotify;
+use fixture::Unit;
+
+#[test]
+fn new_and_delete() {
+    let (tx, rx) = splitrc::local::new(Unit);
+    drop(tx);
+    drop(rx);
+}
+
+#[test]
+fn drop_rx_notifies() {
+    let (tx, rx) = splitrc::local::new(TrackNotify::default());
+    let rx2 = rx.clone();
+    drop(rx);
+    drop(rx2);
+    assert!(!tx.tx_did_drop.load(Ordering::Acquire));
+    assert!(tx.rx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn drop_tx_notifies() {
+    let (tx, rx) = splitrc::local::new(TrackNotify::default());
+    let tx2 = tx.clone();
+    drop(tx);
+    drop(tx2);
+    assert!(rx.tx_did_drop.load(Ordering::Acquire));
+    assert!(!rx.rx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn formatting() {
+    let (tx, rx) = splitrc::local::new(Unit);
+    assert_eq!("Unit", format!("{:?}", tx));
+    assert_eq!("Unit", format!("{}", rx));
+}
+
+#[test]
+fn drop_tx_pinned() {
+    let (tx, rx): (Pin<splitrc::local::Tx<_>>, Pin<splitrc::local::Rx<_>>) =
+        splitrc::local::pin(TrackNotify::default());
+    drop(tx);
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn pin_reports_pinned_context() {
+    struct Pinned(std::cell::Cell<Option<splitrc::DropContext>>);
+    impl splitrc::Notify for Pinned {
+        fn last_tx_did_drop_with(self: Pin<&Self>, ctx: splitrc::DropContext) {
+            self.0.set(Some(ctx));
+        }
+    }
+
+    let (tx, rx) = splitrc::local::pin(Pinned(Default::default()));
+    drop(tx);
+    let ctx = rx.0.get().unwrap();
+    assert!(ctx.is_pinned());
+    assert_eq!(1, ctx.remaining());
+}
@@ -1,5 +1,6 @@
 #![cfg(loom)]
 
+use loom::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 
 mod fixture;
@@ -83,3 +84,417 @@ fn racing_drop_4_threads() {
         loom::thread::spawn(move || rx2.access());
     })
 }
+
+#[test]
+fn racing_upgrade_and_drop() {
+    loom::model(|| {
+        let (tx, rx) = splitrc::new(TrackNotify::default());
+        let tx_weak = splitrc::Tx::downgrade(&tx);
+        drop(rx);
+        loom::thread::spawn(move || tx.access());
+        loom::thread::spawn(move || tx_weak.upgrade().map(|tx| tx.access()));
+    })
+}
+
+#[test]
+#[cfg(feature = "async")]
+fn racing_closed_and_drop() {
+    loom::model(|| {
+        let (tx, rx) = splitrc::new(Unit);
+        let closed = splitrc::Tx::closed(&tx);
+        loom::thread::spawn(move || drop(rx));
+        loom::future::block_on(closed);
+    })
+}
+
+#[test]
+fn racing_into_rx_and_drop() {
+    loom::model(|| {
+        let (tx, rx) = splitrc::new(TrackNotify::default());
+        loom::thread::spawn(move || drop(rx));
+        loom::thread::spawn(move || match splitrc::Tx::into_rx(tx) {
+            Ok(rx) => rx.access(),
+            Err(tx) => tx.access(),
+        });
+    })
+}
+
+// Counts notifications and drops so a model can check that the
+// decrement fast path never notifies twice or drops twice.
+struct CountNotify {
+    notified: loom::sync::Arc<AtomicUsize>,
+    dropped: loom::sync::Arc<AtomicUsize>,
+}
+
+impl splitrc::Notify for CountNotify {
+    fn last_tx_did_drop(&self) {
+        self.notified.fetch_add(1, Ordering::AcqRel);
+    }
+    fn last_rx_did_drop(&self) {
+        self.notified.fetch_add(1, Ordering::AcqRel);
+    }
+    fn will_deallocate(&self) {
+        assert_eq!(0, self.dropped.load(Ordering::Acquire));
+    }
+}
+
+impl Drop for CountNotify {
+    fn drop(&mut self) {
+        self.dropped.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+#[test]
+fn racing_clone_drop_notifies_once() {
+    loom::model(|| {
+        let notified = loom::sync::Arc::new(AtomicUsize::new(0));
+        let dropped = loom::sync::Arc::new(AtomicUsize::new(0));
+        let (tx1, rx) = splitrc::new(CountNotify {
+            notified: notified.clone(),
+            dropped: dropped.clone(),
+        });
+        let tx2 = tx1.clone();
+        let a = loom::thread::spawn(move || drop(tx1));
+        let b = loom::thread::spawn(move || drop(tx2));
+        drop(rx);
+        a.join().unwrap();
+        b.join().unwrap();
+        assert!(notified.load(Ordering::Acquire) <= 1);
+        assert_eq!(1, dropped.load(Ordering::Acquire));
+    })
+}
+
+#[test]
+fn racing_last_halves_drop_once() {
+    loom::model(|| {
+        let notified = loom::sync::Arc::new(AtomicUsize::new(0));
+        let dropped = loom::sync::Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = splitrc::new(CountNotify {
+            notified: notified.clone(),
+            dropped: dropped.clone(),
+        });
+        let a = loom::thread::spawn(move || drop(tx));
+        drop(rx);
+        a.join().unwrap();
+        assert!(notified.load(Ordering::Acquire) <= 1);
+        assert_eq!(1, dropped.load(Ordering::Acquire));
+    })
+}
+
+#[test]
+fn racing_into_inner_drops_once() {
+    loom::model(|| {
+        let notified = loom::sync::Arc::new(AtomicUsize::new(0));
+        let dropped = loom::sync::Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = splitrc::new(CountNotify {
+            notified: notified.clone(),
+            dropped: dropped.clone(),
+        });
+        let a = loom::thread::spawn(move || splitrc::Tx::into_inner(tx));
+        let b = splitrc::Rx::into_inner(rx);
+        let a = a.join().unwrap();
+        assert!(a.is_none() || b.is_none());
+        drop((a, b));
+        assert_eq!(1, dropped.load(Ordering::Acquire));
+    })
+}
+
+#[test]
+fn wait_rx_closed_wakes() {
+    loom::model(|| {
+        let (tx, rx) = splitrc::new(Unit);
+        let a = loom::thread::spawn(move || drop(rx));
+        assert!(splitrc::Tx::wait_rx_closed(&tx, None));
+        a.join().unwrap();
+    })
+}
+
+#[test]
+fn oneshot_value_arrives_before_close() {
+    loom::model(|| {
+        let (tx, mut rx) = splitrc::oneshot::channel();
+        let a = loom::thread::spawn(move || tx.send(1).unwrap());
+        let received = loop {
+            match rx.try_recv() {
+                Err(splitrc::oneshot::TryRecvError::Empty) => loom::thread::yield_now(),
+                result => break result,
+            }
+        };
+        assert_eq!(Ok(1), received);
+        a.join().unwrap();
+    })
+}
+
+#[test]
+fn racing_atomic_tx_swaps() {
+    loom::model(|| {
+        let (tx1, rx1) = splitrc::new(TrackNotify::default());
+        let (tx2, rx2) = splitrc::new(TrackNotify::default());
+        let cell = std::sync::Arc::new(splitrc::AtomicTx::new(None));
+        let cell2 = cell.clone();
+        let t = loom::thread::spawn(move || drop(cell2.swap(Some(tx1))));
+        drop(cell.swap(Some(tx2)));
+        t.join().unwrap();
+        drop(cell.take());
+        assert_eq!((true, false), rx1.access());
+        assert_eq!((true, false), rx2.access());
+    })
+}
+
+#[test]
+fn split_swap_reader_and_writer() {
+    loom::model(|| {
+        let (tx1, rx1) = splitrc::new(TrackNotify::default());
+        let (tx2, rx2) = splitrc::new(TrackNotify::default());
+        let swap = std::sync::Arc::new(splitrc::SplitSwap::new(tx1));
+        let reader = swap.clone();
+        let t = loom::thread::spawn(move || {
+            let guard = reader.load();
+            assert_eq!((false, false), guard.access());
+        });
+        swap.store(tx2);
+        t.join().unwrap();
+        assert_eq!((true, false), rx1.access());
+        drop(swap);
+        assert_eq!((true, false), rx2.access());
+    })
+}
+
+#[test]
+fn split_swap_reader_and_two_writes() {
+    struct Payload {
+        id: usize,
+        freed: loom::sync::Arc<[AtomicUsize; 3]>,
+    }
+    impl splitrc::Notify for Payload {}
+    impl Drop for Payload {
+        fn drop(&mut self) {
+            self.freed[self.id].store(1, Ordering::Release);
+        }
+    }
+
+    loom::model(|| {
+        let freed = loom::sync::Arc::new([
+            AtomicUsize::new(0),
+            AtomicUsize::new(0),
+            AtomicUsize::new(0),
+        ]);
+        let [tx0, tx1, tx2] = [0, 1, 2].map(|id| {
+            let (tx, rx) = splitrc::new(Payload {
+                id,
+                freed: freed.clone(),
+            });
+            drop(rx);
+            tx
+        });
+        let swap = std::sync::Arc::new(splitrc::SplitSwap::new(tx0));
+        let reader = swap.clone();
+        let reader_freed = freed.clone();
+        let t = loom::thread::spawn(move || {
+            let guard = reader.load();
+            let id = guard.id;
+            assert_eq!(
+                0,
+                reader_freed[id].load(Ordering::Acquire),
+                "payload {} freed while guard alive",
+                id
+            );
+        });
+        swap.store(tx1);
+        swap.store(tx2);
+        t.join().unwrap();
+        assert_eq!(1, freed[0].load(Ordering::Acquire));
+        assert_eq!(1, freed[1].load(Ordering::Acquire));
+        drop(swap);
+        assert_eq!(1, freed[2].load(Ordering::Acquire));
+    })
+}
+
+#[test]
+fn racing_multi_roles_drop_once() {
+    struct Roles {
+        notified: loom::sync::Arc<AtomicUsize>,
+        dropped: loom::sync::Arc<AtomicUsize>,
+    }
+    impl splitrc::multi::Notify for Roles {
+        fn last_role_did_drop(&self, _role: usize) {
+            self.notified.fetch_add(1, Ordering::AcqRel);
+        }
+    }
+    impl Drop for Roles {
+        fn drop(&mut self) {
+            self.dropped.fetch_add(1, Ordering::AcqRel);
+        }
+    }
+
+    loom::model(|| {
+        let notified = loom::sync::Arc::new(AtomicUsize::new(0));
+        let dropped = loom::sync::Arc::new(AtomicUsize::new(0));
+        let [a, b, c] = splitrc::multi::new::<_, 3>(Roles {
+            notified: notified.clone(),
+            dropped: dropped.clone(),
+        });
+        let t1 = loom::thread::spawn(move || drop(a));
+        let t2 = loom::thread::spawn(move || drop(b));
+        drop(c);
+        t1.join().unwrap();
+        t2.join().unwrap();
+        assert_eq!(2, notified.load(Ordering::Acquire));
+        assert_eq!(1, dropped.load(Ordering::Acquire));
+    })
+}
+
+#[test]
+fn racing_weighted_drops() {
+    loom::model(|| {
+        let (tx, rx) = splitrc::weighted::new(TrackNotify::default());
+        let tx2 = tx.clone();
+        loom::thread::spawn(move || tx.access());
+        loom::thread::spawn(move || tx2.access());
+        loom::thread::spawn(move || rx.access());
+    })
+}
+
+#[test]
+#[cfg(feature = "sharded")]
+fn racing_sharded_last_drop_once() {
+    loom::model(|| {
+        let notified = loom::sync::Arc::new(AtomicUsize::new(0));
+        let dropped = loom::sync::Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = splitrc::sharded::new(CountNotify {
+            notified: notified.clone(),
+            dropped: dropped.clone(),
+        });
+        // The clone lands in the spawned thread's shard, which races
+        // to empty against the original's.
+        let a = loom::thread::spawn(move || {
+            let tx2 = tx.clone();
+            drop(tx);
+            drop(tx2);
+        });
+        drop(rx);
+        a.join().unwrap();
+        assert_eq!(1, notified.load(Ordering::Acquire));
+        assert_eq!(1, dropped.load(Ordering::Acquire));
+    })
+}
+
+#[test]
+#[cfg(feature = "sharded")]
+fn racing_sharded_clone_and_drop() {
+    loom::model(|| {
+        let notified = loom::sync::Arc::new(AtomicUsize::new(0));
+        let dropped = loom::sync::Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = splitrc::sharded::new(CountNotify {
+            notified: notified.clone(),
+            dropped: dropped.clone(),
+        });
+        let tx = loom::sync::Arc::new(tx);
+        let shared = tx.clone();
+        // Occupies a new shard while the original shard empties.
+        let a = loom::thread::spawn(move || drop(Clone::clone(&*shared)));
+        drop(tx);
+        a.join().unwrap();
+        assert_eq!(1, notified.load(Ordering::Acquire));
+        drop(rx);
+        assert_eq!(1, dropped.load(Ordering::Acquire));
+    })
+}
+
+#[test]
+fn racing_drop_during_reentrant_notification() {
+    struct SelfOwned {
+        tx: loom::sync::Mutex<Option<splitrc::Tx<SelfOwned>>>,
+        in_callback: loom::sync::atomic::AtomicBool,
+        dropped: loom::sync::Arc<AtomicUsize>,
+    }
+
+    impl splitrc::Notify for SelfOwned {
+        fn last_rx_did_drop(&self) {
+            self.in_callback.store(true, Ordering::Relaxed);
+            drop(self.tx.lock().unwrap().take());
+            self.in_callback.store(false, Ordering::Relaxed);
+        }
+    }
+
+    impl Drop for SelfOwned {
+        fn drop(&mut self) {
+            assert!(!self.in_callback.load(Ordering::Relaxed));
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    loom::model(|| {
+        let dropped = loom::sync::Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = splitrc::new(SelfOwned {
+            tx: loom::sync::Mutex::new(None),
+            in_callback: loom::sync::atomic::AtomicBool::new(false),
+            dropped: dropped.clone(),
+        });
+        *tx.tx.lock().unwrap() = Some(tx.clone());
+        // The notification drops the stored Tx while this one races
+        // to be the last.
+        let a = loom::thread::spawn(move || drop(rx));
+        drop(tx);
+        a.join().unwrap();
+        assert_eq!(1, dropped.load(Ordering::Acquire));
+    })
+}
+
+#[cfg(feature = "revive")]
+#[test]
+fn racing_drop_during_revival() {
+    use std::pin::Pin;
+
+    struct Cached {
+        cached: loom::sync::Mutex<Option<splitrc::Rx<Cached>>>,
+        in_callback: loom::sync::atomic::AtomicBool,
+        dropped: loom::sync::Arc<AtomicUsize>,
+    }
+
+    impl splitrc::Notify for Cached {
+        fn last_rx_did_drop_revivable(
+            self: Pin<&Self>,
+            _ctx: splitrc::DropContext,
+            reviver: &splitrc::RxReviver<'_, Self>,
+        ) {
+            self.in_callback.store(true, Ordering::Relaxed);
+            let mut cached = self.cached.lock().unwrap();
+            if cached.is_none() {
+                *cached = Some(reviver.revive());
+            }
+            drop(cached);
+            self.in_callback.store(false, Ordering::Relaxed);
+        }
+    }
+
+    impl Drop for Cached {
+        fn drop(&mut self) {
+            assert!(!self.in_callback.load(Ordering::Relaxed));
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    loom::model(|| {
+        let dropped = loom::sync::Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = splitrc::new(Cached {
+            cached: loom::sync::Mutex::new(None),
+            in_callback: loom::sync::atomic::AtomicBool::new(false),
+            dropped: dropped.clone(),
+        });
+        let weak = splitrc::Rx::downgrade(&rx);
+        // The last Tx races with the revival.
+        let a = loom::thread::spawn(move || drop(rx));
+        drop(tx);
+        a.join().unwrap();
+        // If the Tx dropped first, the Rx was not notified and freed
+        // the payload.
+        if let Some(cached) = weak.upgrade() {
+            let revived = cached.cached.lock().unwrap().take();
+            drop(cached);
+            assert_eq!(0, dropped.load(Ordering::Acquire));
+            drop(revived);
+        }
+        assert_eq!(1, dropped.load(Ordering::Acquire));
+    })
+}
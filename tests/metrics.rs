@@ -0,0 +1,139 @@
+#![cfg(feature = "metrics")]
+
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use metrics_util::CompositeKey;
+
+struct Unit;
+impl splitrc::Notify for Unit {}
+
+/// Returns the value of the metric `name` with exactly `labels` in
+/// `snapshot`.
+fn value<'a, U, D>(
+    snapshot: &'a [(CompositeKey, U, D, DebugValue)],
+    name: &str,
+    labels: &[(&str, &str)],
+) -> Option<&'a DebugValue> {
+    snapshot
+        .iter()
+        .find(|(key, _, _, _)| {
+            let key = key.key();
+            key.name() == name
+                && key.labels().count() == labels.len()
+                && labels
+                    .iter()
+                    .all(|&(k, v)| key.labels().any(|l| l.key() == k && l.value() == v))
+        })
+        .map(|(_, _, _, value)| value)
+}
+
+fn gauge(value: Option<&DebugValue>) -> f64 {
+    match value {
+        Some(DebugValue::Gauge(v)) => v.into_inner(),
+        other => panic!("expected a gauge, got {:?}", other),
+    }
+}
+
+fn counter(value: Option<&DebugValue>) -> u64 {
+    match value {
+        Some(DebugValue::Counter(v)) => *v,
+        other => panic!("expected a counter, got {:?}", other),
+    }
+}
+
+#[test]
+fn live_allocations_gauge() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, || {
+        let (tx, rx) = splitrc::new(Unit);
+        let (tx2, rx2) = splitrc::new(Unit);
+        drop((tx, rx));
+        let snapshot = snapshotter.snapshot().into_vec();
+        assert_eq!(
+            1.0,
+            gauge(value(&snapshot, "splitrc_live_allocations", &[]))
+        );
+        drop((tx2, rx2));
+    });
+    // Snapshots drain, so this is the change since the last one.
+    let snapshot = snapshotter.snapshot().into_vec();
+    assert_eq!(
+        -1.0,
+        gauge(value(&snapshot, "splitrc_live_allocations", &[]))
+    );
+}
+
+#[test]
+fn labeled_clones_and_notifications() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, || {
+        let (tx, rx) = splitrc::new_labeled("workers", Unit);
+        let tx2 = tx.clone();
+        let rx2 = rx.clone();
+        drop((tx, tx2));
+        drop((rx, rx2));
+    });
+    let snapshot = snapshotter.snapshot().into_vec();
+    let labels = [("label", "workers")];
+    assert_eq!(
+        0.0,
+        gauge(value(&snapshot, "splitrc_live_allocations", &labels))
+    );
+    assert_eq!(None, value(&snapshot, "splitrc_live_allocations", &[]));
+    assert_eq!(
+        2,
+        counter(value(&snapshot, "splitrc_clones_total", &labels))
+    );
+    assert_eq!(
+        1,
+        counter(value(
+            &snapshot,
+            "splitrc_notifications_total",
+            &[("label", "workers"), ("half", "tx")]
+        ))
+    );
+    // The rx half closed after the tx half, so it was not notified.
+    assert_eq!(
+        None,
+        value(
+            &snapshot,
+            "splitrc_notifications_total",
+            &[("label", "workers"), ("half", "rx")]
+        )
+    );
+}
+
+#[test]
+fn scoped_allocations_are_balanced() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, || {
+        splitrc::scope(Unit, |tx, rx| drop((tx, rx)));
+        // A leaked handle leaves the payload for the scope to drop.
+        splitrc::scope(Unit, |tx, rx| {
+            std::mem::forget(tx);
+            drop(rx);
+        });
+    });
+    let snapshot = snapshotter.snapshot().into_vec();
+    assert_eq!(
+        0.0,
+        gauge(value(&snapshot, "splitrc_live_allocations", &[]))
+    );
+}
+
+#[test]
+fn unique_allocations_are_balanced() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, || {
+        drop(splitrc::new_unique(Unit));
+        drop(splitrc::UniqueHandle::split(splitrc::new_unique(Unit)));
+    });
+    let snapshot = snapshotter.snapshot().into_vec();
+    assert_eq!(
+        0.0,
+        gauge(value(&snapshot, "splitrc_live_allocations", &[]))
+    );
+}
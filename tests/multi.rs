@@ -0,0 +1,88 @@
+use splitrc::multi::Handle;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+const PRODUCER: usize = 0;
+const CONSUMER: usize = 1;
+const CONTROL: usize = 2;
+
+#[derive(Default)]
+struct Roles {
+    notified: Mutex<Vec<usize>>,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl splitrc::multi::Notify for Roles {
+    fn last_role_did_drop(&self, role: usize) {
+        self.notified.lock().unwrap().push(role);
+    }
+}
+
+impl Drop for Roles {
+    fn drop(&mut self) {
+        self.dropped.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+#[test]
+fn handles_are_returned_in_role_order() {
+    let handles = splitrc::multi::new::<_, 3>(Roles::default());
+    for (i, handle) in handles.iter().enumerate() {
+        assert_eq!(i, Handle::role(handle));
+        assert_eq!(1, Handle::count(handle, i));
+    }
+    assert!(Handle::ptr_eq(&handles[0], &handles[2]));
+}
+
+#[test]
+fn last_handle_of_role_notifies_that_role() {
+    let [producer, consumer, control] = splitrc::multi::new::<_, 3>(Roles::default());
+    let control2 = control.clone();
+    assert_eq!(2, Handle::count(&producer, CONTROL));
+    drop(control);
+    assert!(producer.notified.lock().unwrap().is_empty());
+    drop(control2);
+    assert_eq!(vec![CONTROL], *producer.notified.lock().unwrap());
+    assert!(Handle::is_closed(&consumer, CONTROL));
+    drop(producer);
+    assert_eq!(vec![CONTROL, PRODUCER], *consumer.notified.lock().unwrap());
+    assert!(!Handle::is_closed(&consumer, CONSUMER));
+}
+
+#[test]
+fn last_role_deallocates_without_notifying() {
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let [a, b] = splitrc::multi::new::<_, 2>(Roles {
+        notified: Mutex::default(),
+        dropped: dropped.clone(),
+    });
+    drop(a);
+    assert_eq!(vec![0], *b.notified.lock().unwrap());
+    assert_eq!(0, dropped.load(Ordering::Acquire));
+    drop(b);
+    assert_eq!(1, dropped.load(Ordering::Acquire));
+}
+
+#[test]
+fn roles_drop_on_other_threads() {
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let handles = splitrc::multi::new::<_, 8>(Roles {
+        notified: Mutex::default(),
+        dropped: dropped.clone(),
+    });
+    let threads: Vec<_> = handles
+        .into_iter()
+        .map(|handle| std::thread::spawn(move || drop(handle.clone())))
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(1, dropped.load(Ordering::Acquire));
+}
+
+#[test]
+#[should_panic(expected = "role out of range")]
+fn count_panics_on_unknown_role() {
+    let [a, _b] = splitrc::multi::new::<_, 2>(Roles::default());
+    Handle::count(&a, 2);
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "nightly")]
+
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+mod fixture;
+use fixture::TrackNotify;
+use fixture::Unit;
+
+trait Plugin: splitrc::Notify + Send + Sync {
+    fn tracker(&self) -> &TrackNotify;
+
+    fn rx_count(self: splitrc::Tx<Self>) -> u32;
+
+    fn stop(self: splitrc::Rx<Self>) -> bool;
+}
+
+#[derive(Default)]
+struct Echo {
+    track: TrackNotify,
+    stopped: AtomicBool,
+}
+
+impl splitrc::Notify for Echo {
+    fn last_tx_did_drop(&self) {
+        self.track.last_tx_did_drop()
+    }
+
+    fn last_rx_did_drop(&self) {
+        self.track.last_rx_did_drop()
+    }
+}
+
+impl Plugin for Echo {
+    fn tracker(&self) -> &TrackNotify {
+        &self.track
+    }
+
+    fn rx_count(self: splitrc::Tx<Self>) -> u32 {
+        splitrc::Tx::rx_count(&self)
+    }
+
+    fn stop(self: splitrc::Rx<Self>) -> bool {
+        !self.stopped.swap(true, Ordering::AcqRel)
+    }
+}
+
+#[test]
+fn handles_coerce_to_trait_objects() {
+    let (tx, rx) = splitrc::new(Echo::default());
+    let tx: splitrc::Tx<dyn Plugin> = tx;
+    let rx: splitrc::Rx<dyn Plugin> = rx;
+    drop(tx);
+    assert!(rx.tracker().tx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn arrays_coerce_to_slices() {
+    let (tx, rx) = splitrc::new([Unit, Unit]);
+    let tx: splitrc::Tx<[Unit]> = tx;
+    assert_eq!(2, tx.len());
+    drop(rx);
+}
+
+#[test]
+fn pinned_handles_coerce() {
+    let (tx, rx) = splitrc::pin(Echo::default());
+    let tx: Pin<splitrc::Tx<dyn Plugin>> = tx;
+    drop(rx);
+    assert!(tx.tracker().rx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn trait_objects_dispatch_on_handles() {
+    let (tx, rx) = splitrc::new(Echo::default());
+    let (tx, rx): (splitrc::Tx<dyn Plugin>, splitrc::Rx<dyn Plugin>) = (tx, rx);
+    let rx2 = rx.clone();
+    assert_eq!(2, tx.clone().rx_count());
+    assert!(rx.stop());
+    assert!(!rx2.stop());
+    assert!(tx.tracker().rx_did_drop.load(Ordering::Acquire));
+}
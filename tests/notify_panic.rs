@@ -0,0 +1,106 @@
+// With abort-on-notify-panic, these would abort the test process.
+#![cfg(not(feature = "abort-on-notify-panic"))]
+
+use std::panic;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Where [PanicNotify] panics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PanicOn {
+    Notify,
+    Deallocate,
+}
+
+/// Panics from the chosen callback and counts how many times it has
+/// been dropped.
+#[derive(Debug)]
+struct PanicNotify<'a> {
+    panic_on: PanicOn,
+    drops: &'a AtomicUsize,
+}
+
+impl splitrc::Notify for PanicNotify<'_> {
+    fn last_tx_did_drop(&self) {
+        if self.panic_on == PanicOn::Notify {
+            panic!("last_tx_did_drop")
+        }
+    }
+    fn last_rx_did_drop(&self) {
+        if self.panic_on == PanicOn::Notify {
+            panic!("last_rx_did_drop")
+        }
+    }
+    fn will_deallocate(&self) {
+        if self.panic_on == PanicOn::Deallocate {
+            panic!("will_deallocate")
+        }
+    }
+}
+
+impl Drop for PanicNotify<'_> {
+    fn drop(&mut self) {
+        self.drops.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn notify_panic_still_frees() {
+    let drops = AtomicUsize::new(0);
+    let (tx, rx) = splitrc::new(PanicNotify {
+        panic_on: PanicOn::Notify,
+        drops: &drops,
+    });
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| drop(tx)));
+    assert!(result.is_err());
+    assert_eq!(0, splitrc::Rx::counts(&rx).tx);
+    assert_eq!(0, drops.load(Ordering::Relaxed));
+    drop(rx);
+    assert_eq!(1, drops.load(Ordering::Relaxed));
+}
+
+#[test]
+fn notify_panic_then_last_drop_frees() {
+    let drops = AtomicUsize::new(0);
+    let (tx, rx) = splitrc::new(PanicNotify {
+        panic_on: PanicOn::Notify,
+        drops: &drops,
+    });
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| drop(rx)));
+    assert!(result.is_err());
+    drop(tx);
+    assert_eq!(1, drops.load(Ordering::Relaxed));
+}
+
+#[test]
+fn will_deallocate_panic_still_drops() {
+    let drops = AtomicUsize::new(0);
+    let (tx, rx) = splitrc::new(PanicNotify {
+        panic_on: PanicOn::Deallocate,
+        drops: &drops,
+    });
+    drop(tx);
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| drop(rx)));
+    assert!(result.is_err());
+    assert_eq!(1, drops.load(Ordering::Relaxed));
+}
+
+#[cfg(feature = "drop-callbacks")]
+#[test]
+fn notify_panic_still_fires_callbacks() {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    let drops = AtomicUsize::new(0);
+    let (tx, rx) = splitrc::new(PanicNotify {
+        panic_on: PanicOn::Notify,
+        drops: &drops,
+    });
+    let fired = Arc::new(AtomicBool::new(false));
+    let fired2 = fired.clone();
+    splitrc::Rx::on_tx_drop(&rx, move || fired2.store(true, Ordering::Relaxed));
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| drop(tx)));
+    assert!(result.is_err());
+    assert!(fired.load(Ordering::Relaxed));
+    drop(rx);
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "observer")]
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+#[derive(Default)]
+struct Observed {
+    tx_clones: AtomicUsize,
+    rx_clones: AtomicUsize,
+    tx_drops: AtomicUsize,
+    rx_drops: AtomicUsize,
+}
+
+impl splitrc::Notify for Observed {
+    fn tx_did_clone(&self) {
+        self.tx_clones.fetch_add(1, Ordering::Relaxed);
+    }
+    fn rx_did_clone(&self) {
+        self.rx_clones.fetch_add(1, Ordering::Relaxed);
+    }
+    fn tx_did_drop_one(&self) {
+        self.tx_drops.fetch_add(1, Ordering::Relaxed);
+    }
+    fn rx_did_drop_one(&self) {
+        self.rx_drops.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Observed {
+    fn get(&self) -> [usize; 4] {
+        [
+            self.tx_clones.load(Ordering::Relaxed),
+            self.rx_clones.load(Ordering::Relaxed),
+            self.tx_drops.load(Ordering::Relaxed),
+            self.rx_drops.load(Ordering::Relaxed),
+        ]
+    }
+}
+
+#[test]
+fn clone_and_drop_are_observed() {
+    let (tx, rx) = splitrc::new(Observed::default());
+    assert_eq!([0, 0, 0, 0], rx.get());
+    let tx2 = tx.clone();
+    drop(tx);
+    assert_eq!([1, 0, 1, 0], rx.get());
+    let rx2 = rx.clone();
+    drop(rx);
+    assert_eq!([1, 1, 1, 1], rx2.get());
+    drop(tx2);
+    assert_eq!([1, 1, 2, 1], rx2.get());
+}
+
+#[test]
+fn upgrade_and_borrow_are_observed() {
+    let (tx, rx) = splitrc::new(Observed::default());
+    let weak = splitrc::Tx::downgrade(&tx);
+    drop(weak.upgrade());
+    drop(splitrc::RxBorrow::to_rx(splitrc::Rx::borrow_handle(&rx)));
+    assert_eq!([1, 1, 1, 1], rx.get());
+    drop(tx);
+}
+
+#[test]
+fn erased_clone_is_observed() {
+    let (tx, rx) = splitrc::new(Observed::default());
+    let erased = splitrc::Tx::erase(tx);
+    drop(erased.clone());
+    assert_eq!([1, 0, 1, 0], rx.get());
+    drop(erased);
+}
+
+#[test]
+fn txrx_counts_both_halves() {
+    let both = splitrc::TxRx::new(Observed::default());
+    drop(both.clone());
+    assert_eq!([1, 1, 1, 1], both.get());
+}
+
+#[test]
+fn wrapped_payloads_forward_observations() {
+    let (tx, rx) = splitrc::new_padded(Observed::default());
+    drop((tx.clone(), rx.clone()));
+    assert_eq!([1, 1, 1, 1], rx.get());
+    drop(tx);
+
+    let (tx, rx) = splitrc::from_header_and_slice(Observed::default(), &[1, 2, 3]);
+    drop((tx.clone(), rx.clone()));
+    assert_eq!([1, 1, 1, 1], rx.header.get());
+    drop(tx);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn shared_buf_forwards_observations() {
+    let buf = splitrc::buf::SharedBuf::with_capacity(0, Observed::default());
+    let (tx, rx) = splitrc::new(buf);
+    drop((tx.clone(), rx.clone()));
+    assert_eq!([1, 1, 1, 1], rx.notify().get());
+    drop(tx);
+}
@@ -0,0 +1,49 @@
+use splitrc::{OffsetRx, OffsetTx, Rx, Tx};
+use std::mem;
+use std::sync::atomic::Ordering;
+
+mod fixture;
+
+use fixture::TrackNotify;
+use fixture::Unit;
+
+#[test]
+fn offset_handle_is_payload_pointer() {
+    assert_eq!(
+        mem::size_of::<*const Unit>(),
+        mem::size_of::<OffsetTx<Unit>>()
+    );
+    assert_eq!(
+        mem::size_of::<*const Unit>(),
+        mem::size_of::<Option<OffsetRx<Unit>>>()
+    );
+
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let data = Tx::as_ptr(&tx);
+    let tx = OffsetTx::from(tx);
+    let rx = OffsetRx::from(rx);
+    assert_eq!(data, OffsetTx::as_ptr(&tx));
+    assert_eq!(data, OffsetRx::as_ptr(&rx));
+    assert_eq!(data, &*tx as *const TrackNotify);
+
+    let ptr: *const TrackNotify = unsafe { mem::transmute(tx) };
+    assert_eq!(data, ptr);
+    let tx: OffsetTx<TrackNotify> = unsafe { mem::transmute(ptr) };
+    drop(tx);
+    assert!(rx.tx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn offset_clone_and_convert_back() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let rx = OffsetRx::from(rx);
+    let rx2 = rx.clone();
+    assert_eq!(2, Tx::rx_count(&tx));
+    assert_eq!(2, OffsetRx::with_rx(&rx2, Rx::rx_count));
+
+    let rx = OffsetRx::into_rx(rx);
+    drop(rx2);
+    assert!(!tx.rx_did_drop.load(Ordering::Acquire));
+    drop(rx);
+    assert!(tx.rx_did_drop.load(Ordering::Acquire));
+}
@@ -0,0 +1,74 @@
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+mod fixture;
+use fixture::Unit;
+
+// Send but not Sync.
+#[derive(Default)]
+struct Mailbox {
+    value: Cell<u32>,
+    rx_did_drop: Arc<AtomicBool>,
+}
+
+impl splitrc::Notify for Mailbox {
+    fn last_rx_did_drop(&self) {
+        self.rx_did_drop.store(true, Ordering::Release);
+    }
+}
+
+#[test]
+fn payload_moves_between_threads() {
+    let (mut tx, rx) = splitrc::new_once(Mailbox::default());
+    std::thread::spawn(move || drop(rx)).join().unwrap();
+    assert!(splitrc::TxOnce::is_closed(&tx));
+    let mailbox = splitrc::TxOnce::get_mut(&mut tx).unwrap();
+    assert!(mailbox.rx_did_drop.load(Ordering::Acquire));
+    mailbox.value.set(7);
+    let mailbox = splitrc::TxOnce::into_inner(tx).unwrap();
+    assert_eq!(7, mailbox.value.get());
+}
+
+#[test]
+fn halves_can_be_sent_to_another_thread() {
+    let rx_did_drop = Arc::new(AtomicBool::new(false));
+    let (tx, mut rx) = splitrc::new_once(Mailbox {
+        value: Cell::new(0),
+        rx_did_drop: rx_did_drop.clone(),
+    });
+    let tx = std::thread::spawn(move || tx).join().unwrap();
+    assert!(splitrc::RxOnce::get_mut(&mut rx).is_none());
+    assert!(!rx_did_drop.load(Ordering::Acquire));
+    std::thread::spawn(move || drop(rx)).join().unwrap();
+    assert!(splitrc::TxOnce::is_closed(&tx));
+    assert!(rx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn into_inner_notifies_while_other_half_lives() {
+    let (tx, rx) = splitrc::new_once(Mailbox::default());
+    assert!(splitrc::RxOnce::into_inner(rx).is_none());
+    let counts = splitrc::TxOnce::counts(&tx);
+    assert_eq!((1, 0), (counts.tx, counts.rx));
+    assert!(splitrc::TxOnce::into_inner(tx).is_some());
+}
+
+#[test]
+fn converts_into_cloneable_handles() {
+    let (tx, rx) = splitrc::new_once(Unit);
+    let tx = splitrc::TxOnce::into_tx(tx);
+    let rx = splitrc::RxOnce::into_rx(rx);
+    let tx2 = tx.clone();
+    drop(tx);
+    drop(tx2);
+    assert_eq!(0, splitrc::Rx::tx_count(&rx));
+}
+
+#[test]
+fn debug_reports_closed() {
+    let (tx, rx) = splitrc::new_once(Unit);
+    assert_eq!("TxOnce { closed: false }", format!("{:?}", tx));
+    drop(tx);
+    assert_eq!("RxOnce { closed: true }", format!("{:?}", rx));
+}
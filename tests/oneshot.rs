@@ -0,0 +1,68 @@
+#![cfg(feature = "std")]
+
+use splitrc::oneshot::{self, RecvError, TryRecvError};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark()
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    Pin::new(future).poll(&mut Context::from_waker(&waker))
+}
+
+#[test]
+fn receives_value_from_another_thread() {
+    let (tx, rx) = oneshot::channel();
+    let worker = thread::spawn(move || tx.send(String::from("done")).unwrap());
+    assert_eq!(Ok(String::from("done")), block_on(rx));
+    worker.join().unwrap();
+}
+
+#[test]
+fn dropping_sender_without_sending_fails_receiver() {
+    let (tx, mut rx) = oneshot::channel::<u32>();
+    assert!(poll_once(&mut rx).is_pending());
+    assert_eq!(Err(TryRecvError::Empty), rx.try_recv());
+    drop(tx);
+    assert_eq!(Err(TryRecvError::Closed), rx.try_recv());
+    assert_eq!(Poll::Ready(Err(RecvError)), poll_once(&mut rx));
+}
+
+#[test]
+fn send_returns_value_if_receiver_dropped() {
+    let (tx, rx) = oneshot::channel();
+    assert!(!tx.is_closed());
+    drop(rx);
+    assert!(tx.is_closed());
+    assert_eq!(Err(vec![1, 2]), tx.send(vec![1, 2]));
+}
+
+#[test]
+fn value_is_received_once() {
+    let (tx, mut rx) = oneshot::channel();
+    tx.send(3).unwrap();
+    assert_eq!(Ok(3), rx.try_recv());
+    assert_eq!(Err(TryRecvError::Closed), rx.try_recv());
+}
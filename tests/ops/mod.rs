@@ -0,0 +1,290 @@
+//! Applies a sequence of handle operations to one allocation and
+//! checks that each half is notified at most once, exactly when
+//! expected, and that the payload is dropped exactly once. Shared by
+//! tests/proptest.rs and the fuzz target.
+
+use splitrc::{Rx, RxWeak, Tx, TxWeak};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Op {
+    CloneTx(usize),
+    CloneRx(usize),
+    DropTx(usize),
+    DropRx(usize),
+    DowngradeTx(usize),
+    DowngradeRx(usize),
+    UpgradeTx(usize),
+    UpgradeRx(usize),
+    DropTxWeak(usize),
+    DropRxWeak(usize),
+    TxIntoRx(usize),
+    RxIntoTx(usize),
+    RawTx(usize),
+    RawRx(usize),
+}
+
+impl Op {
+    const KINDS: u8 = 14;
+
+    /// Decodes an operation from a kind byte and an index. Indices are
+    /// reduced modulo the number of handles when applied.
+    pub fn decode(kind: u8, index: u8) -> Op {
+        let i = index as usize;
+        match kind % Op::KINDS {
+            0 => Op::CloneTx(i),
+            1 => Op::CloneRx(i),
+            2 => Op::DropTx(i),
+            3 => Op::DropRx(i),
+            4 => Op::DowngradeTx(i),
+            5 => Op::DowngradeRx(i),
+            6 => Op::UpgradeTx(i),
+            7 => Op::UpgradeRx(i),
+            8 => Op::DropTxWeak(i),
+            9 => Op::DropRxWeak(i),
+            10 => Op::TxIntoRx(i),
+            11 => Op::RxIntoTx(i),
+            12 => Op::RawTx(i),
+            _ => Op::RawRx(i),
+        }
+    }
+
+    /// Decodes a byte string two bytes per operation.
+    pub fn decode_all(bytes: &[u8]) -> Vec<Op> {
+        bytes
+            .chunks_exact(2)
+            .map(|c| Op::decode(c[0], c[1]))
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct Counters {
+    tx_notified: AtomicUsize,
+    rx_notified: AtomicUsize,
+    will_deallocate: AtomicUsize,
+    dropped: AtomicUsize,
+}
+
+struct Probe(Arc<Counters>);
+
+impl splitrc::Notify for Probe {
+    fn last_tx_did_drop(&self) {
+        self.0.tx_notified.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn last_rx_did_drop(&self) {
+        self.0.rx_notified.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn will_deallocate(&self) {
+        self.0.will_deallocate.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Drop for Probe {
+    fn drop(&mut self) {
+        self.0.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+struct Model {
+    counters: Arc<Counters>,
+    txs: Vec<Tx<Probe>>,
+    rxs: Vec<Rx<Probe>>,
+    tx_weaks: Vec<TxWeak<Probe>>,
+    rx_weaks: Vec<RxWeak<Probe>>,
+    expect_tx_notified: usize,
+    expect_rx_notified: usize,
+}
+
+/// Removes the handle at `index` modulo the length, if any.
+fn take<H>(handles: &mut Vec<H>, index: usize) -> Option<H> {
+    if handles.is_empty() {
+        None
+    } else {
+        Some(handles.swap_remove(index % handles.len()))
+    }
+}
+
+fn get<H>(handles: &[H], index: usize) -> Option<&H> {
+    if handles.is_empty() {
+        None
+    } else {
+        Some(&handles[index % handles.len()])
+    }
+}
+
+impl Model {
+    fn new() -> Model {
+        let counters = Arc::<Counters>::default();
+        let (tx, rx) = splitrc::new(Probe(counters.clone()));
+        Model {
+            counters,
+            txs: vec![tx],
+            rxs: vec![rx],
+            tx_weaks: Vec::new(),
+            rx_weaks: Vec::new(),
+            expect_tx_notified: 0,
+            expect_rx_notified: 0,
+        }
+    }
+
+    fn tx_removed(&mut self) {
+        if self.txs.is_empty() && !self.rxs.is_empty() {
+            self.expect_tx_notified += 1;
+        }
+    }
+
+    fn rx_removed(&mut self) {
+        if self.rxs.is_empty() && !self.txs.is_empty() {
+            self.expect_rx_notified += 1;
+        }
+    }
+
+    fn apply(&mut self, op: Op) {
+        match op {
+            Op::CloneTx(i) => {
+                if let Some(tx) = get(&self.txs, i) {
+                    let tx = tx.clone();
+                    self.txs.push(tx);
+                }
+            }
+            Op::CloneRx(i) => {
+                if let Some(rx) = get(&self.rxs, i) {
+                    let rx = rx.clone();
+                    self.rxs.push(rx);
+                }
+            }
+            Op::DropTx(i) => {
+                if let Some(tx) = take(&mut self.txs, i) {
+                    drop(tx);
+                    self.tx_removed();
+                }
+            }
+            Op::DropRx(i) => {
+                if let Some(rx) = take(&mut self.rxs, i) {
+                    drop(rx);
+                    self.rx_removed();
+                }
+            }
+            Op::DowngradeTx(i) => {
+                if let Some(tx) = get(&self.txs, i) {
+                    let weak = Tx::downgrade(tx);
+                    self.tx_weaks.push(weak);
+                }
+            }
+            Op::DowngradeRx(i) => {
+                if let Some(rx) = get(&self.rxs, i) {
+                    let weak = Rx::downgrade(rx);
+                    self.rx_weaks.push(weak);
+                }
+            }
+            Op::UpgradeTx(i) => {
+                if let Some(weak) = get(&self.tx_weaks, i) {
+                    let tx = weak.upgrade();
+                    assert_eq!(!self.txs.is_empty(), tx.is_some());
+                    self.txs.extend(tx);
+                }
+            }
+            Op::UpgradeRx(i) => {
+                if let Some(weak) = get(&self.rx_weaks, i) {
+                    let rx = weak.upgrade();
+                    assert_eq!(!self.rxs.is_empty(), rx.is_some());
+                    self.rxs.extend(rx);
+                }
+            }
+            Op::DropTxWeak(i) => drop(take(&mut self.tx_weaks, i)),
+            Op::DropRxWeak(i) => drop(take(&mut self.rx_weaks, i)),
+            Op::TxIntoRx(i) => {
+                if let Some(tx) = take(&mut self.txs, i) {
+                    match Tx::into_rx(tx) {
+                        Ok(rx) => {
+                            self.rxs.push(rx);
+                            self.tx_removed();
+                        }
+                        Err(tx) => {
+                            assert!(self.rxs.is_empty());
+                            self.txs.push(tx);
+                        }
+                    }
+                }
+            }
+            Op::RxIntoTx(i) => {
+                if let Some(rx) = take(&mut self.rxs, i) {
+                    match Rx::into_tx(rx) {
+                        Ok(tx) => {
+                            self.txs.push(tx);
+                            self.rx_removed();
+                        }
+                        Err(rx) => {
+                            assert!(self.txs.is_empty());
+                            self.rxs.push(rx);
+                        }
+                    }
+                }
+            }
+            Op::RawTx(i) => {
+                if let Some(tx) = take(&mut self.txs, i) {
+                    let ptr = Tx::into_raw(tx);
+                    // SAFETY: ptr came from into_raw and is used once.
+                    self.txs.push(unsafe { Tx::from_raw(ptr) });
+                }
+            }
+            Op::RawRx(i) => {
+                if let Some(rx) = take(&mut self.rxs, i) {
+                    let ptr = Rx::into_raw(rx);
+                    // SAFETY: ptr came from into_raw and is used once.
+                    self.rxs.push(unsafe { Rx::from_raw(ptr) });
+                }
+            }
+        }
+    }
+
+    fn check(&self) {
+        let c = &self.counters;
+        assert_eq!(
+            self.expect_tx_notified,
+            c.tx_notified.load(Ordering::Relaxed)
+        );
+        assert_eq!(
+            self.expect_rx_notified,
+            c.rx_notified.load(Ordering::Relaxed)
+        );
+        assert!(self.expect_tx_notified <= 1 && self.expect_rx_notified <= 1);
+        let live = !self.txs.is_empty() || !self.rxs.is_empty();
+        let dropped = if live { 0 } else { 1 };
+        assert_eq!(dropped, c.will_deallocate.load(Ordering::Relaxed));
+        assert_eq!(dropped, c.dropped.load(Ordering::Relaxed));
+        if let Some(tx) = self.txs.first() {
+            let counts = Tx::counts(tx);
+            assert_eq!(self.txs.len(), counts.tx as usize);
+            assert_eq!(self.rxs.len(), counts.rx as usize);
+        }
+        if let Some(rx) = self.rxs.first() {
+            let counts = Rx::counts(rx);
+            assert_eq!(self.txs.len(), counts.tx as usize);
+            assert_eq!(self.rxs.len(), counts.rx as usize);
+        }
+    }
+}
+
+/// Applies `ops`, then drops the remaining handles, checking the
+/// notification and drop counts after every step.
+pub fn run(ops: &[Op]) {
+    let mut model = Model::new();
+    model.check();
+    for &op in ops {
+        model.apply(op);
+        model.check();
+    }
+    while !model.txs.is_empty() || !model.rxs.is_empty() {
+        model.apply(Op::DropTx(0));
+        model.apply(Op::DropRx(0));
+        model.check();
+    }
+    model.tx_weaks.clear();
+    model.rx_weaks.clear();
+    model.check();
+}
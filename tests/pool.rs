@@ -0,0 +1,47 @@
+#![cfg(feature = "std")]
+
+use std::sync::atomic::Ordering;
+use std::thread;
+
+mod fixture;
+
+use fixture::TrackNotify;
+use fixture::Unit;
+
+#[test]
+fn recycles_allocation() {
+    let pool = splitrc::pool::Pool::new();
+    let (tx, rx) = pool.alloc(TrackNotify::default());
+    let first = &*tx as *const TrackNotify;
+    drop(tx);
+    assert!(rx.tx_did_drop.load(Ordering::Acquire));
+    assert_eq!(0, pool.idle());
+    drop(rx);
+    assert_eq!(1, pool.idle());
+
+    let (tx, rx) = pool.alloc(TrackNotify::default());
+    assert_eq!(first, &*tx as *const TrackNotify);
+    assert_eq!(0, pool.idle());
+    assert!(!rx.tx_did_drop.load(Ordering::Acquire));
+    drop((tx, rx));
+}
+
+#[test]
+fn max_idle_frees_extra() {
+    let pool = splitrc::pool::Pool::with_max_idle(1);
+    let a = pool.alloc(Unit);
+    let b = pool.alloc(Unit);
+    drop(a);
+    drop(b);
+    assert_eq!(1, pool.idle());
+}
+
+#[test]
+fn handles_outlive_pool() {
+    let pool = splitrc::pool::Pool::new();
+    let (tx, rx) = pool.alloc(TrackNotify::default());
+    drop(pool);
+    let worker = thread::spawn(move || drop(tx));
+    worker.join().unwrap();
+    assert!(rx.tx_did_drop.load(Ordering::Acquire));
+}
@@ -0,0 +1,23 @@
+mod ops;
+
+use ops::Op;
+use proptest::prelude::*;
+
+fn ops() -> impl Strategy<Value = Vec<Op>> {
+    prop::collection::vec(
+        (any::<u8>(), any::<u8>()).prop_map(|(kind, index)| Op::decode(kind, index)),
+        0..64,
+    )
+}
+
+proptest! {
+    #[test]
+    fn notifies_and_drops_exactly_once(ops in ops()) {
+        ops::run(&ops);
+    }
+}
+
+#[test]
+fn decodes_byte_strings() {
+    ops::run(&Op::decode_all(&[0, 0, 10, 1, 2, 0, 4, 0, 3, 3, 6, 0]));
+}
@@ -0,0 +1,60 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::task::Waker;
+
+#[derive(Default)]
+struct Task {
+    woken: AtomicUsize,
+    tx_did_drop: AtomicBool,
+    rx_did_drop: AtomicBool,
+}
+
+impl splitrc::Notify for Task {
+    fn last_tx_did_drop(&self) {
+        self.tx_did_drop.store(true, Ordering::Release);
+    }
+
+    fn last_rx_did_drop(&self) {
+        self.rx_did_drop.store(true, Ordering::Release);
+    }
+}
+
+impl splitrc::Wake for Task {
+    fn wake(&self) {
+        self.woken.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+#[test]
+fn tx_waker_counts_as_tx() {
+    let (tx, rx) = splitrc::new(Task::default());
+    let waker = Waker::from(tx);
+    let clone = waker.clone();
+    assert_eq!(2, splitrc::Rx::counts(&rx).tx);
+    clone.wake_by_ref();
+    clone.wake();
+    assert_eq!(2, rx.woken.load(Ordering::Acquire));
+    assert_eq!(1, splitrc::Rx::counts(&rx).tx);
+    assert!(!rx.tx_did_drop.load(Ordering::Acquire));
+    drop(waker);
+    assert!(rx.tx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn rx_waker_counts_as_rx() {
+    let (tx, rx) = splitrc::new(Task::default());
+    let waker = splitrc::Rx::into_waker(rx);
+    assert!(waker.will_wake(&waker.clone()));
+    assert_eq!(1, splitrc::Tx::counts(&tx).rx);
+    waker.wake();
+    assert_eq!(1, tx.woken.load(Ordering::Acquire));
+    assert!(tx.rx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn waker_keeps_allocation_alive() {
+    let (tx, rx) = splitrc::new(Task::default());
+    drop(rx);
+    let waker = Waker::from(tx);
+    waker.wake_by_ref();
+    drop(waker);
+}
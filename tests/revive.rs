@@ -0,0 +1,193 @@
+#![cfg(feature = "revive")]
+
+use splitrc::{DropContext, Notify, Rx, RxReviver, Tx, TxOnce, TxReviver};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// An entry that a cache keeps alive for a number of revivals after
+/// its readers are gone.
+#[derive(Default)]
+struct Entry {
+    revivals: AtomicUsize,
+    cached: Mutex<Option<Rx<Entry>>>,
+    // Dropped from the rx notification, before or after reviving.
+    tx: Mutex<Option<Tx<Entry>>>,
+    drop_tx_first: AtomicBool,
+    rx_notified: AtomicUsize,
+    tx_notified: AtomicUsize,
+    dropped: DropFlag,
+}
+
+/// Set when the entry is dropped.
+#[derive(Default)]
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Notify for Entry {
+    fn last_rx_did_drop_revivable(
+        self: Pin<&Self>,
+        _ctx: DropContext,
+        reviver: &RxReviver<'_, Self>,
+    ) {
+        self.rx_notified.fetch_add(1, Ordering::Relaxed);
+        if self.drop_tx_first.load(Ordering::Relaxed) {
+            drop(self.tx.lock().unwrap().take());
+        }
+        let revive = self
+            .revivals
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            .is_ok();
+        if revive {
+            *self.cached.lock().unwrap() = Some(reviver.revive());
+        }
+        drop(self.tx.lock().unwrap().take());
+        assert!(!self.dropped.0.load(Ordering::Relaxed));
+    }
+
+    fn last_tx_did_drop(&self) {
+        self.tx_notified.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn take_cached(tx: &Tx<Entry>) -> Option<Rx<Entry>> {
+    tx.cached.lock().unwrap().take()
+}
+
+#[test]
+fn revived_half_is_open() {
+    let (tx, rx) = splitrc::new(Entry {
+        revivals: AtomicUsize::new(1),
+        ..Default::default()
+    });
+    drop(rx);
+    assert_eq!(1, tx.rx_notified.load(Ordering::Relaxed));
+    assert_eq!(1, Tx::rx_count(&tx));
+
+    // Out of revivals, so the half closes.
+    drop(take_cached(&tx));
+    assert_eq!(2, tx.rx_notified.load(Ordering::Relaxed));
+    assert_eq!(0, Tx::rx_count(&tx));
+}
+
+#[test]
+fn revived_half_outlives_other_half() {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = splitrc::new(Entry {
+        revivals: AtomicUsize::new(1),
+        drop_tx_first: AtomicBool::new(true),
+        dropped: DropFlag(dropped.clone()),
+        ..Default::default()
+    });
+    *tx.tx.lock().unwrap() = Some(tx.clone());
+    let weak = Rx::downgrade(&rx);
+    drop(tx);
+    drop(rx);
+    let cached = weak.upgrade().expect("revived");
+    let mut revived = cached.cached.lock().unwrap().take().unwrap();
+    drop((cached, weak));
+    // The tx half closed while the rx half was closed, so it was not
+    // notified, and the revived handle is now the only one.
+    assert_eq!(0, Rx::tx_count(&revived));
+    assert_eq!(0, revived.tx_notified.load(Ordering::Relaxed));
+    assert!(Rx::get_mut(&mut revived).is_some());
+    drop(revived);
+    assert!(dropped.load(Ordering::Relaxed));
+}
+
+#[test]
+fn other_half_closing_after_revival_is_notified() {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = splitrc::new(Entry {
+        revivals: AtomicUsize::new(1),
+        dropped: DropFlag(dropped.clone()),
+        ..Default::default()
+    });
+    *tx.tx.lock().unwrap() = Some(tx.clone());
+    let weak = Rx::downgrade(&rx);
+    drop(tx);
+    drop(rx);
+    let cached = weak.upgrade().expect("revived");
+    assert_eq!(1, cached.tx_notified.load(Ordering::Relaxed));
+    assert_eq!(0, Rx::tx_count(&cached));
+    let revived = cached.cached.lock().unwrap().take();
+    drop(cached);
+    drop(revived);
+    assert!(dropped.load(Ordering::Relaxed));
+}
+
+#[test]
+fn tx_half_may_be_revived() {
+    #[derive(Default)]
+    struct Producer {
+        revived: AtomicBool,
+        kept: Mutex<Option<Tx<Producer>>>,
+    }
+
+    impl Notify for Producer {
+        fn last_tx_did_drop_revivable(
+            self: Pin<&Self>,
+            _ctx: DropContext,
+            reviver: &TxReviver<'_, Self>,
+        ) {
+            if !self.revived.swap(true, Ordering::Relaxed) {
+                *self.kept.lock().unwrap() = Some(reviver.revive());
+            }
+        }
+    }
+
+    let (tx, rx) = splitrc::new(Producer::default());
+    drop(tx);
+    assert_eq!(1, Rx::tx_count(&rx));
+    let kept = rx.kept.lock().unwrap().take();
+    drop(kept);
+    assert_eq!(0, Rx::tx_count(&rx));
+}
+
+/// Records plain notifications and panics if offered a revival.
+#[derive(Default)]
+struct Unrevivable {
+    notified: AtomicBool,
+}
+
+impl Notify for Unrevivable {
+    fn last_rx_did_drop_with(self: Pin<&Self>, _ctx: DropContext) {
+        self.notified.store(true, Ordering::Relaxed);
+    }
+
+    fn last_rx_did_drop_revivable(
+        self: Pin<&Self>,
+        _ctx: DropContext,
+        _reviver: &RxReviver<'_, Self>,
+    ) {
+        panic!("this allocation cannot mint an Rx");
+    }
+}
+
+#[test]
+fn local_allocations_are_not_revivable() {
+    let (tx, rx) = splitrc::local::new(Unrevivable::default());
+    drop(rx);
+    assert!(tx.notified.load(Ordering::Relaxed));
+}
+
+#[test]
+fn scoped_allocations_are_not_revivable() {
+    splitrc::scope(Unrevivable::default(), |tx, rx| {
+        drop(rx);
+        assert!(tx.notified.load(Ordering::Relaxed));
+    });
+}
+
+#[test]
+fn once_allocations_are_not_revivable() {
+    let (tx, rx) = splitrc::new_once(Unrevivable::default());
+    drop(rx);
+    let data = TxOnce::into_inner(tx).unwrap();
+    assert!(data.notified.load(Ordering::Relaxed));
+}
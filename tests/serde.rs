@@ -0,0 +1,38 @@
+#![cfg(feature = "serde")]
+
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Snapshot {
+    name: String,
+    values: Vec<u32>,
+}
+
+impl splitrc::Notify for Snapshot {}
+
+#[test]
+fn serialize_as_payload() {
+    let (tx, rx) = splitrc::new(Snapshot {
+        name: "a".into(),
+        values: vec![1, 2],
+    });
+    let expected = r#"{"name":"a","values":[1,2]}"#;
+    assert_eq!(expected, serde_json::to_string(&tx).unwrap());
+    assert_eq!(expected, serde_json::to_string(&rx).unwrap());
+}
+
+#[test]
+fn deserialize_pair() {
+    let json = r#"{"name":"b","values":[3]}"#;
+    let mut de = serde_json::Deserializer::from_str(json);
+    let (tx, rx) = splitrc::deserialize_pair::<Snapshot, _>(&mut de).unwrap();
+    assert!(splitrc::same_allocation(&tx, &rx));
+    assert_eq!(vec![3], rx.values);
+
+    let mut de = serde_json::Deserializer::from_str(json);
+    let (tx, _rx) = splitrc::PairSeed::<Snapshot>::new()
+        .deserialize(&mut de)
+        .unwrap();
+    assert_eq!("b", tx.name);
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "sharded")]
+
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+mod fixture;
+
+use fixture::TrackNotify;
+use fixture::Unit;
+
+#[test]
+fn new_and_delete() {
+    let (tx, rx) = splitrc::sharded::new(Unit);
+    drop(tx);
+    drop(rx);
+}
+
+#[test]
+fn drop_tx_notifies_after_clones() {
+    let (tx, rx) = splitrc::sharded::new(TrackNotify::default());
+    let tx2 = tx.clone();
+    drop(tx);
+    assert!(!rx.tx_did_drop.load(Ordering::Acquire));
+    drop(tx2);
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn clones_on_many_threads_notify_once() {
+    const THREADS: usize = 8;
+    let (tx, rx) = splitrc::sharded::new(TrackNotify::default());
+    // The main thread joins both barriers, so every thread still
+    // holds its rx when the original is dropped.
+    let barrier = Arc::new(Barrier::new(THREADS + 1));
+    let threads: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let rx = rx.clone();
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..1000 {
+                    drop(rx.clone());
+                }
+                barrier.wait();
+            })
+        })
+        .collect();
+    barrier.wait();
+    drop(rx);
+    assert!(!tx.rx_did_drop.load(Ordering::Acquire));
+    barrier.wait();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!((false, true), tx.access());
+}
+
+#[test]
+fn formatting() {
+    let (tx, rx) = splitrc::sharded::new(Unit);
+    assert!(splitrc::sharded::Tx::ptr_eq(&tx, &tx.clone()));
+    assert_eq!("Unit", format!("{:?}", tx));
+    assert_eq!("Unit", format!("{}", rx));
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "std")]
+
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn completes_when_guards_drop() {
+    let (guard, watcher) = splitrc::shutdown::new();
+    let guard2 = guard.clone();
+    assert_eq!(2, watcher.guard_count());
+    drop(guard);
+    assert!(!watcher.is_complete());
+    drop(guard2);
+    assert!(watcher.is_complete());
+    assert_eq!(0, watcher.guard_count());
+    watcher.wait();
+}
+
+#[test]
+fn wait_for_workers() {
+    let (guard, watcher) = splitrc::shutdown::new();
+    for _ in 0..4 {
+        let guard = guard.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            drop(guard);
+        });
+    }
+    drop(guard);
+    watcher.wait();
+    assert!(watcher.is_complete());
+}
+
+#[cfg(not(loom))]
+#[test]
+fn wait_timeout() {
+    let (guard, watcher) = splitrc::shutdown::new();
+    assert!(!watcher.wait_timeout(Duration::from_millis(10)));
+    assert!(guard.is_watched());
+    drop(guard);
+    assert!(watcher.wait_timeout(Duration::MAX));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn drained_future() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Wake, Waker};
+
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let (guard, watcher) = splitrc::shutdown::new();
+    let mut drained = watcher.drained();
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    assert!(Pin::new(&mut drained).poll(&mut cx).is_pending());
+    drop(guard);
+    assert!(Pin::new(&mut drained).poll(&mut cx).is_ready());
+}
@@ -0,0 +1,155 @@
+#![cfg(shuttle)]
+
+use shuttle::sync::atomic::AtomicUsize;
+use shuttle::sync::Arc;
+use shuttle::thread;
+use splitrc::{Rx, Tx};
+use std::sync::atomic::Ordering;
+
+// Loom's exhaustive search does not scale past a few handles, so these
+// races sample schedules instead.
+const ITERATIONS: usize = 1000;
+
+#[derive(Default)]
+struct Calls {
+    tx: AtomicUsize,
+    rx: AtomicUsize,
+    freed: AtomicUsize,
+}
+
+impl Calls {
+    fn access(&self) -> (usize, usize, usize) {
+        (
+            self.tx.load(Ordering::Acquire),
+            self.rx.load(Ordering::Acquire),
+            self.freed.load(Ordering::Acquire),
+        )
+    }
+}
+
+struct Payload(Arc<Calls>);
+
+impl splitrc::Notify for Payload {
+    fn last_tx_did_drop(&self) {
+        self.0.tx.fetch_add(1, Ordering::AcqRel);
+    }
+    fn last_rx_did_drop(&self) {
+        self.0.rx.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+impl Drop for Payload {
+    fn drop(&mut self) {
+        self.0.freed.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+fn new() -> (Arc<Calls>, Tx<Payload>, Rx<Payload>) {
+    let calls = Arc::new(Calls::default());
+    let (tx, rx) = splitrc::new(Payload(calls.clone()));
+    (calls, tx, rx)
+}
+
+fn join_all(handles: Vec<thread::JoinHandle<()>>) {
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+#[test]
+fn four_tx_drops_notify_once() {
+    shuttle::check_random(
+        || {
+            let (calls, tx, rx) = new();
+            let handles = (0..4)
+                .map(|_| {
+                    let tx = tx.clone();
+                    thread::spawn(move || drop(tx))
+                })
+                .collect();
+            drop(tx);
+            join_all(handles);
+            assert_eq!((1, 0, 0), calls.access());
+            drop(rx);
+            assert_eq!((1, 0, 1), calls.access());
+        },
+        ITERATIONS,
+    )
+}
+
+#[test]
+fn four_rx_drops_notify_once() {
+    shuttle::check_random(
+        || {
+            let (calls, tx, rx) = new();
+            let handles = (0..4)
+                .map(|_| {
+                    let rx = rx.clone();
+                    thread::spawn(move || drop(rx))
+                })
+                .collect();
+            drop(rx);
+            join_all(handles);
+            assert_eq!((0, 1, 0), calls.access());
+            drop(tx);
+            assert_eq!((0, 1, 1), calls.access());
+        },
+        ITERATIONS,
+    )
+}
+
+#[test]
+fn both_halves_race_to_close() {
+    shuttle::check_random(
+        || {
+            let (calls, tx, rx) = new();
+            let mut handles = Vec::new();
+            for _ in 0..2 {
+                let tx = tx.clone();
+                handles.push(thread::spawn(move || drop(tx)));
+                let rx = rx.clone();
+                handles.push(thread::spawn(move || drop(rx)));
+            }
+            drop((tx, rx));
+            join_all(handles);
+            // Whichever half closes first is notified; the other
+            // half frees the payload.
+            let (tx_calls, rx_calls, freed) = calls.access();
+            assert_eq!(1, tx_calls + rx_calls);
+            assert_eq!(1, freed);
+        },
+        ITERATIONS,
+    )
+}
+
+#[test]
+fn clones_race_with_drops() {
+    shuttle::check_pct(
+        || {
+            let (calls, tx, rx) = new();
+            let handles = (0..4)
+                .map(|i| {
+                    let tx = tx.clone();
+                    let rx = rx.clone();
+                    thread::spawn(move || {
+                        if i % 2 == 0 {
+                            drop(tx.clone());
+                        } else {
+                            drop(rx.clone());
+                        }
+                        assert!(Tx::rx_count(&tx) >= 1);
+                        drop((tx, rx));
+                    })
+                })
+                .collect();
+            drop(rx);
+            join_all(handles);
+            assert_eq!((0, 1, 0), calls.access());
+            assert_eq!(1, Tx::tx_count(&tx));
+            drop(tx);
+            assert_eq!((0, 1, 1), calls.access());
+        },
+        ITERATIONS,
+        3,
+    )
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "std")]
+
+use std::sync::Arc;
+use std::thread;
+
+mod fixture;
+
+use fixture::TrackNotify;
+use fixture::Unit;
+
+#[test]
+fn guard_delays_retired_drop() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let swap = splitrc::SplitSwap::new(tx);
+    let guard = swap.load();
+    let (next, _next_rx) = splitrc::new(TrackNotify::default());
+    swap.store(next);
+    assert_eq!((false, false), guard.access());
+    assert_eq!(1, splitrc::Rx::tx_count(&rx));
+    drop(guard);
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn later_readers_do_not_hold_back_older_values() {
+    let (a, a_rx) = splitrc::new(TrackNotify::default());
+    let (b, b_rx) = splitrc::new(TrackNotify::default());
+    let (c, _c_rx) = splitrc::new(TrackNotify::default());
+    let swap = splitrc::SplitSwap::new(a);
+    let old_reader = swap.load();
+    swap.store(b);
+    let new_reader = swap.load();
+    swap.store(c);
+    drop(old_reader);
+    assert_eq!((true, false), a_rx.access());
+    assert_eq!((false, false), b_rx.access());
+    drop(new_reader);
+    assert_eq!((true, false), b_rx.access());
+}
+
+#[test]
+fn load_full_outlives_store() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let swap = splitrc::SplitSwap::new(tx);
+    let full = swap.load_full();
+    assert_eq!(2, splitrc::Rx::tx_count(&rx));
+    let (next, _next_rx) = splitrc::new(TrackNotify::default());
+    swap.store(next);
+    assert_eq!(1, splitrc::Rx::tx_count(&rx));
+    drop(full);
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn drop_releases_current_value() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    drop(splitrc::SplitSwap::new(tx));
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn concurrent_loads_and_stores() {
+    let count = if cfg!(miri) { 20 } else { 1000 };
+    let (tx, _rx) = splitrc::new(vec![0usize; 8]);
+    let swap = Arc::new(splitrc::SplitSwap::new(tx));
+    let readers: Vec<_> = (0..3)
+        .map(|_| {
+            let swap = swap.clone();
+            thread::spawn(move || {
+                for _ in 0..count {
+                    let guard = swap.load();
+                    // Every value is uniform, so a torn or freed read
+                    // would show up as a mismatch.
+                    assert!(guard.iter().all(|&n| n == guard[0]));
+                }
+            })
+        })
+        .collect();
+    let rxs: Vec<_> = (1..=count)
+        .map(|i| {
+            let (tx, rx) = splitrc::new(vec![i; 8]);
+            swap.store(tx);
+            rx
+        })
+        .collect();
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert_eq!(count, swap.load()[0]);
+    // Every replaced value has been dropped by the writer or a reader.
+    for rx in &rxs[..count - 1] {
+        assert_eq!(0, splitrc::Rx::tx_count(rx));
+    }
+}
+
+#[test]
+fn debug_shows_current_value() {
+    let (tx, _rx) = splitrc::new(Unit);
+    let swap = splitrc::SplitSwap::new(tx);
+    assert_eq!("SplitSwap { value: Unit }", format!("{:?}", swap));
+    assert_eq!("Unit", format!("{:?}", swap.load()));
+}
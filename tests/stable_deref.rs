@@ -0,0 +1,56 @@
+#![cfg(feature = "stable_deref_trait")]
+
+use stable_deref_trait::{CloneStableDeref, StableDeref};
+
+mod fixture;
+
+use fixture::TrackNotify;
+use fixture::Unit;
+
+// Like owning_ref and yoke, keeps a pointer into the owner's target
+// while the owner moves.
+struct OwningRef<O: StableDeref, U: ?Sized + 'static> {
+    owner: O,
+    field: *const U,
+}
+
+impl<O: StableDeref, U: ?Sized + 'static> OwningRef<O, U> {
+    fn new(owner: O, f: impl FnOnce(&O::Target) -> &U) -> Self {
+        let field = f(&owner) as *const U;
+        OwningRef { owner, field }
+    }
+
+    fn get(&self) -> &U {
+        unsafe { &*self.field }
+    }
+}
+
+impl<O: CloneStableDeref, U: ?Sized + 'static> Clone for OwningRef<O, U> {
+    fn clone(&self) -> Self {
+        OwningRef {
+            owner: self.owner.clone(),
+            field: self.field,
+        }
+    }
+}
+
+#[test]
+fn handles_own_stable_borrows() {
+    let (tx, rx) = splitrc::new((Unit, String::from("payload")));
+    let tx = OwningRef::new(tx, |(_, s)| s.as_str());
+    let rx = OwningRef::new(rx, |(_, s)| &s[..3]);
+    let moved = Box::new(tx);
+    let cloned = rx.clone();
+    drop(rx);
+    assert_eq!("payload", moved.get());
+    assert_eq!("pay", cloned.get());
+    assert_eq!(moved.owner.1.as_ptr(), cloned.get().as_ptr());
+}
+
+#[test]
+fn owning_ref_keeps_handle_alive() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let rx = OwningRef::new(rx, |n| &n.tx_did_drop);
+    drop(tx);
+    assert!(rx.get().load(std::sync::atomic::Ordering::Acquire));
+}
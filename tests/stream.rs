@@ -0,0 +1,119 @@
+#![cfg(feature = "async")]
+
+use futures_core::Stream;
+use futures_sink::Sink;
+use splitrc::{SharedSink, SharedStream};
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+/// An unbounded queue that ends once its senders are gone.
+#[derive(Default)]
+struct Queue {
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    items: VecDeque<u32>,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+impl splitrc::Notify for Queue {
+    fn last_tx_did_drop(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl SharedStream for Queue {
+    type Item = u32;
+
+    fn poll_next(self: Pin<&Self>, cx: &mut Context<'_>) -> Poll<Option<u32>> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(item) = state.items.pop_front() {
+            Poll::Ready(Some(item))
+        } else if state.closed {
+            Poll::Ready(None)
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.state.lock().unwrap().items.len(), None)
+    }
+}
+
+impl SharedSink<u32> for Queue {
+    type Error = ();
+
+    fn poll_ready(self: Pin<&Self>, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&Self>, item: u32) -> Result<(), ()> {
+        let mut state = self.state.lock().unwrap();
+        state.items.push_back(item);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&Self>, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&Self>, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[derive(Default)]
+struct CountWakes(Mutex<usize>);
+
+impl Wake for CountWakes {
+    fn wake(self: Arc<Self>) {
+        *self.0.lock().unwrap() += 1;
+    }
+}
+
+fn send<S: Sink<u32> + Unpin>(sink: &mut S, item: u32, cx: &mut Context<'_>) {
+    assert!(Pin::new(&mut *sink).poll_ready(cx).is_ready());
+    assert!(Pin::new(&mut *sink).start_send(item).is_ok());
+    assert!(Pin::new(&mut *sink).poll_flush(cx).is_ready());
+}
+
+#[test]
+fn pinned_halves_are_stream_and_sink() {
+    let wakes = Arc::new(CountWakes::default());
+    let waker = Waker::from(wakes.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let (tx, rx) = splitrc::pin(Queue::default());
+    let mut tx2 = tx.clone();
+    let mut rx = rx;
+    assert_eq!(Poll::Pending, Pin::new(&mut rx).poll_next(&mut cx));
+
+    send(&mut tx2, 1, &mut cx);
+    assert_eq!(1, *wakes.0.lock().unwrap());
+    send(&mut tx2, 2, &mut cx);
+    assert_eq!((2, None), rx.size_hint());
+    assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut rx).poll_next(&mut cx));
+    assert_eq!(Poll::Ready(Some(2)), Pin::new(&mut rx).poll_next(&mut cx));
+    assert_eq!(Poll::Pending, Pin::new(&mut rx).poll_next(&mut cx));
+
+    // The stream ends when the last sender closes the tx half.
+    drop(tx);
+    assert!(Pin::new(&mut tx2).poll_close(&mut cx).is_ready());
+    drop(tx2);
+    assert_eq!(2, *wakes.0.lock().unwrap());
+    assert_eq!(Poll::Ready(None), Pin::new(&mut rx).poll_next(&mut cx));
+}
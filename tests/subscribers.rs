@@ -0,0 +1,91 @@
+#![cfg(feature = "drop-callbacks")]
+
+use std::sync::{Arc, Mutex};
+
+mod fixture;
+
+use fixture::Unit;
+
+#[derive(Clone, Default)]
+struct Log(Arc<Mutex<Vec<&'static str>>>);
+
+impl Log {
+    fn record(&self, name: &'static str) -> impl FnOnce() + Send + 'static {
+        let log = self.clone();
+        move || log.0.lock().unwrap().push(name)
+    }
+
+    fn get(&self) -> Vec<&'static str> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+#[test]
+fn on_tx_drop_runs_in_order_when_last_tx_drops() {
+    let log = Log::default();
+    let (tx, rx) = splitrc::new(Unit);
+    splitrc::Rx::on_tx_drop(&rx, log.record("a"));
+    splitrc::Rx::on_tx_drop(&rx, log.record("b"));
+    let tx2 = tx.clone();
+    drop(tx);
+    assert!(log.get().is_empty());
+    drop(tx2);
+    assert_eq!(vec!["a", "b"], log.get());
+    drop(rx);
+    assert_eq!(vec!["a", "b"], log.get());
+}
+
+#[test]
+fn unsubscribed_callbacks_do_not_run() {
+    let log = Log::default();
+    let (tx, rx) = splitrc::new(Unit);
+    let a = splitrc::Tx::on_rx_drop(&tx, log.record("a"));
+    let b = splitrc::Tx::on_rx_drop(&tx, log.record("b"));
+    assert_ne!(a, b);
+    assert!(splitrc::Tx::unsubscribe_rx_drop(&tx, a));
+    assert!(!splitrc::Tx::unsubscribe_rx_drop(&tx, a));
+    drop(rx);
+    assert_eq!(vec!["b"], log.get());
+    assert!(!splitrc::Tx::unsubscribe_rx_drop(&tx, b));
+}
+
+#[test]
+fn subscribing_after_close_runs_immediately() {
+    let log = Log::default();
+    let (tx, rx) = splitrc::new(Unit);
+    drop(tx);
+    splitrc::Rx::on_tx_drop(&rx, log.record("late"));
+    assert_eq!(vec!["late"], log.get());
+}
+
+#[test]
+fn runs_on_deallocation_if_other_half_went_first() {
+    let log = Log::default();
+    let (tx, rx) = splitrc::new(Unit);
+    splitrc::Rx::on_tx_drop(&rx, log.record("tx"));
+    splitrc::Tx::on_rx_drop(&tx, log.record("rx"));
+    drop(rx);
+    assert_eq!(vec!["rx"], log.get());
+    drop(tx);
+    assert_eq!(vec!["rx", "tx"], log.get());
+}
+
+#[test]
+fn callbacks_may_subscribe() {
+    let log = Log::default();
+    let (tx, rx) = splitrc::new(Unit);
+    let rx = Arc::new(rx);
+    let nested = log.record("nested");
+    splitrc::Rx::on_tx_drop(&rx, {
+        let rx = Arc::downgrade(&rx);
+        let outer = log.record("outer");
+        move || {
+            outer();
+            if let Some(rx) = rx.upgrade() {
+                splitrc::Rx::on_tx_drop(&rx, nested);
+            }
+        }
+    });
+    drop(tx);
+    assert_eq!(vec!["outer", "nested"], log.get());
+}
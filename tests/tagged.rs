@@ -0,0 +1,67 @@
+use splitrc::{Rx, TaggedRx, TaggedTx, Tx};
+use std::mem;
+use std::sync::atomic::Ordering;
+
+mod fixture;
+
+use fixture::TrackNotify;
+use fixture::Unit;
+
+#[test]
+fn tag_fits_in_handle() {
+    assert_eq!(
+        mem::size_of::<Tx<Unit>>(),
+        mem::size_of::<TaggedTx<Unit, 2>>()
+    );
+    assert_eq!(
+        mem::size_of::<Rx<Unit>>(),
+        mem::size_of::<TaggedRx<Unit, 2>>()
+    );
+}
+
+#[test]
+fn set_tag_keeps_payload() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let mut tx: TaggedTx<_, 2> = TaggedTx::from(tx);
+    let mut rx: TaggedRx<_, 2> = TaggedRx::new(rx, 3);
+    assert_eq!(0, TaggedTx::tag(&tx));
+    assert_eq!(3, TaggedRx::tag(&rx));
+    for tag in 0..4 {
+        TaggedTx::set_tag(&mut tx, tag);
+        TaggedRx::set_tag(&mut rx, 3 - tag);
+        assert_eq!(tag, TaggedTx::tag(&tx));
+        assert_eq!(3 - tag, TaggedRx::tag(&rx));
+        assert!(!tx.tx_did_drop.load(Ordering::Acquire));
+        assert!(!rx.rx_did_drop.load(Ordering::Acquire));
+    }
+}
+
+#[test]
+fn clone_keeps_tag() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let tx: TaggedTx<_, 2> = TaggedTx::new(tx, 3);
+    let tx2 = tx.clone();
+    assert_eq!(3, TaggedTx::tag(&tx2));
+    assert_eq!(2, Rx::tx_count(&rx));
+    drop(tx);
+    drop(tx2);
+    assert!(rx.tx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn into_handle_discards_tag() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let rx = TaggedRx::<_, 1>::new(rx, 1);
+    let rx = TaggedRx::into_rx(rx);
+    assert_eq!(Tx::addr(&tx), Rx::addr(&rx));
+    drop(rx);
+    assert!(tx.rx_did_drop.load(Ordering::Acquire));
+    assert_eq!(0, Tx::rx_count(&tx));
+}
+
+#[test]
+#[should_panic(expected = "tag does not fit")]
+fn tag_too_large_panics() {
+    let (tx, _rx) = splitrc::new(Unit);
+    let _ = TaggedTx::<_, 1>::new(tx, 2);
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "test-util")]
+
+use splitrc::test_util::{CountingNotify, TrackNotify, Unit};
+
+#[test]
+fn unit_displays() {
+    let (tx, rx) = splitrc::new(Unit);
+    assert_eq!("Unit", format!("{}", tx));
+    drop((tx, rx));
+}
+
+#[test]
+fn track_notify_records_first_half() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    drop(rx);
+    assert_eq!((false, true), tx.access());
+}
+
+// With abort-on-notify-panic, this would abort the test process.
+#[cfg(not(feature = "abort-on-notify-panic"))]
+#[test]
+fn panicking_notify_unwinds_from_selected_half() {
+    let (tx, rx) = splitrc::new(splitrc::test_util::PanickingNotify {
+        panic_on_tx_drop: true,
+        panic_on_rx_drop: false,
+    });
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || drop(tx)));
+    assert!(result.is_err());
+    drop(rx);
+
+    let (tx, rx) = splitrc::new(splitrc::test_util::PanickingNotify {
+        panic_on_tx_drop: true,
+        panic_on_rx_drop: false,
+    });
+    drop(rx);
+    drop(tx);
+}
+
+#[test]
+fn counting_notify_shares_counts_across_allocations() {
+    let counts = CountingNotify::new();
+    let (tx, rx) = splitrc::new(counts.clone());
+    let (tx2, rx2) = splitrc::new(counts.clone());
+    drop(rx);
+    drop(tx2);
+    assert_eq!(
+        (1, 1, 0),
+        (counts.tx_drops(), counts.rx_drops(), counts.deallocations())
+    );
+    drop((tx, rx2));
+    assert_eq!(2, counts.deallocations());
+}
@@ -4,6 +4,7 @@ use std::panic;
 use std::pin::Pin;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::sync::Barrier;
@@ -58,8 +59,9 @@ fn display_formatting() {
     assert_eq!("Unit", format!("{}", rx));
 }
 
+// Every clone is leaked, which Miri reports.
 #[test]
-#[ignore]
+#[cfg_attr(any(miri, not(feature = "compact-counts")), ignore)]
 fn tx_panic_on_overflow() {
     let (tx, rx) = splitrc::new(Unit);
     drop(rx);
@@ -70,8 +72,9 @@ fn tx_panic_on_overflow() {
     assert!(result.is_err());
 }
 
+// Every clone is leaked, which Miri reports.
 #[test]
-#[ignore]
+#[cfg_attr(any(miri, not(feature = "compact-counts")), ignore)]
 fn rx_panic_on_overflow() {
     let (tx, rx) = splitrc::new(Unit);
     drop(tx);
@@ -82,6 +85,64 @@ fn rx_panic_on_overflow() {
     assert!(result.is_err());
 }
 
+// The counts are the whole header without the features that add
+// fields to it.
+#[cfg(all(
+    target_pointer_width = "64",
+    not(any(
+        feature = "async",
+        feature = "event-listener",
+        feature = "drop-callbacks",
+        feature = "leak-track",
+        feature = "metrics",
+        feature = "revive"
+    ))
+))]
+#[test]
+fn header_size() {
+    use allocator_api2::alloc::{AllocError, Allocator, Global};
+    use std::alloc::Layout;
+    use std::ptr::NonNull;
+
+    static SIZE: AtomicUsize = AtomicUsize::new(0);
+
+    // Records the size of the last allocation.
+    struct Recording;
+
+    unsafe impl Allocator for Recording {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            SIZE.store(layout.size(), Ordering::Relaxed);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    let (tx, rx) = splitrc::new_in((), Recording);
+    let expected = if cfg!(feature = "compact-counts") {
+        8
+    } else {
+        16
+    };
+    assert_eq!(expected, SIZE.load(Ordering::Relaxed));
+    drop((tx, rx));
+}
+
+#[cfg(feature = "compact-counts")]
+#[test]
+#[cfg_attr(miri, ignore)]
+fn compact_overflow_leaves_other_half_intact() {
+    let (tx, rx) = splitrc::new(Unit);
+    let result = panic::catch_unwind(|| loop {
+        mem::forget(rx.clone())
+    });
+    assert!(result.is_err());
+    assert_eq!(1, splitrc::Tx::tx_count(&tx));
+    assert_eq!(1 << 14, splitrc::Tx::rx_count(&tx));
+}
+
 #[test]
 fn pointers_are_unpinned() {
     let (tx, rx) = splitrc::new(Unit);
@@ -128,18 +189,39 @@ fn alloc_pinned() {
 fn drop_tx_pinned() {
     let (tx, rx): (Pin<splitrc::Tx<MustPin>>, Pin<splitrc::Rx<MustPin>>) =
         splitrc::pin(Default::default());
-    assert_eq!(false, tx.tx_did_drop.load(Ordering::Acquire));
+    assert!(!tx.tx_did_drop.load(Ordering::Acquire));
     drop(tx);
-    assert_eq!(true, rx.tx_did_drop.load(Ordering::Acquire));
+    assert!(rx.tx_did_drop.load(Ordering::Acquire));
 }
 
 #[test]
 fn drop_rx_pinned() {
     let (tx, rx): (Pin<splitrc::Tx<MustPin>>, Pin<splitrc::Rx<MustPin>>) =
         splitrc::pin(Default::default());
-    assert_eq!(false, rx.rx_did_drop.load(Ordering::Acquire));
+    assert!(!rx.rx_did_drop.load(Ordering::Acquire));
+    drop(rx);
+    assert!(tx.rx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn pinned_weak_upgrades_pinned() {
+    let (tx, rx): (Pin<splitrc::Tx<MustPin>>, Pin<splitrc::Rx<MustPin>>) =
+        splitrc::pin(Default::default());
+    let tx_weak = splitrc::Tx::downgrade_pinned(&tx);
+    let rx_weak = splitrc::Rx::downgrade_pinned(&rx).clone();
+    assert_eq!(rx_weak.id(), tx_weak.id());
+
+    let tx2: Pin<splitrc::Tx<MustPin>> = tx_weak.upgrade().unwrap();
+    drop(tx);
+    assert!(!rx.tx_did_drop.load(Ordering::Acquire));
+    drop(tx2);
+    assert!(rx.tx_did_drop.load(Ordering::Acquire));
+    assert!(tx_weak.upgrade().is_none());
+
+    let rx2: Pin<splitrc::Rx<MustPin>> = rx_weak.upgrade().unwrap();
     drop(rx);
-    assert_eq!(true, tx.rx_did_drop.load(Ordering::Acquire));
+    drop(rx2);
+    assert!(rx_weak.upgrade().is_none());
 }
 
 struct Count<'a> {
@@ -219,3 +301,1224 @@ fn stress_dealloc() {
     let final_count = count.load(Ordering::Acquire);
     assert_eq!(6 * T, final_count);
 }
+
+#[test]
+fn weak_upgrade() {
+    let (tx, rx) = splitrc::new(Unit);
+    let tx_weak = splitrc::Tx::downgrade(&tx);
+    let rx_weak = splitrc::Rx::downgrade(&rx);
+    assert!(tx_weak.upgrade().is_some());
+    assert!(rx_weak.upgrade().is_some());
+}
+
+#[test]
+fn weak_upgrade_fails_after_half_drops() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let tx_weak = splitrc::Tx::downgrade(&tx);
+    let rx_weak = splitrc::Rx::downgrade(&rx);
+    drop(tx);
+    assert!(tx_weak.upgrade().is_none());
+    let rx2 = rx_weak.upgrade().unwrap();
+    drop(rx);
+    assert!(rx2.tx_did_drop.load(Ordering::Acquire));
+    assert!(!rx2.rx_did_drop.load(Ordering::Acquire));
+    drop(rx2);
+    assert!(rx_weak.upgrade().is_none());
+}
+
+#[derive(Debug)]
+struct DropFlag<'a> {
+    dropped: &'a AtomicBool,
+}
+
+impl splitrc::Notify for DropFlag<'_> {}
+
+impl Drop for DropFlag<'_> {
+    fn drop(&mut self) {
+        self.dropped.store(true, Ordering::Release);
+    }
+}
+
+#[test]
+fn weak_does_not_keep_data_alive() {
+    let dropped = AtomicBool::new(false);
+    let (tx, rx) = splitrc::new(DropFlag { dropped: &dropped });
+    let tx_weak = splitrc::Tx::downgrade(&tx);
+    let rx_weak = splitrc::Rx::downgrade(&rx);
+    drop(tx);
+    drop(rx);
+    assert!(dropped.load(Ordering::Acquire));
+    assert!(tx_weak.clone().upgrade().is_none());
+    assert!(rx_weak.upgrade().is_none());
+}
+
+trait Tracked: splitrc::Notify {
+    fn access(&self) -> (bool, bool);
+}
+
+impl Tracked for TrackNotify {
+    fn access(&self) -> (bool, bool) {
+        TrackNotify::access(self)
+    }
+}
+
+#[test]
+fn unsize_to_trait_object() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let tx: splitrc::Tx<dyn Tracked> =
+        unsafe { splitrc::Tx::unsize(tx, |p| p as *const dyn Tracked) };
+    let rx: splitrc::Rx<dyn Tracked> =
+        unsafe { splitrc::Rx::unsize(rx, |p| p as *const dyn Tracked) };
+    assert_eq!((false, false), tx.access());
+    drop(tx);
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn unsize_to_slice() {
+    let (tx, rx) = splitrc::new([1u8, 2, 3]);
+    let tx: splitrc::Tx<[u8]> = unsafe { splitrc::Tx::unsize(tx, |p| p as *const [u8]) };
+    let rx: splitrc::Rx<[u8]> = unsafe { splitrc::Rx::unsize(rx, |p| p as *const [u8]) };
+    assert_eq!(&[1, 2, 3], &*tx);
+    let rx_weak = splitrc::Rx::downgrade(&rx);
+    drop(tx);
+    assert_eq!(&[1, 2, 3], &*rx_weak.upgrade().unwrap());
+}
+
+struct Cyclic {
+    tx: splitrc::TxWeak<Cyclic>,
+    rx: splitrc::RxWeak<Cyclic>,
+    upgraded_during_construction: bool,
+}
+
+impl splitrc::Notify for Cyclic {}
+
+#[test]
+fn new_cyclic() {
+    let (tx, rx) = splitrc::new_cyclic(|tx: &splitrc::TxWeak<Cyclic>, rx| Cyclic {
+        tx: tx.clone(),
+        rx: rx.clone(),
+        upgraded_during_construction: tx.upgrade().is_some() || rx.upgrade().is_some(),
+    });
+    assert!(!tx.upgraded_during_construction);
+    assert!(rx.tx.upgrade().is_some());
+    assert!(tx.rx.upgrade().is_some());
+}
+
+#[test]
+fn new_cyclic_panic() {
+    let result = panic::catch_unwind(|| {
+        splitrc::new_cyclic(|_: &splitrc::TxWeak<Unit>, _| panic!("constructor failed"))
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn ptr_eq() {
+    let (tx1, rx1) = splitrc::new(Unit);
+    let (tx2, rx2) = splitrc::new(Unit);
+    assert!(splitrc::Tx::ptr_eq(&tx1, &tx1.clone()));
+    assert!(!splitrc::Tx::ptr_eq(&tx1, &tx2));
+    assert!(splitrc::Rx::ptr_eq(&rx1, &rx1.clone()));
+    assert!(!splitrc::Rx::ptr_eq(&rx1, &rx2));
+    assert!(splitrc::same_allocation(&tx1, &rx1));
+    assert!(!splitrc::same_allocation(&tx1, &rx2));
+}
+
+#[test]
+fn ptr_eq_ignores_metadata() {
+    let (tx, rx) = splitrc::new([1u8, 2, 3]);
+    let tx2 = tx.clone();
+    let tx2: splitrc::Tx<[u8]> = unsafe { splitrc::Tx::unsize(tx2, |p| p as *const [u8]) };
+    let rx: splitrc::Rx<[u8]> = unsafe { splitrc::Rx::unsize(rx, |p| p as *const [u8]) };
+    assert!(splitrc::same_allocation(&tx2, &rx));
+    drop(tx);
+}
+
+#[test]
+fn into_rx_notifies_last_tx() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let rx2 = splitrc::Tx::into_rx(tx).unwrap();
+    assert_eq!((true, false), rx.access());
+    drop(rx2);
+}
+
+#[test]
+fn into_rx_fails_after_rx_closed() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    drop(rx);
+    let tx = splitrc::Tx::into_rx(tx).unwrap_err();
+    assert_eq!((false, true), tx.access());
+}
+
+#[test]
+fn into_tx_notifies_last_rx() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let tx2 = splitrc::Rx::into_tx(rx).unwrap();
+    assert_eq!((false, true), tx.access());
+    drop(tx);
+    assert_eq!((false, true), tx2.access());
+}
+
+#[test]
+fn into_rx_with_remaining_tx_does_not_notify() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let rx2 = splitrc::Tx::into_rx(tx.clone()).unwrap();
+    assert_eq!((false, false), tx.access());
+    drop(rx);
+    drop(rx2);
+    assert_eq!((false, true), tx.access());
+}
+
+#[test]
+fn into_rx_deallocates_once() {
+    let dropped = AtomicBool::new(false);
+    let (tx, rx) = splitrc::new(DropFlag { dropped: &dropped });
+    let rx2 = splitrc::Tx::into_rx(tx).unwrap();
+    drop(rx);
+    assert!(!dropped.load(Ordering::Acquire));
+    drop(rx2);
+    assert!(dropped.load(Ordering::Acquire));
+}
+
+#[test]
+fn try_new() {
+    let (tx, rx) = splitrc::try_new(TrackNotify::default()).unwrap();
+    drop(tx);
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn try_pin() {
+    let (tx, rx) = splitrc::try_pin(TrackNotify::default()).unwrap();
+    drop(rx);
+    assert_eq!((false, true), tx.access());
+}
+
+#[test]
+fn alloc_error_display() {
+    assert_eq!("memory allocation failed", splitrc::AllocError.to_string());
+}
+
+#[test]
+fn counts() {
+    let (tx, rx) = splitrc::new(Unit);
+    assert_eq!(splitrc::Counts { tx: 1, rx: 1 }, splitrc::Tx::counts(&tx));
+    let tx2 = tx.clone();
+    let rx2 = rx.clone();
+    let rx3 = rx.clone();
+    assert_eq!(2, splitrc::Tx::tx_count(&tx));
+    assert_eq!(3, splitrc::Tx::rx_count(&tx));
+    assert_eq!(splitrc::Counts { tx: 2, rx: 3 }, splitrc::Rx::counts(&rx));
+    drop(tx2);
+    drop(rx2);
+    assert_eq!(1, splitrc::Rx::tx_count(&rx3));
+    assert_eq!(2, splitrc::Rx::rx_count(&rx3));
+}
+
+#[test]
+fn raw_round_trip() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let tx_ptr = splitrc::Tx::into_raw(tx);
+    let rx_ptr = splitrc::Rx::into_raw(rx);
+    assert_eq!(tx_ptr, rx_ptr);
+    let tx = unsafe { splitrc::Tx::from_raw(tx_ptr) };
+    let rx = unsafe { splitrc::Rx::from_raw(rx_ptr) };
+    assert_eq!(splitrc::Counts { tx: 1, rx: 1 }, splitrc::Tx::counts(&tx));
+    drop(tx);
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn raw_increment_and_decrement() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let ptr = splitrc::Tx::into_raw(tx);
+    unsafe { splitrc::Tx::increment_tx_count(ptr) };
+    assert_eq!(2, splitrc::Rx::tx_count(&rx));
+    unsafe { splitrc::Tx::decrement_tx_count(ptr) };
+    assert_eq!((false, false), rx.access());
+    unsafe { splitrc::Tx::decrement_tx_count(ptr) };
+    assert_eq!((true, false), rx.access());
+
+    let ptr = splitrc::Rx::into_raw(rx);
+    unsafe { splitrc::Rx::increment_rx_count(ptr) };
+    unsafe { splitrc::Rx::decrement_rx_count(ptr) };
+    unsafe { splitrc::Rx::decrement_rx_count(ptr) };
+}
+
+#[test]
+fn as_ptr_points_at_payload() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let ptr = splitrc::Tx::as_ptr(&tx);
+    assert_eq!(ptr, splitrc::Rx::as_ptr(&rx));
+    assert_eq!(ptr, &*tx as *const TrackNotify);
+    assert_eq!(ptr, splitrc::Tx::into_raw(tx));
+    // Reading through the pointer and reclaiming the handle from it
+    // both rely on its provenance.
+    assert_eq!((false, false), unsafe { &*ptr }.access());
+    drop(unsafe { splitrc::Tx::from_raw(ptr) });
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn unnotified() {
+    let (tx, rx) = splitrc::new(splitrc::Unnotified(String::from("hello")));
+    assert_eq!(5, tx.len());
+    drop(tx);
+    assert_eq!("hello", rx.as_str());
+}
+
+#[test]
+fn callbacks() {
+    let tx_dropped = AtomicBool::new(false);
+    let rx_dropped = AtomicBool::new(false);
+    let (tx, rx) = splitrc::new_with_callbacks(
+        7,
+        |v| {
+            assert_eq!(7, *v);
+            tx_dropped.store(true, Ordering::Release);
+        },
+        |_| rx_dropped.store(true, Ordering::Release),
+    );
+    assert_eq!(7, **tx);
+    drop(tx);
+    assert!(tx_dropped.load(Ordering::Acquire));
+    // Calling the callback again directly is a no-op.
+    splitrc::Notify::last_tx_did_drop(&*rx);
+    drop(rx);
+    assert!(!rx_dropped.load(Ordering::Acquire));
+}
+
+#[test]
+fn map_projects_field() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let tx = splitrc::Tx::map(tx, |t| &t.rx_did_drop);
+    let rx = splitrc::Rx::map(rx, |t| &t.tx_did_drop);
+    let rx2 = rx.clone();
+    drop(rx);
+    drop(rx2);
+    assert!(tx.load(Ordering::Acquire));
+    assert_eq!(1, splitrc::Tx::tx_count(splitrc::MappedTx::handle(&tx)));
+    let tx = splitrc::MappedTx::into_handle(tx);
+    assert!(!tx.tx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn map_chains() {
+    let (tx, rx) = splitrc::new(splitrc::Unnotified((1u32, String::from("hello"))));
+    let rx = splitrc::Rx::map(rx, |t| &t.1);
+    let rx = splitrc::MappedRx::map(rx, |s| s.as_str());
+    drop(tx);
+    assert_eq!("hello", &*rx);
+    assert_eq!("\"hello\"", format!("{:?}", rx));
+}
+
+#[test]
+fn map_pinned_projects_pinned_field() {
+    let (tx, rx) = splitrc::pin((MustPin::default(), MustPin::default()));
+    // SAFETY: Tuple elements are structurally pinned.
+    let rx = splitrc::Rx::map_pinned(rx, |t| unsafe { t.map_unchecked(|t| &t.1) });
+    let rx2 = rx.clone();
+    drop(rx);
+    drop(tx);
+    // Tuples forward notifications to each pinned element.
+    assert!(rx2.tx_did_drop.load(Ordering::Acquire));
+    splitrc::PinnedMappedRx::as_pin(&rx2).drop_rx();
+    let rx = splitrc::PinnedMappedRx::into_handle(rx2);
+    assert!(!rx.0.rx_did_drop.load(Ordering::Acquire));
+    assert!(rx.1.rx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn erase() {
+    let (tx1, rx1) = splitrc::new(TrackNotify::default());
+    let (tx2, rx2) = splitrc::new(Unit);
+    let erased_rx1 = splitrc::Rx::erase(rx1);
+    let guards = vec![erased_rx1.clone(), erased_rx1, splitrc::Rx::erase(rx2)];
+    assert!(splitrc::ErasedRx::ptr_eq(&guards[0], &guards[1]));
+    assert!(!splitrc::ErasedRx::ptr_eq(&guards[0], &guards[2]));
+    assert_eq!(2, splitrc::Tx::rx_count(&tx1));
+    drop(guards);
+    assert!(tx1.rx_did_drop.load(Ordering::Acquire));
+    drop(tx2);
+
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    drop(splitrc::Tx::erase(tx));
+    assert!(rx.tx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn downcast() {
+    type AnyHandle = dyn splitrc::AnyNotify + Send + Sync;
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let tx: splitrc::Tx<AnyHandle> = unsafe { splitrc::Tx::unsize(tx, |p| p as *const AnyHandle) };
+    let rx: splitrc::Rx<AnyHandle> = unsafe { splitrc::Rx::unsize(rx, |p| p as *const AnyHandle) };
+    let tx = splitrc::Tx::downcast::<Unit>(tx).unwrap_err();
+    let tx = splitrc::Tx::downcast::<TrackNotify>(tx).unwrap();
+    // Notifications still reach the concrete payload while erased.
+    drop(rx);
+    assert_eq!((false, true), tx.access());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn channel_notify() {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let (tx1, rx1) = splitrc::new(splitrc::ChannelNotify::with_key((), 1, sender.clone()));
+    let (tx2, rx2) = splitrc::new(splitrc::ChannelNotify::with_key((), 2, sender));
+    assert_eq!(2, *tx2.key());
+    drop(rx2);
+    drop(tx1);
+    assert_eq!(splitrc::DropEvent::RxGone(2), receiver.recv().unwrap());
+    assert_eq!(splitrc::DropEvent::TxGone(1), receiver.recv().unwrap());
+    drop(rx1);
+    drop(tx2);
+    assert!(receiver.try_recv().is_err());
+
+    let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+    let (tx, rx) = splitrc::new(splitrc::ChannelNotify::new("data", sender));
+    assert_eq!("data", **rx);
+    drop(tx);
+    assert_eq!(splitrc::DropEvent::TxGone(()), receiver.recv().unwrap());
+}
+
+#[test]
+fn new_deferred() {
+    let (sender, queue) = std::sync::mpsc::channel::<splitrc::NotifyJob>();
+    let sender = std::sync::Mutex::new(sender);
+    let (tx, rx) = splitrc::new_deferred(
+        TrackNotify::default(),
+        Arc::new(move |job| sender.lock().unwrap().send(job).unwrap()),
+    );
+    drop(tx);
+    // Nothing runs until the job does, and the job keeps data alive.
+    assert_eq!((false, false), rx.access());
+    let job = queue.recv().unwrap();
+    assert_eq!(2, splitrc::Rx::rx_count(&rx));
+    job();
+    assert_eq!((true, false), rx.access());
+    assert_eq!(1, splitrc::Rx::rx_count(&rx));
+    // With no Tx left to hold, the last notification runs inline.
+    drop(rx);
+    assert!(queue.try_recv().is_err());
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Key(u32);
+
+impl splitrc::Notify for Key {}
+
+#[test]
+fn comparison_delegates_to_payload() {
+    let (tx1, rx1) = splitrc::new(Key(1));
+    let (tx2, rx2) = splitrc::new(Key(2));
+    assert_eq!(tx1, tx1.clone());
+    assert_ne!(tx1, tx2);
+    assert_eq!(tx1, rx1);
+    assert_eq!(rx2, tx2);
+    assert!(tx1 < tx2);
+    assert_eq!(std::cmp::Ordering::Greater, rx2.cmp(&rx1));
+
+    let mut set = std::collections::HashSet::new();
+    assert!(set.insert(rx1.clone()));
+    assert!(!set.insert(rx1));
+    // Borrow<T> lets the payload be used for lookup.
+    assert!(set.contains(&Key(1)));
+    assert!(!set.contains(&Key(2)));
+}
+
+#[test]
+fn pointer_identity() {
+    let (tx, rx) = splitrc::new(Key(1));
+    let (tx2, _rx2) = splitrc::new(Key(1));
+    assert_eq!(splitrc::Tx::addr(&tx), splitrc::Rx::addr(&rx));
+    assert_ne!(splitrc::Tx::addr(&tx), splitrc::Tx::addr(&tx2));
+    assert_eq!(format!("{:p}", &*tx), format!("{:p}", tx));
+    assert_eq!(format!("{:p}", tx), format!("{:p}", rx));
+
+    // Equal payloads are distinct by address.
+    assert_eq!(tx, tx2);
+    assert_ne!(splitrc::ByAddress(tx.clone()), splitrc::ByAddress(tx2));
+    assert_eq!(splitrc::ByAddress(tx.clone()), splitrc::ByAddress(tx));
+}
+
+#[test]
+fn ids_identify_allocations() {
+    let (tx, rx) = splitrc::new(Key(1));
+    let (tx2, _rx2) = splitrc::new(Key(1));
+    let id = splitrc::Tx::id(&tx);
+    assert_eq!(id, splitrc::Rx::id(&rx));
+    assert_eq!(id, splitrc::Tx::downgrade(&tx).id());
+    assert_eq!(id, splitrc::Rx::downgrade(&rx).id());
+    assert_eq!(splitrc::Tx::addr(&tx), id.addr());
+    assert_ne!(id, splitrc::Tx::id(&tx2));
+
+    let mut ids = std::collections::BTreeSet::new();
+    ids.insert(id);
+    assert!(ids.contains(&splitrc::Rx::id(&rx)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn error_passthrough() {
+    use std::error::Error;
+
+    #[derive(Debug)]
+    struct Wrapped(std::fmt::Error);
+
+    impl std::fmt::Display for Wrapped {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("wrapped")
+        }
+    }
+
+    impl Error for Wrapped {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    impl splitrc::Notify for Wrapped {}
+
+    let (tx, rx) = splitrc::new(Wrapped(std::fmt::Error));
+    let err: Box<dyn Error + Send + Sync> = Box::new(tx);
+    assert_eq!("wrapped", err.to_string());
+    assert!(err.source().unwrap().is::<std::fmt::Error>());
+    drop(rx);
+
+    let alloc = CountingAlloc::default();
+    let (tx, rx) = splitrc::new_in(Wrapped(std::fmt::Error), &alloc);
+    let err: &dyn Error = &rx;
+    assert_eq!("wrapped", err.to_string());
+    assert!(err.source().unwrap().is::<std::fmt::Error>());
+    drop(tx);
+}
+
+#[test]
+fn scope_notifies_and_drops() {
+    let dropped = AtomicBool::new(false);
+    let result = splitrc::scope(DropFlag { dropped: &dropped }, |tx, rx| {
+        let rx2 = rx.clone();
+        std::thread::scope(|s| {
+            s.spawn(move || drop(tx));
+        });
+        assert_eq!(2, splitrc::ScopedRx::counts(&rx).rx);
+        drop(rx2);
+        assert!(!dropped.load(Ordering::Acquire));
+        drop(rx);
+        assert!(dropped.load(Ordering::Acquire));
+        7
+    });
+    assert_eq!(7, result);
+
+    let result = splitrc::scope(TrackNotify::default(), |tx, rx| {
+        drop(tx);
+        rx.access()
+    });
+    assert_eq!((true, false), result);
+}
+
+#[test]
+fn scope_drops_leaked_data() {
+    let dropped = AtomicBool::new(false);
+    splitrc::scope(DropFlag { dropped: &dropped }, |tx, rx| {
+        mem::forget(tx);
+        mem::forget(rx);
+    });
+    assert!(dropped.load(Ordering::Acquire));
+}
+
+struct Large {
+    header: u32,
+    bytes: [u8; 1 << 16],
+}
+
+impl splitrc::Notify for Large {}
+
+#[test]
+fn new_with_initializes_in_place() {
+    let (tx, rx) = unsafe {
+        splitrc::new_with(|slot: &mut mem::MaybeUninit<Large>| {
+            let p = slot.as_mut_ptr();
+            std::ptr::addr_of_mut!((*p).header).write(7);
+            std::ptr::addr_of_mut!((*p).bytes).write_bytes(1, 1);
+        })
+    };
+    assert_eq!(7, tx.header);
+    assert!(rx.bytes.iter().all(|&b| b == 1));
+}
+
+#[test]
+fn new_with_panic_frees() {
+    let result = panic::catch_unwind(|| unsafe {
+        splitrc::new_with(|_: &mut mem::MaybeUninit<Large>| panic!("init failed"))
+    });
+    assert!(result.is_err());
+}
+
+#[derive(Default)]
+struct CountingAlloc {
+    live: AtomicUsize,
+}
+
+unsafe impl allocator_api2::alloc::Allocator for CountingAlloc {
+    fn allocate(
+        &self,
+        layout: std::alloc::Layout,
+    ) -> Result<std::ptr::NonNull<[u8]>, allocator_api2::alloc::AllocError> {
+        self.live.fetch_add(1, Ordering::Relaxed);
+        allocator_api2::alloc::Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: std::ptr::NonNull<u8>, layout: std::alloc::Layout) {
+        self.live.fetch_sub(1, Ordering::Relaxed);
+        allocator_api2::alloc::Global.deallocate(ptr, layout)
+    }
+}
+
+#[test]
+fn new_in_frees_with_allocator() {
+    let alloc = CountingAlloc::default();
+    let (tx, rx) = splitrc::new_in(TrackNotify::default(), &alloc);
+    assert_eq!(1, alloc.live.load(Ordering::Relaxed));
+    let tx2 = tx.clone();
+    drop(tx);
+    drop(tx2);
+    assert_eq!((true, false), rx.access());
+    assert_eq!(1, splitrc::Rx::allocator(&rx).live.load(Ordering::Relaxed));
+    drop(rx);
+    assert_eq!(0, alloc.live.load(Ordering::Relaxed));
+}
+
+#[test]
+fn comparison_with_allocator() {
+    let alloc = CountingAlloc::default();
+    let (tx1, rx1) = splitrc::new_in(Key(1), &alloc);
+    let (tx2, rx2) = splitrc::new_in(Key(2), &alloc);
+    assert_eq!(tx1, rx1);
+    assert!(tx1 < tx2);
+    assert_eq!(std::cmp::Ordering::Less, rx1.cmp(&rx2));
+
+    let mut set = std::collections::HashSet::new();
+    assert!(set.insert(tx1.clone()));
+    assert!(!set.insert(tx1));
+}
+
+#[test]
+fn format_pointer_with_allocator() {
+    let alloc = CountingAlloc::default();
+    let (tx, rx) = splitrc::new_in(Key(1), &alloc);
+    assert_eq!(format!("{:p}", &*tx), format!("{:p}", tx));
+    assert_eq!(format!("{:p}", tx), format!("{:p}", rx));
+}
+
+#[test]
+fn new_padded_aligns_payload() {
+    let (tx, rx) = splitrc::new_padded(TrackNotify::default());
+    assert!(mem::align_of::<splitrc::CachePadded<TrackNotify>>() >= 64);
+    assert_eq!(
+        0,
+        splitrc::Tx::addr(&tx) % mem::align_of::<splitrc::CachePadded<TrackNotify>>()
+    );
+    drop(tx);
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn unique_mutates_then_splits() {
+    let mut unique = splitrc::new_unique(TrackNotify::default());
+    let data: &mut TrackNotify = &mut unique;
+    data.rx_did_drop.store(true, Ordering::Release);
+    let (tx, rx) = splitrc::UniqueHandle::split(unique);
+    assert_eq!((false, true), rx.access());
+    drop(tx);
+    assert_eq!((true, true), rx.access());
+}
+
+#[test]
+fn unique_drop_does_not_notify() {
+    let dropped = AtomicBool::new(false);
+    drop(splitrc::new_unique(DropFlag { dropped: &dropped }));
+    assert!(dropped.load(Ordering::Acquire));
+}
+
+#[test]
+fn get_mut_requires_unique_handle() {
+    let (mut tx, rx) = splitrc::new(TrackNotify::default());
+    assert!(splitrc::Tx::get_mut(&mut tx).is_none());
+    drop(rx);
+    let tx2 = tx.clone();
+    assert!(splitrc::Tx::get_mut(&mut tx).is_none());
+    drop(tx2);
+    let weak = splitrc::Tx::downgrade(&tx);
+    assert!(splitrc::Tx::get_mut(&mut tx).is_none());
+    drop(weak);
+    let data = splitrc::Tx::get_mut(&mut tx).unwrap();
+    assert_eq!((false, true), data.access());
+}
+
+#[test]
+fn rx_get_mut() {
+    let (tx, mut rx) = splitrc::new(TrackNotify::default());
+    assert!(splitrc::Rx::get_mut(&mut rx).is_none());
+    drop(tx);
+    let data = splitrc::Rx::get_mut(&mut rx).unwrap();
+    data.tx_did_drop.store(false, Ordering::Release);
+    assert_eq!((false, false), rx.access());
+}
+
+#[test]
+fn try_unwrap_last_handle() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let tx = splitrc::Tx::try_unwrap(tx).unwrap_err();
+    drop(rx);
+    let weak = splitrc::Tx::downgrade(&tx);
+    let data = splitrc::Tx::try_unwrap(tx).unwrap();
+    assert_eq!((false, true), data.access());
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn into_inner_returns_payload_to_last_handle() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let rx2 = rx.clone();
+    assert!(splitrc::Rx::into_inner(rx).is_none());
+    assert!(splitrc::Tx::into_inner(tx).is_none());
+    let data = splitrc::Rx::into_inner(rx2).unwrap();
+    assert_eq!((true, false), data.access());
+}
+
+#[derive(Clone, Default)]
+struct Versioned {
+    version: u32,
+    notified: Arc<AtomicU64>,
+}
+
+impl splitrc::Notify for Versioned {
+    fn last_tx_did_drop(&self) {
+        self.notified.fetch_add(1, Ordering::AcqRel);
+    }
+    fn last_rx_did_drop(&self) {
+        self.notified.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+#[test]
+fn make_mut_clones_when_shared() {
+    let (mut tx, rx) = splitrc::new(Versioned::default());
+    splitrc::Tx::make_mut(&mut tx).version = 1;
+    assert_eq!(0, rx.version);
+    assert_eq!(1, tx.version);
+    // The original lost its only Tx.
+    assert_eq!(1, rx.notified.load(Ordering::Acquire));
+    let copy = splitrc::Tx::addr(&tx);
+    splitrc::Tx::make_mut(&mut tx).version = 2;
+    assert_eq!(copy, splitrc::Tx::addr(&tx));
+    assert_eq!(2, tx.version);
+    let notified = rx.notified.clone();
+    drop(rx);
+    drop(tx);
+    // The copy's missing Rx half never notifies.
+    assert_eq!(1, notified.load(Ordering::Acquire));
+}
+
+#[test]
+fn rx_make_mut_in_place_when_unique() {
+    let (tx, mut rx) = splitrc::new(Versioned::default());
+    drop(tx);
+    let addr = splitrc::Rx::addr(&rx);
+    splitrc::Rx::make_mut(&mut rx).version = 3;
+    assert_eq!(addr, splitrc::Rx::addr(&rx));
+    assert_eq!(1, rx.notified.load(Ordering::Acquire));
+}
+
+#[test]
+fn pair_names_halves() {
+    let splitrc::Pair { tx, rx } = splitrc::new_pair(TrackNotify::default());
+    drop(tx);
+    assert_eq!((true, false), rx.access());
+
+    let pair = splitrc::Pair::pinned(TrackNotify::default());
+    let (tx, rx) = pair.map(|tx| tx, |rx| rx);
+    drop(rx);
+    assert_eq!((false, true), tx.access());
+}
+
+#[test]
+fn txrx_counts_as_both() {
+    let both = splitrc::TxRx::new(TrackNotify::default());
+    let both2 = both.clone();
+    assert_eq!(
+        splitrc::Counts { tx: 2, rx: 2 },
+        splitrc::TxRx::counts(&both)
+    );
+    drop(both2);
+    let (tx, rx) = splitrc::TxRx::split(both);
+    let both = splitrc::TxRx::join((tx, rx)).unwrap();
+    let rx = splitrc::TxRx::rx(&both);
+    drop(both);
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn txrx_join_rejects_mismatch() {
+    let (tx1, _rx1) = splitrc::new(Unit);
+    let (_tx2, rx2) = splitrc::new(Unit);
+    assert!(splitrc::TxRx::join((tx1, rx2)).is_err());
+}
+
+#[test]
+fn borrowed_handles_do_not_count() {
+    fn leaf(rx: splitrc::RxBorrow<'_, TrackNotify>) -> splitrc::Rx<TrackNotify> {
+        splitrc::RxBorrow::to_rx(rx)
+    }
+
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let borrowed = splitrc::Tx::borrow_handle(&tx);
+    let copy = borrowed;
+    assert_eq!(splitrc::Counts { tx: 1, rx: 1 }, splitrc::Tx::counts(&tx));
+    assert_eq!((false, false), copy.access());
+    let tx2 = splitrc::TxBorrow::to_tx(borrowed);
+    assert_eq!(2, splitrc::Tx::tx_count(&tx2));
+    drop((tx, tx2));
+
+    let rx2 = leaf(splitrc::Rx::borrow_handle(&rx));
+    drop(rx);
+    assert_eq!((true, false), rx2.access());
+}
+
+#[test]
+fn header_slice_holds_body_inline() {
+    let body = vec![String::from("a"), String::from("b")];
+    let (tx, rx) = splitrc::from_header_and_slice(TrackNotify::default(), &body);
+    assert_eq!(body, tx.slice);
+    drop(tx);
+    assert_eq!((true, false), rx.header.access());
+}
+
+#[test]
+fn header_slice_iter_panic_frees() {
+    let dropped = AtomicBool::new(false);
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        let items = (0..3).map(|i| {
+            if i == 2 {
+                panic!("item failed");
+            }
+            String::from("x")
+        });
+        splitrc::from_header_and_iter(DropFlag { dropped: &dropped }, items)
+    }));
+    assert!(result.is_err());
+    assert!(dropped.load(Ordering::Acquire));
+}
+
+#[test]
+fn str_and_slice_payloads() {
+    let (tx, rx) = splitrc::from_str("split");
+    assert_eq!("split", &*tx);
+    assert_eq!(5, rx.len());
+    drop((tx, rx));
+
+    let strings = [String::from("a"), String::from("bc")];
+    let (tx, rx) = splitrc::from_slice(&strings);
+    assert_eq!(strings, *rx);
+    drop((tx, rx));
+
+    let (tx, rx) = splitrc::from_iter(std::iter::empty::<u64>());
+    assert!(tx.is_empty());
+    drop((tx, rx));
+}
+
+#[test]
+#[cfg(not(any(loom, shuttle)))]
+fn static_split_rc_never_notifies() {
+    static STATIC: splitrc::StaticSplitRc<TrackNotify> = splitrc::StaticSplitRc::new(TrackNotify {
+        tx_did_drop: AtomicBool::new(false),
+        rx_did_drop: AtomicBool::new(false),
+    });
+    let (tx, rx) = STATIC.handles();
+    assert_eq!(splitrc::Counts { tx: 2, rx: 2 }, splitrc::Tx::counts(&tx));
+    drop(tx);
+    let (tx, rx2) = STATIC.handles();
+    drop((rx, rx2));
+    assert_eq!((false, false), tx.access());
+    assert!(splitrc::Tx::try_unwrap(tx).is_err());
+}
+
+#[test]
+fn new_in_place_releases_storage() {
+    type Storage = splitrc::SplitStorage<TrackNotify>;
+    static RELEASED: std::sync::Mutex<Option<Pin<&'static mut Storage>>> =
+        std::sync::Mutex::new(None);
+    fn release(storage: Pin<&'static mut Storage>) {
+        *RELEASED.lock().unwrap() = Some(storage);
+    }
+
+    let storage = Pin::static_mut(Box::leak(Box::new(Storage::new())));
+    let (tx, rx) = splitrc::new_in_place(storage, TrackNotify::default(), release);
+    let tx2 = tx.clone();
+    drop(tx);
+    drop(tx2);
+    assert!(rx.tx_did_drop.load(Ordering::Acquire));
+    assert!(RELEASED.lock().unwrap().is_none());
+    drop(rx);
+
+    // The storage comes back and can be reused.
+    let storage = RELEASED.lock().unwrap().take().unwrap();
+    let (tx, rx) = splitrc::new_in_place(storage, TrackNotify::default(), release);
+    drop(rx);
+    assert!(tx.rx_did_drop.load(Ordering::Acquire));
+    drop(tx);
+    assert!(RELEASED.lock().unwrap().is_some());
+}
+
+#[derive(Default)]
+struct Counters {
+    notified: AtomicUsize,
+    deallocated: AtomicUsize,
+}
+
+struct CountDealloc<'a>(&'a Counters);
+
+impl splitrc::Notify for CountDealloc<'_> {
+    fn last_tx_did_drop(&self) {
+        self.0.notified.fetch_add(1, Ordering::Relaxed);
+    }
+    fn last_rx_did_drop(&self) {
+        self.0.notified.fetch_add(1, Ordering::Relaxed);
+    }
+    fn will_deallocate(&self) {
+        self.0.deallocated.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn will_deallocate_in_either_order() {
+    let counters = Counters::default();
+    let (tx, rx) = splitrc::new(CountDealloc(&counters));
+    drop(tx);
+    assert_eq!(0, counters.deallocated.load(Ordering::Relaxed));
+    drop(rx);
+    assert_eq!(1, counters.deallocated.load(Ordering::Relaxed));
+
+    let (tx, rx) = splitrc::pin(CountDealloc(&counters));
+    drop(rx);
+    assert_eq!(1, counters.deallocated.load(Ordering::Relaxed));
+    drop(tx);
+    assert_eq!(2, counters.deallocated.load(Ordering::Relaxed));
+    assert_eq!(2, counters.notified.load(Ordering::Relaxed));
+}
+
+#[test]
+fn will_deallocate_once_under_contention() {
+    let counters = Counters::default();
+    for i in 0..100 {
+        let (tx, rx) = splitrc::new(CountDealloc(&counters));
+        let barrier = Barrier::new(2);
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                barrier.wait();
+                drop(tx);
+            });
+            barrier.wait();
+            drop(rx);
+        });
+        assert_eq!(i + 1, counters.deallocated.load(Ordering::Relaxed));
+    }
+}
+
+#[test]
+fn will_deallocate_skipped_when_moved_out() {
+    let counters = Counters::default();
+    let (tx, rx) = splitrc::new(CountDealloc(&counters));
+    drop(rx);
+    assert!(splitrc::Tx::into_inner(tx).is_some());
+    assert_eq!(1, counters.notified.load(Ordering::Relaxed));
+    assert_eq!(0, counters.deallocated.load(Ordering::Relaxed));
+}
+
+#[derive(Default)]
+struct RecordContext {
+    tx: std::sync::Mutex<Option<splitrc::DropContext>>,
+    rx: std::sync::Mutex<Option<splitrc::DropContext>>,
+}
+
+impl splitrc::Notify for RecordContext {
+    fn last_tx_did_drop_with(self: Pin<&Self>, ctx: splitrc::DropContext) {
+        *self.tx.lock().unwrap() = Some(ctx);
+    }
+    fn last_rx_did_drop_with(self: Pin<&Self>, ctx: splitrc::DropContext) {
+        *self.rx.lock().unwrap() = Some(ctx);
+    }
+}
+
+#[test]
+fn drop_context_reports_surviving_half() {
+    let (tx, rx) = splitrc::new(RecordContext::default());
+    let rx2 = rx.clone();
+    drop(tx);
+    let ctx = rx.tx.lock().unwrap().unwrap();
+    assert_eq!(2, ctx.remaining());
+    assert!(!ctx.is_pinned());
+    drop((rx, rx2));
+
+    let (tx, rx) = splitrc::pin(RecordContext::default());
+    drop(rx);
+    let ctx = tx.rx.lock().unwrap().unwrap();
+    assert_eq!(1, ctx.remaining());
+    assert!(ctx.is_pinned());
+    assert!(tx.tx.lock().unwrap().is_none());
+}
+
+#[test]
+fn drop_context_defaults_to_existing_callbacks() {
+    let (tx, rx) = splitrc::pin(TrackNotify::default());
+    drop(tx);
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn std_types_ignore_notifications() {
+    drop(splitrc::new(()));
+    drop(splitrc::new(7u64));
+    let (tx, rx) = splitrc::new(String::from("hello"));
+    drop(tx);
+    assert_eq!("hello", *rx);
+    let (tx, rx) = splitrc::new(vec![1, 2, 3]);
+    drop(rx);
+    assert_eq!([1, 2, 3], tx[..]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn handles_forward_as_ref() {
+    use std::path::{Path, PathBuf};
+
+    fn is_toml(path: impl AsRef<Path>) -> bool {
+        path.as_ref().extension() == Some("toml".as_ref())
+    }
+
+    let (tx, rx) = splitrc::new(PathBuf::from("Cargo.toml"));
+    assert!(is_toml(&tx));
+    assert!(is_toml(rx));
+    let (tx, rx) = splitrc::new(String::from("hello"));
+    assert_eq!(b"hello", AsRef::<[u8]>::as_ref(&tx));
+    assert_eq!("hello", AsRef::<str>::as_ref(&rx));
+    // The payload itself is still available.
+    assert_eq!("hello", AsRef::<String>::as_ref(&rx));
+    let (tx, _rx) = splitrc::new(vec![1, 2, 3]);
+    assert_eq!([1, 2, 3], AsRef::<[i32]>::as_ref(&tx));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn handles_read_and_write_through_shared_payload() {
+    use std::collections::VecDeque;
+    use std::io::{self, Read, Write};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Pipe(Mutex<VecDeque<u8>>);
+    impl splitrc::Notify for Pipe {}
+
+    impl Read for &Pipe {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().read(buf)
+        }
+    }
+
+    impl Write for &Pipe {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn is_io<T: Read + Write>() {}
+    is_io::<splitrc::Tx<std::fs::File>>();
+    is_io::<splitrc::Rx<std::net::TcpStream>>();
+
+    let (mut tx, mut rx) = splitrc::new(Pipe::default());
+    write!(tx, "hello").unwrap();
+    tx.flush().unwrap();
+    let mut s = String::new();
+    rx.read_to_string(&mut s).unwrap();
+    assert_eq!("hello", s);
+    // Either half may do either.
+    rx.write_all(b"back").unwrap();
+    let mut buf = [0; 4];
+    tx.read_exact(&mut buf).unwrap();
+    assert_eq!(b"back", &buf);
+}
+
+#[test]
+fn box_forwards_notifications() {
+    let (tx, rx) = splitrc::new(Box::new(TrackNotify::default()));
+    drop(tx);
+    assert_eq!((true, false), rx.access());
+
+    let (tx, rx) = splitrc::new(Box::new(MustPin::default()) as Box<dyn splitrc::Notify>);
+    drop((tx, rx));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn mutex_forwards_notifications_when_unlocked() {
+    let (tx, rx) = splitrc::new(std::sync::Mutex::new(TrackNotify::default()));
+    drop(tx);
+    assert_eq!((true, false), rx.lock().unwrap().access());
+
+    let (tx, rx) = splitrc::new(std::sync::RwLock::new(TrackNotify::default()));
+    let guard = tx.write().unwrap();
+    drop(rx);
+    assert_eq!((false, false), guard.access());
+    drop(guard);
+}
+
+#[test]
+fn tuple_forwards_to_every_element() {
+    let (tx, rx) = splitrc::pin((TrackNotify::default(), MustPin::default()));
+    drop(rx);
+    assert_eq!((false, true), tx.0.access());
+    assert!(tx.1.rx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn handles_are_unwind_safe() {
+    fn assert_unwind_safe<T: panic::UnwindSafe + panic::RefUnwindSafe>() {}
+    assert_unwind_safe::<splitrc::Tx<Unit>>();
+    assert_unwind_safe::<splitrc::Rx<Unit>>();
+    assert_unwind_safe::<splitrc::TxWeak<Unit>>();
+    assert_unwind_safe::<splitrc::RxWeak<Unit>>();
+
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let result = panic::catch_unwind(|| {
+        let _rx = rx.clone();
+        panic!("job failed")
+    });
+    assert!(result.is_err());
+    drop(tx);
+    assert!(rx.tx_did_drop.load(Ordering::Acquire));
+}
+
+#[test]
+fn optional_handles_round_trip_through_atomic_ptr() {
+    use std::sync::atomic::AtomicPtr;
+
+    assert_eq!(
+        mem::size_of::<*const Unit>(),
+        mem::size_of::<Option<splitrc::Tx<Unit>>>()
+    );
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let slot = AtomicPtr::new(splitrc::Rx::<TrackNotify>::option_into_raw(None) as *mut _);
+    assert!(unsafe { splitrc::Rx::option_from_raw(slot.load(Ordering::Acquire)) }.is_none());
+    slot.store(
+        splitrc::Rx::option_into_raw(Some(rx)) as *mut TrackNotify,
+        Ordering::Release,
+    );
+    drop(tx);
+    let rx =
+        unsafe { splitrc::Rx::option_from_raw(slot.swap(std::ptr::null_mut(), Ordering::AcqRel)) };
+    assert_eq!((true, false), rx.unwrap().access());
+}
+
+// Leaked allocations are reported by Miri.
+#[cfg_attr(miri, ignore)]
+#[test]
+fn leaked_tx_outlives_rx() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let leaked: &'static TrackNotify = splitrc::Tx::leak(tx);
+    assert_eq!(1, splitrc::Rx::tx_count(&rx));
+    drop(rx);
+    // The rx half still notifies, and the payload outlives it.
+    assert_eq!((false, true), leaked.access());
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn leaking_both_halves_never_notifies() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let a = splitrc::Tx::leak(tx);
+    let b = splitrc::Rx::leak(rx);
+    assert!(std::ptr::eq(a, b));
+    assert_eq!((false, false), a.access());
+}
+
+#[test]
+fn clone_many_counts_every_handle() {
+    let (tx, rx) = splitrc::new(TrackNotify::default());
+    let txs: Vec<_> = splitrc::Tx::clone_many(&tx, 10).collect();
+    let rxs = splitrc::Rx::clone_many(&rx, 3);
+    assert_eq!(3, rxs.len());
+    drop(rxs);
+    assert_eq!(splitrc::Counts { tx: 11, rx: 1 }, splitrc::Tx::counts(&tx));
+    drop(tx);
+    splitrc::Tx::drop_many(txs);
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn unyielded_clones_are_released() {
+    let (tx, rx) = splitrc::new(Unit);
+    let mut many = splitrc::Tx::clone_many(&tx, 10_000);
+    let first = many.next().unwrap();
+    assert_eq!(10_001, splitrc::Rx::tx_count(&rx));
+    drop(many);
+    assert_eq!(2, splitrc::Rx::tx_count(&rx));
+    drop((first, tx, rx));
+}
+
+#[test]
+fn drop_many_releases_runs_across_allocations() {
+    let (tx1, rx1) = splitrc::new(TrackNotify::default());
+    let (tx2, rx2) = splitrc::new(TrackNotify::default());
+    let handles = vec![tx1.clone(), tx1, tx2.clone(), tx2.clone()];
+    splitrc::Tx::drop_many(handles);
+    assert_eq!((true, false), rx1.access());
+    assert_eq!(1, splitrc::Rx::tx_count(&rx2));
+    drop((tx2, rx1, rx2));
+}
+
+#[test]
+fn notification_may_drop_last_handle_of_other_half() {
+    struct SelfOwned {
+        tx: std::sync::Mutex<Option<splitrc::Tx<SelfOwned>>>,
+        in_callback: AtomicBool,
+        dropped: Arc<AtomicBool>,
+    }
+
+    impl splitrc::Notify for SelfOwned {
+        fn last_rx_did_drop(&self) {
+            self.in_callback.store(true, Ordering::Relaxed);
+            drop(self.tx.lock().unwrap().take());
+            // Freeing is deferred until this notification returns.
+            assert!(!self.dropped.load(Ordering::Relaxed));
+            self.in_callback.store(false, Ordering::Relaxed);
+        }
+
+        fn last_tx_did_drop(&self) {
+            panic!("the tx half closed after the rx half");
+        }
+    }
+
+    impl Drop for SelfOwned {
+        fn drop(&mut self) {
+            assert!(!self.in_callback.load(Ordering::Relaxed));
+            self.dropped.store(true, Ordering::Relaxed);
+        }
+    }
+
+    let dropped = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = splitrc::new(SelfOwned {
+        tx: Default::default(),
+        in_callback: AtomicBool::new(false),
+        dropped: dropped.clone(),
+    });
+    *tx.tx.lock().unwrap() = Some(tx.clone());
+    drop(tx);
+    drop(rx);
+    assert!(dropped.load(Ordering::Relaxed));
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "std")]
+
+use std::thread;
+
+#[test]
+fn dropping_last_source_cancels() {
+    let (source, watcher) = splitrc::token::new();
+    let source2 = source.clone();
+    assert!(!watcher.is_cancelled());
+    drop(source);
+    assert!(!watcher.is_cancelled());
+    drop(source2);
+    assert!(watcher.is_cancelled());
+    assert!(watcher.clone().is_cancelled());
+}
+
+#[test]
+fn dropping_watchers_does_not_cancel() {
+    let (source, watcher) = splitrc::token::new();
+    assert!(source.has_watchers());
+    drop(watcher);
+    assert!(!source.has_watchers());
+    drop(source);
+}
+
+#[test]
+fn wait_from_another_thread() {
+    let (source, watcher) = splitrc::token::new();
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let watcher = watcher.clone();
+            thread::spawn(move || watcher.wait())
+        })
+        .collect();
+    drop(source);
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    watcher.wait();
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn cancelled_future() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Wake, Waker};
+
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let (source, watcher) = splitrc::token::new();
+    let mut cancelled = watcher.cancelled();
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    assert!(Pin::new(&mut cancelled).poll(&mut cx).is_pending());
+    drop(source);
+    assert!(Pin::new(&mut cancelled).poll(&mut cx).is_ready());
+}
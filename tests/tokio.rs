@@ -0,0 +1,80 @@
+#![cfg(feature = "tokio")]
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+// tokio's sync primitives do not need a runtime.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark()
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+fn poll_once<F: Future>(future: Pin<&mut F>) -> Poll<F::Output> {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    future.poll(&mut Context::from_waker(&waker))
+}
+
+#[test]
+fn closed_tokio_resolves_when_other_half_drops() {
+    let (tx, rx) = splitrc::new(splitrc::TokioNotify::new(7));
+    let mut closed = Box::pin(splitrc::Tx::closed_tokio(&tx));
+    assert!(poll_once(closed.as_mut()).is_pending());
+    let rx2 = rx.clone();
+    drop(rx);
+    assert!(poll_once(closed.as_mut()).is_pending());
+    let worker = thread::spawn(move || drop(rx2));
+    block_on(closed);
+    worker.join().unwrap();
+    assert!(tx.is_rx_dropped());
+    assert!(!tx.is_tx_dropped());
+    assert_eq!(7, **tx);
+}
+
+#[test]
+fn closed_tokio_resolves_after_payload_drops() {
+    let (tx, rx) = splitrc::new(splitrc::TokioNotify::new(()));
+    let closed = splitrc::Rx::closed_tokio(&rx);
+    drop((rx, tx));
+    block_on(closed);
+}
+
+#[test]
+fn notify_and_watch_observe_drops() {
+    let (tx, rx) = splitrc::new(splitrc::TokioNotify::new(()));
+    let mut state = rx.subscribe();
+    {
+        let notified = rx.notify().notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+        drop(tx);
+        block_on(notified);
+    }
+    assert!(state.has_changed().unwrap());
+    assert_eq!(
+        splitrc::HalvesDropped {
+            tx: true,
+            rx: false
+        },
+        *state.borrow_and_update()
+    );
+    drop(rx);
+    assert!(state.has_changed().is_err());
+}
@@ -0,0 +1,122 @@
+#![cfg(feature = "tracing")]
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Records the message of every event.
+#[derive(Clone, Default)]
+struct Messages(Arc<Mutex<Vec<String>>>);
+
+impl Messages {
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+struct MessageVisitor<'a>(&'a mut String);
+
+impl Visit for MessageVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            *self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+impl Subscriber for Messages {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut message = String::new();
+        event.record(&mut MessageVisitor(&mut message));
+        self.0.lock().unwrap().push(message);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+struct Unit;
+impl splitrc::Notify for Unit {}
+
+#[test]
+fn lifecycle_events() {
+    let messages = Messages::default();
+    tracing::subscriber::with_default(messages.clone(), || {
+        let (tx, rx) = splitrc::new(Unit);
+        let tx2 = tx.clone();
+        drop(tx);
+        drop(tx2);
+        drop(rx);
+    });
+    assert_eq!(
+        vec![
+            "allocate",
+            "clone tx",
+            "drop tx",
+            "drop tx",
+            "last tx dropped",
+            "drop rx",
+            "deallocate",
+        ],
+        messages.take()
+    );
+}
+
+#[test]
+fn into_inner_deallocates() {
+    let messages = Messages::default();
+    tracing::subscriber::with_default(messages.clone(), || {
+        let (tx, rx) = splitrc::new(Unit);
+        drop(rx);
+        assert!(splitrc::Tx::into_inner(tx).is_some());
+    });
+    assert_eq!(
+        Some("deallocate"),
+        messages.take().last().map(String::as_str)
+    );
+}
+
+#[test]
+fn erased_clones_are_reported() {
+    let messages = Messages::default();
+    tracing::subscriber::with_default(messages.clone(), || {
+        let (tx, rx) = splitrc::new(Unit);
+        let tx = splitrc::Tx::erase(tx);
+        let rx = splitrc::Rx::erase(rx);
+        let tx2 = tx.clone();
+        let rx2 = rx.clone();
+        drop((tx, tx2, rx, rx2));
+    });
+    assert_eq!(
+        vec!["allocate", "clone tx", "clone rx"],
+        messages.take()[..3]
+    );
+}
+
+#[test]
+fn scoped_and_unique_events_are_balanced() {
+    let messages = Messages::default();
+    tracing::subscriber::with_default(messages.clone(), || {
+        splitrc::scope(Unit, |tx, rx| drop((tx, rx)));
+        drop(splitrc::new_unique(Unit));
+    });
+    let messages = messages.take();
+    let count = |event| messages.iter().filter(|m| *m == event).count();
+    assert_eq!(2, count("allocate"));
+    assert_eq!(2, count("deallocate"));
+}
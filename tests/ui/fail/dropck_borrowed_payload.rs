@@ -0,0 +1,16 @@
+// Dropping the last handle notifies the payload, which may read its
+// borrows, so they must outlive the handle.
+
+struct Borrowed<'a>(&'a str);
+impl splitrc::Notify for Borrowed<'_> {
+    fn last_rx_did_drop(&self) {
+        println!("{} closed", self.0);
+    }
+}
+
+fn main() {
+    let (tx, _rx);
+    let name = String::from("reader");
+    (tx, _rx) = splitrc::new(Borrowed(&name));
+    drop(tx);
+}
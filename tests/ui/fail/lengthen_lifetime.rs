@@ -0,0 +1,16 @@
+// Covariance only shortens lifetimes.
+
+use splitrc::{Rx, Tx};
+
+struct Borrowed<'a>(&'a str);
+impl splitrc::Notify for Borrowed<'_> {}
+
+fn lengthen_tx<'a>(tx: Tx<Borrowed<'a>>) -> Tx<Borrowed<'static>> {
+    tx
+}
+
+fn lengthen_rx<'a>(rx: Rx<Borrowed<'a>>) -> Rx<Borrowed<'static>> {
+    rx
+}
+
+fn main() {}
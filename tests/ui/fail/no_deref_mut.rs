@@ -0,0 +1,7 @@
+// Handles share the payload, so it cannot be mutated through them.
+
+fn main() {
+    let (tx, rx) = splitrc::new(0u32);
+    *tx += 1;
+    drop(rx);
+}
@@ -0,0 +1,19 @@
+// Handles cross threads only if the payload is Send and Sync.
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+struct NonSend(PhantomData<*const ()>);
+impl splitrc::Notify for NonSend {}
+
+struct NonSync(Cell<u32>);
+impl splitrc::Notify for NonSync {}
+
+fn require_send<T: Send>() {}
+
+fn main() {
+    require_send::<splitrc::Tx<NonSend>>();
+    require_send::<splitrc::RxWeak<NonSend>>();
+    require_send::<splitrc::Rx<NonSync>>();
+    require_send::<splitrc::local::Tx<u32>>();
+}
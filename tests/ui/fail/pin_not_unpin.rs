@@ -0,0 +1,13 @@
+// A pinned payload cannot be moved back out of its pinned handle.
+
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+
+struct MustPin(PhantomPinned);
+impl splitrc::Notify for MustPin {}
+
+fn main() {
+    let (tx, rx) = splitrc::pin(MustPin(PhantomPinned));
+    let tx: splitrc::Tx<MustPin> = Pin::into_inner(tx);
+    drop((tx, rx));
+}
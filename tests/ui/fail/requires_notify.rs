@@ -0,0 +1,8 @@
+// Payloads must implement Notify.
+
+struct Plain;
+
+fn main() {
+    let (tx, rx) = splitrc::new(Plain);
+    drop((tx, rx));
+}
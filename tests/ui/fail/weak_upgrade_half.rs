@@ -0,0 +1,8 @@
+// A weak reference only upgrades to the half it was downgraded from.
+
+fn main() {
+    let (tx, rx) = splitrc::new(0u32);
+    let weak = splitrc::Tx::downgrade(&tx);
+    let rx2: splitrc::Rx<u32> = weak.upgrade().unwrap();
+    drop((tx, rx, rx2));
+}
@@ -0,0 +1,15 @@
+// A borrowed payload compiles when the borrow outlives the handles.
+
+struct Borrowed<'a>(&'a str);
+impl splitrc::Notify for Borrowed<'_> {
+    fn last_rx_did_drop(&self) {
+        println!("{} closed", self.0);
+    }
+}
+
+fn main() {
+    let name = String::from("reader");
+    let (tx, rx) = splitrc::new(Borrowed(&name));
+    drop(tx);
+    drop(rx);
+}
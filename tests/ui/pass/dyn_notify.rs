@@ -0,0 +1,11 @@
+// Notify stays dyn compatible as it grows.
+
+fn notify_rx_closed(payload: &dyn splitrc::Notify) {
+    payload.last_rx_did_drop();
+}
+
+fn main() {
+    let (tx, rx) = splitrc::new(());
+    notify_rx_closed(&*tx);
+    drop((tx, rx));
+}
@@ -0,0 +1,37 @@
+// Handles are covariant in the payload, like Arc and Weak.
+
+use splitrc::{Rx, RxWeak, Tx, TxWeak};
+
+struct Borrowed<'a>(&'a str);
+impl splitrc::Notify for Borrowed<'_> {}
+
+fn shorten_tx<'a>(tx: Tx<Borrowed<'static>>) -> Tx<Borrowed<'a>> {
+    tx
+}
+
+fn shorten_rx<'a>(rx: Rx<Borrowed<'static>>) -> Rx<Borrowed<'a>> {
+    rx
+}
+
+fn shorten_tx_weak<'a>(weak: TxWeak<Borrowed<'static>>) -> TxWeak<Borrowed<'a>> {
+    weak
+}
+
+fn shorten_rx_weak<'a>(weak: RxWeak<Borrowed<'static>>) -> RxWeak<Borrowed<'a>> {
+    weak
+}
+
+fn main() {
+    let (tx, rx) = splitrc::new(Borrowed("static"));
+    let tx_weak = Tx::downgrade(&tx);
+    let rx_weak = Rx::downgrade(&rx);
+    let local = String::from("local");
+    // Handles to a 'static payload may be stored next to handles
+    // with a shorter borrow.
+    let (local_tx, local_rx) = splitrc::new(Borrowed(&local));
+    let tx = [shorten_tx(tx), local_tx];
+    let rx = [shorten_rx(rx), local_rx];
+    let tx_weak = [shorten_tx_weak(tx_weak), Tx::downgrade(&tx[1])];
+    let rx_weak = [shorten_rx_weak(rx_weak), Rx::downgrade(&rx[1])];
+    drop((tx, rx, tx_weak, rx_weak));
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "std")]
+
+use std::thread;
+use std::time::Duration;
+
+mod fixture;
+
+use fixture::Unit;
+
+#[test]
+fn wait_rx_closed_blocks_until_last_rx_drops() {
+    let (tx, rx) = splitrc::new(Unit);
+    let rx2 = rx.clone();
+    let worker = thread::spawn(move || {
+        drop(rx);
+        thread::sleep(Duration::from_millis(10));
+        drop(rx2);
+    });
+    assert!(splitrc::Tx::wait_rx_closed(&tx, None));
+    assert_eq!(0, splitrc::Tx::counts(&tx).rx);
+    worker.join().unwrap();
+}
+
+#[test]
+fn wait_tx_closed_blocks_until_last_tx_drops() {
+    let (tx, rx) = splitrc::new(Unit);
+    let worker = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        drop(tx);
+    });
+    assert!(splitrc::Rx::wait_tx_closed(
+        &rx,
+        Some(Duration::from_secs(60))
+    ));
+    assert_eq!(0, splitrc::Rx::counts(&rx).tx);
+    worker.join().unwrap();
+}
+
+#[test]
+fn wait_tx_closed_returns_if_already_closed() {
+    let (tx, rx) = splitrc::new(Unit);
+    drop(tx);
+    assert!(splitrc::Rx::wait_tx_closed(&rx, Some(Duration::ZERO)));
+}
+
+#[test]
+fn wait_rx_closed_times_out() {
+    let (tx, rx) = splitrc::new(Unit);
+    assert!(!splitrc::Tx::wait_rx_closed(
+        &tx,
+        Some(Duration::from_millis(10))
+    ));
+    assert!(!splitrc::Tx::wait_rx_closed(&tx, Some(Duration::ZERO)));
+    drop(rx);
+    assert!(splitrc::Tx::wait_rx_closed(&tx, Some(Duration::ZERO)));
+}
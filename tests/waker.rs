@@ -0,0 +1,53 @@
+#![cfg(feature = "async")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Wake, Waker};
+
+#[derive(Default)]
+struct CountWaker(AtomicUsize);
+
+impl Wake for CountWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+#[test]
+fn wakes_when_last_tx_drops() {
+    let (tx, rx) = splitrc::new(splitrc::WakerNotify::new(7));
+    let count = Arc::new(CountWaker::default());
+    let waker = Waker::from(count.clone());
+    rx.register_tx_waker(&waker);
+    let tx2 = tx.clone();
+    drop(tx);
+    assert_eq!(0, count.0.load(Ordering::Acquire));
+    assert!(!rx.is_tx_dropped());
+    drop(tx2);
+    assert_eq!(1, count.0.load(Ordering::Acquire));
+    assert!(rx.is_tx_dropped());
+    assert_eq!(7, **rx);
+}
+
+#[test]
+fn register_after_drop_wakes_immediately() {
+    let (tx, rx) = splitrc::new(splitrc::WakerNotify::new(()));
+    drop(rx);
+    let count = Arc::new(CountWaker::default());
+    tx.register_rx_waker(&Waker::from(count.clone()));
+    assert_eq!(1, count.0.load(Ordering::Acquire));
+}
+
+#[test]
+fn poll_rx_dropped() {
+    let (tx, rx) = splitrc::new(splitrc::WakerNotify::new(()));
+    let count = Arc::new(CountWaker::default());
+    let waker = Waker::from(count.clone());
+    let mut cx = Context::from_waker(&waker);
+    assert!(tx.poll_rx_dropped(&mut cx).is_pending());
+    // Polling again with the same waker does not replace it.
+    assert!(tx.poll_rx_dropped(&mut cx).is_pending());
+    drop(rx);
+    assert_eq!(1, count.0.load(Ordering::Acquire));
+    assert!(tx.poll_rx_dropped(&mut cx).is_ready());
+}
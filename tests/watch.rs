@@ -0,0 +1,95 @@
+#![cfg(feature = "std")]
+
+use splitrc::watch;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark()
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+fn poll_once<F: Future>(future: Pin<&mut F>) -> Poll<F::Output> {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    future.poll(&mut Context::from_waker(&waker))
+}
+
+#[test]
+fn changed_resolves_on_send() {
+    let (tx, mut rx) = watch::channel("a");
+    assert!(!rx.has_changed().unwrap());
+    let worker = thread::spawn(move || {
+        tx.send("b").unwrap();
+        tx
+    });
+    block_on(rx.changed()).unwrap();
+    assert_eq!("b", *rx.borrow());
+    assert!(!rx.has_changed().unwrap());
+    drop(worker.join().unwrap());
+}
+
+#[test]
+fn changed_fails_when_sender_drops() {
+    let (tx, mut rx) = watch::channel(0);
+    tx.send_modify(|value| *value += 1);
+    drop(tx);
+    // The unseen value is still reported first.
+    assert!(rx.has_changed().unwrap());
+    block_on(rx.changed()).unwrap();
+    assert_eq!(1, *rx.borrow());
+    assert_eq!(Err(watch::RecvError), rx.has_changed());
+    assert_eq!(Err(watch::RecvError), block_on(rx.changed()));
+}
+
+#[test]
+fn pending_changed_wakes_when_sender_drops() {
+    let (tx, mut rx) = watch::channel(());
+    let mut changed = Box::pin(rx.changed());
+    assert!(poll_once(changed.as_mut()).is_pending());
+    let worker = thread::spawn(move || drop(tx));
+    assert_eq!(Err(watch::RecvError), block_on(changed));
+    worker.join().unwrap();
+}
+
+#[test]
+fn receivers_track_versions_independently() {
+    let (tx, mut rx) = watch::channel(0);
+    let rx2 = rx.clone();
+    assert_eq!(0, tx.send_replace(1));
+    assert_eq!(1, *rx.borrow_and_update());
+    assert!(!rx.has_changed().unwrap());
+    assert!(rx2.has_changed().unwrap());
+    let rx3 = tx.subscribe().unwrap();
+    assert!(!rx3.has_changed().unwrap());
+}
+
+#[test]
+fn send_fails_without_receivers() {
+    let (tx, rx) = watch::channel(0);
+    let mut closed = Box::pin(tx.closed());
+    assert!(poll_once(closed.as_mut()).is_pending());
+    drop(rx);
+    block_on(closed);
+    assert!(tx.is_closed());
+    assert_eq!(Err(watch::SendError(2)), tx.send(2));
+    assert!(tx.subscribe().is_none());
+    assert_eq!(0, *tx.borrow());
+}
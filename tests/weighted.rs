@@ -0,0 +1,97 @@
+use std::sync::atomic::Ordering;
+use std::thread;
+
+mod fixture;
+
+use fixture::TrackNotify;
+use fixture::Unit;
+
+#[test]
+fn new_and_delete() {
+    let (tx, rx) = splitrc::weighted::new(Unit);
+    drop(tx);
+    drop(rx);
+}
+
+#[test]
+fn clone_splits_weight() {
+    let (tx, rx) = splitrc::weighted::new(TrackNotify::default());
+    assert_eq!(1, splitrc::weighted::Tx::weight(&tx));
+    let tx2 = tx.clone();
+    let total = splitrc::weighted::Tx::weight(&tx) + splitrc::weighted::Tx::weight(&tx2);
+    assert_eq!(
+        total,
+        splitrc::Rx::counts(&splitrc::weighted::Rx::to_shared(&rx)).tx
+    );
+    // Splitting again does not touch the shared count.
+    let tx3 = tx2.clone();
+    assert_eq!(
+        total,
+        splitrc::Rx::counts(&splitrc::weighted::Rx::to_shared(&rx)).tx
+    );
+    drop((tx, tx2));
+    assert!(!rx.tx_did_drop.load(Ordering::Acquire));
+    drop(tx3);
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn merge_returns_weight() {
+    let (tx, rx) = splitrc::weighted::new(TrackNotify::default());
+    let clones: Vec<_> = (0..10).map(|_| tx.clone()).collect();
+    let shared = splitrc::weighted::Rx::to_shared(&rx);
+    let total = splitrc::Rx::counts(&shared).tx;
+    for clone in clones {
+        splitrc::weighted::Tx::merge(&tx, clone);
+    }
+    assert_eq!(total, splitrc::weighted::Tx::weight(&tx));
+    assert_eq!(total, splitrc::Rx::counts(&shared).tx);
+    drop(tx);
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+#[should_panic(expected = "same allocation")]
+fn merge_rejects_other_allocations() {
+    let (_tx1, rx1) = splitrc::weighted::new(Unit);
+    let (_tx2, rx2) = splitrc::weighted::new(Unit);
+    splitrc::weighted::Rx::merge(&rx1, rx2);
+}
+
+#[test]
+fn clones_drop_on_other_threads() {
+    let (tx, rx) = splitrc::weighted::new(TrackNotify::default());
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            let rx = rx.clone();
+            thread::spawn(move || {
+                let clones: Vec<_> = (0..100).map(|_| rx.clone()).collect();
+                drop(clones);
+            })
+        })
+        .collect();
+    drop(rx);
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!((false, true), tx.access());
+}
+
+#[test]
+fn shared_handle_keeps_half_alive() {
+    let (tx, rx) = splitrc::weighted::new(TrackNotify::default());
+    let shared = splitrc::weighted::Tx::to_shared(&tx);
+    drop(tx);
+    assert!(!rx.tx_did_drop.load(Ordering::Acquire));
+    let tx = splitrc::weighted::Tx::from(shared);
+    assert!(splitrc::weighted::Tx::ptr_eq(&tx, &tx.clone()));
+    drop(tx);
+    assert_eq!((true, false), rx.access());
+}
+
+#[test]
+fn formatting() {
+    let (tx, rx) = splitrc::weighted::new(Unit);
+    assert_eq!("Unit", format!("{:?}", tx));
+    assert_eq!("Unit", format!("{}", rx));
+}